@@ -2,8 +2,11 @@ use gpui::prelude::*;
 use gpui::*;
 use gpui_component::badge::Badge;
 use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::checkbox::Checkbox;
 use gpui_component::divider::Divider;
 use gpui_component::input::{Input, InputState};
+use gpui_component::menu::{DropdownMenu, PopupMenuItem};
+use gpui_component::popover::Popover;
 use gpui_component::resizable::{h_resizable, resizable_panel, v_resizable};
 use gpui_component::scroll::{ScrollableElement, Scrollbar};
 use gpui_component::spinner::Spinner;
@@ -12,22 +15,44 @@ use gpui_component::tag::Tag;
 use gpui_component::theme::{ActiveTheme, Theme, ThemeMode};
 use gpui_component::tooltip::Tooltip;
 use gpui_component::*;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
+use crate::assertions::{self, Assertion, AssertionResult};
+use crate::client::ClientManager;
+use crate::codegen::{self, CodeTarget};
+use crate::components::auth_editor::AuthEditor;
 use crate::components::git_panel::GitPanel;
+use crate::components::proxy_editor::ProxyEditor;
+use crate::components::settings_editor::SettingsEditor;
+use crate::components::tls_editor::TlsEditor;
 use crate::config::AppConfig;
+use crate::cookies::CookieJar;
+use crate::curl;
+use crate::environment::{self, EnvironmentStore};
 use crate::fs;
 use crate::git::GitService;
-use crate::request;
-use crate::types::{FileEntry, HttpMethod, KeyValuePair, RequestTab, SavedRequest, SidebarTab};
+use crate::history::{self, DiffLine, HistoryEntry};
+use crate::openapi;
+use crate::postman;
+use crate::request::{self, BodyProgress, PreparedRequest, RequestContext};
+use crate::settings::FolderSettings;
+use crate::timing::Timings;
+use crate::types::{
+    AuthKind, BodyMode, FileEntry, HttpMethod, KeyValuePair, RequestFormat, RequestTab,
+    ResponseTab, SavedKeyValue, SavedRequest, SettingsScope, SidebarTab, TreeNode,
+};
+use crate::watcher::FolderWatcher;
 
 // Define keyboard actions
 actions!(
     api_client,
     [
         SendRequest,
+        CancelRequest,
         SaveRequest,
         NewRequest,
+        NewTab,
         OpenFolder,
         ToggleSidebar,
         ToggleTheme,
@@ -35,38 +60,84 @@ actions!(
     ]
 );
 
-pub struct App {
+/// Which key-value table a row belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KvList {
+    Params,
+    Headers,
+    Form,
+}
+
+impl KvList {
+    fn id_prefix(&self) -> &'static str {
+        match self {
+            KvList::Params => "param",
+            KvList::Headers => "header",
+            KvList::Form => "form",
+        }
+    }
+}
+
+/// One open request in the editor, with its own inputs and response
+struct EditorTab {
+    id: usize,
+    /// Request file the tab edits, `None` until it is saved
+    path: Option<PathBuf>,
+    /// Contents of `path` as the app last loaded or saved it
+    file_content: Option<String>,
+    /// Editor state as of the last load or save, to tell whether there are unsaved changes
+    clean_state: String,
+    /// Pinned tabs stay at the front of the tab strip and can't be closed with a click
+    pinned: bool,
     url_input: Entity<InputState>,
     name_input: Entity<InputState>,
+    description_input: Entity<InputState>,
     body_input: Entity<InputState>,
+    body_mode: BodyMode,
     params: Vec<KeyValuePair>,
     headers: Vec<KeyValuePair>,
+    form_fields: Vec<KeyValuePair>,
+    assertions: Vec<Assertion>,
+    assertion_results: Vec<AssertionResult>,
+    auth_editor: Entity<AuthEditor>,
+    /// Send the folder's cookies and keep the ones the response sets
+    use_cookie_jar: bool,
+    settings_editor: Entity<SettingsEditor>,
     response_body: String,
     response_is_large: bool,
     scroll_handle: ScrollHandle,
     method: HttpMethod,
     active_tab: RequestTab,
     is_loading: bool,
+    /// The request being sent; dropping it aborts the request and its connection
+    request_task: Option<Task<()>>,
+    /// When the request being sent went out
+    request_started: Option<std::time::Instant>,
     response_status: Option<(u16, String)>,
+    /// The request was cancelled before a response arrived
+    response_cancelled: bool,
+    /// Bytes of the body received so far, while it is being read
+    response_progress: Option<u64>,
+    /// The body passed the maximum download size and was cut off
+    response_truncated: bool,
+    /// File the body was written to instead of being shown, and its size
+    response_file: Option<(PathBuf, u64)>,
+    response_headers: Vec<(String, String)>,
+    response_version: Option<String>,
+    response_remote_addr: Option<std::net::SocketAddr>,
+    /// The response came over a connection whose certificate wasn't checked
+    response_unverified_tls: bool,
+    response_tab: ResponseTab,
     response_time: Option<u128>,
-    // Sidebar state
-    sidebar_visible: bool,
-    current_folder: Option<PathBuf>,
-    saved_requests: Vec<FileEntry>,
-    selected_request: Option<usize>,
-    // Rename state
-    rename_input: Entity<InputState>,
-    renaming_index: Option<usize>,
-    // Git state
-    git_service: Option<std::rc::Rc<GitService>>,
-    git_panel: Entity<GitPanel>,
-    sidebar_tab: SidebarTab,
-    current_branch: Option<String>,
-    _subscription: Subscription,
+    /// Phases and sizes behind `response_time`, shown from the timing badge
+    response_timings: Option<Timings>,
+    /// Title and lines of the diff shown in place of the response body
+    history_diff: Option<(String, Vec<DiffLine>)>,
 }
 
-impl App {
-    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+impl EditorTab {
+    /// A tab with an unsaved example request
+    fn new(id: usize, window: &mut Window, cx: &mut Context<App>) -> Self {
         let url_input = cx.new(|cx| {
             let mut state = InputState::new(window, cx);
             state.set_placeholder("Enter request URL...", window, cx);
@@ -81,9 +152,9 @@ impl App {
             state
         });
 
-        let rename_input = cx.new(|cx| {
+        let description_input = cx.new(|cx| {
             let mut state = InputState::new(window, cx);
-            state.set_placeholder("New Name", window, cx);
+            state.set_placeholder("Description", window, cx);
             state
         });
 
@@ -93,45 +164,373 @@ impl App {
             state
         });
 
+        let auth_editor = cx.new(|cx| AuthEditor::new(window, cx));
+        let settings_editor = cx.new(|cx| SettingsEditor::new(false, window, cx));
+
         // Create initial empty param rows
-        let params = vec![Self::create_kv_pair(window, cx, "", "")];
+        let params = vec![App::create_kv_pair(window, cx, "", "")];
+        let form_fields = vec![App::create_kv_pair(window, cx, "", "")];
 
         // Create initial header rows
         let headers = vec![
-            Self::create_kv_pair(window, cx, "Content-Type", "application/json"),
-            Self::create_kv_pair(window, cx, "", ""),
+            App::create_kv_pair(window, cx, "Content-Type", "application/json"),
+            App::create_kv_pair(window, cx, "", ""),
         ];
 
-        // Load config
-        let config = AppConfig::load();
-        let current_folder = config.last_opened_folder;
-        let saved_requests = if let Some(folder) = &current_folder {
-            fs::scan_folder(folder)
-        } else {
-            Vec::new()
-        };
-
-        let mut app = Self {
+        let mut tab = Self {
+            id,
+            path: None,
+            file_content: None,
+            clean_state: String::new(),
+            pinned: false,
             url_input,
             name_input,
+            description_input,
             body_input,
+            body_mode: BodyMode::Json,
             params,
             headers,
+            form_fields,
+            assertions: Vec::new(),
+            assertion_results: Vec::new(),
+            auth_editor,
+            use_cookie_jar: true,
+            settings_editor,
             response_body: String::new(),
             response_is_large: false,
             scroll_handle: ScrollHandle::new(),
             method: HttpMethod::Get,
             active_tab: RequestTab::Params,
             is_loading: false,
+            request_task: None,
+            request_started: None,
             response_status: None,
+            response_cancelled: false,
+            response_progress: None,
+            response_truncated: false,
+            response_file: None,
+            response_headers: Vec::new(),
+            response_version: None,
+            response_remote_addr: None,
+            response_unverified_tls: false,
+            response_tab: ResponseTab::Body,
             response_time: None,
+            response_timings: None,
+            history_diff: None,
+        };
+        tab.mark_clean(cx);
+        tab
+    }
+
+    /// Build a saved request from the editor state
+    fn request(&self, cx: &gpui::App) -> SavedRequest {
+        let mut request = SavedRequest::new(
+            self.name_input.read(cx).value().to_string(),
+            &self.method,
+            self.url_input.read(cx).value().to_string(),
+        );
+        request.description = self.description_input.read(cx).value().to_string();
+        request.params = App::read_kv_list(&self.params, cx);
+        request.headers = App::read_kv_list(&self.headers, cx);
+        request.body_mode = self.body_mode;
+        request.form = App::read_kv_list(&self.form_fields, cx);
+        request.body = self.body_input.read(cx).value().to_string();
+        request.assertions = self.assertions.clone();
+        request.auth = self.auth_editor.read(cx).auth(cx);
+        request.skip_cookie_jar = !self.use_cookie_jar;
+        request.settings = self.settings_editor.read(cx).settings(cx);
+        request
+    }
+
+    fn editor_state(&self, cx: &gpui::App) -> String {
+        serde_json::to_string(&self.request(cx)).unwrap_or_default()
+    }
+
+    /// Treat the current editor state as saved
+    fn mark_clean(&mut self, cx: &gpui::App) {
+        self.clean_state = self.editor_state(cx);
+    }
+
+    fn is_dirty(&self, cx: &gpui::App) -> bool {
+        self.editor_state(cx) != self.clean_state
+    }
+
+    /// Note what the tab's file holds, so later changes on disk can be told apart from
+    /// the app's own writes
+    fn remember_file_content(&mut self) {
+        self.file_content = self
+            .path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok());
+    }
+
+    fn kv_list_mut(&mut self, list: KvList) -> &mut Vec<KeyValuePair> {
+        match list {
+            KvList::Params => &mut self.params,
+            KvList::Headers => &mut self.headers,
+            KvList::Form => &mut self.form_fields,
+        }
+    }
+
+    /// Replace a key-value table with saved entries, keeping an empty row for new input
+    fn set_kv_list(
+        &mut self,
+        list: KvList,
+        entries: &[SavedKeyValue],
+        window: &mut Window,
+        cx: &mut Context<App>,
+    ) {
+        let mut pairs: Vec<KeyValuePair> = entries
+            .iter()
+            .map(|entry| {
+                let mut pair = App::create_kv_pair(window, cx, &entry.key, &entry.value);
+                pair.enabled = entry.enabled;
+                pair
+            })
+            .collect();
+        pairs.push(App::create_kv_pair(window, cx, "", ""));
+        *self.kv_list_mut(list) = pairs;
+    }
+
+    /// Fill the editor with a saved request
+    fn apply_request(
+        &mut self,
+        request: &SavedRequest,
+        window: &mut Window,
+        cx: &mut Context<App>,
+    ) {
+        self.name_input.update(cx, |state, cx| {
+            state.set_value(&request.name, window, cx);
+        });
+        self.description_input.update(cx, |state, cx| {
+            state.set_value(&request.description, window, cx);
+        });
+        self.method = request.http_method();
+        self.url_input.update(cx, |state, cx| {
+            state.set_value(&request.url, window, cx);
+        });
+        self.body_mode = request.body_mode;
+        self.body_input.update(cx, |state, cx| {
+            state.set_value(&request.body, window, cx);
+        });
+
+        self.set_kv_list(KvList::Params, &request.params, window, cx);
+        self.set_kv_list(KvList::Headers, &request.headers, window, cx);
+        self.set_kv_list(KvList::Form, &request.form, window, cx);
+        self.assertions = request.assertions.clone();
+        self.assertion_results.clear();
+        self.auth_editor.update(cx, |editor, cx| {
+            editor.set_auth(&request.auth, window, cx);
+        });
+        self.use_cookie_jar = !request.skip_cookie_jar;
+        self.settings_editor.update(cx, |editor, cx| {
+            editor.set_settings(&request.settings, window, cx);
+        });
+    }
+
+    /// Load a request file into the tab and treat it as saved
+    fn load_file(&mut self, path: &Path, window: &mut Window, cx: &mut Context<App>) -> bool {
+        let Some(request) = fs::load_request(path) else {
+            return false;
+        };
+        self.apply_request(&request, window, cx);
+        self.path = Some(path.to_path_buf());
+        self.remember_file_content();
+        self.mark_clean(cx);
+        true
+    }
+
+    /// Clear the response before a request is sent
+    fn start_loading(&mut self) {
+        self.is_loading = true;
+        self.request_started = Some(std::time::Instant::now());
+        self.response_status = None;
+        self.response_cancelled = false;
+        self.response_progress = None;
+        self.response_truncated = false;
+        self.response_file = None;
+        self.response_headers.clear();
+        self.response_version = None;
+        self.response_remote_addr = None;
+        self.response_unverified_tls = false;
+        self.assertion_results.clear();
+        self.response_body.clear();
+        self.response_is_large = false;
+        self.response_time = None;
+        self.response_timings = None;
+        self.history_diff = None;
+    }
+
+    /// Display a response body, pretty-printing JSON when it's small enough
+    fn show_response_body(&mut self, body: String) {
+        self.response_is_large = body.len() > MAX_RESPONSE_DISPLAY_BYTES;
+        self.response_body = if self.response_is_large {
+            body
+        } else {
+            pretty_json(body)
+        };
+    }
+
+    /// Show the body received so far; large bodies only update the byte count
+    fn show_progress(&mut self, progress: BodyProgress) {
+        self.response_progress = Some(progress.received);
+        self.response_is_large = progress.body.len() > MAX_RESPONSE_DISPLAY_BYTES;
+        if !self.response_is_large {
+            self.response_body = progress.text();
+        }
+    }
+
+    /// Abort the request being sent, keeping how long it had been running
+    fn cancel(&mut self) {
+        self.request_task = None;
+        self.is_loading = false;
+        self.response_progress = None;
+        let elapsed = self
+            .request_started
+            .map(|started| started.elapsed().as_millis())
+            .unwrap_or(0);
+        self.response_time = Some(elapsed);
+        self.response_status = Some((0, "Cancelled".to_string()));
+        self.response_cancelled = true;
+        self.response_body = format!("Request cancelled after {} ms", elapsed);
+        self.response_is_large = false;
+    }
+
+    fn show_error(&mut self, error: &str) {
+        self.response_status = Some((0, "Error".to_string()));
+        self.response_body = format!("Error: {}", error);
+        self.response_is_large = false;
+    }
+
+    /// A new tab nobody has typed into or sent yet, which can be reused
+    fn is_pristine(&self, cx: &gpui::App) -> bool {
+        self.path.is_none()
+            && !self.is_loading
+            && self.response_status.is_none()
+            && !self.is_dirty(cx)
+    }
+
+    /// Label shown in the tab strip
+    fn title(&self, cx: &gpui::App) -> String {
+        let name = self.name_input.read(cx).value().to_string();
+        if name.is_empty() {
+            "Untitled".to_string()
+        } else {
+            name
+        }
+    }
+}
+
+pub struct App {
+    /// Open editor tabs; there is always at least one
+    tabs: Vec<EditorTab>,
+    active_editor: usize,
+    next_tab_id: usize,
+    // Sidebar state
+    sidebar_visible: bool,
+    current_folder: Option<PathBuf>,
+    saved_requests: Vec<FileEntry>,
+    request_tree: Vec<TreeNode>,
+    collapsed_folders: HashSet<PathBuf>,
+    /// Watches the open folder for changes made outside the app
+    folder_watch: Option<Task<()>>,
+    reload_prompt_open: bool,
+    environments: EnvironmentStore,
+    folder_settings: FolderSettings,
+    folder_defaults_editor: Entity<SettingsEditor>,
+    settings_scope: SettingsScope,
+    config: AppConfig,
+    proxy_editor: Entity<ProxyEditor>,
+    /// Environment whose proxy is being edited, `None` for the proxy of all requests
+    proxy_target: Option<String>,
+    tls_editor: Entity<TlsEditor>,
+    /// HTTP clients shared by every request, keeping connections alive between sends
+    clients: ClientManager,
+    // History state
+    history: Vec<HistoryEntry>,
+    // Rename state
+    rename_input: Entity<InputState>,
+    /// Request file or folder being renamed
+    renaming: Option<PathBuf>,
+    // Cookie state
+    cookie_jar: CookieJar,
+    cookie_input: Entity<InputState>,
+    /// Index of the cookie whose value is being edited
+    editing_cookie: Option<usize>,
+    // Git state
+    git_service: Option<std::rc::Rc<GitService>>,
+    git_panel: Entity<GitPanel>,
+    sidebar_tab: SidebarTab,
+    current_branch: Option<String>,
+    _subscription: Subscription,
+}
+
+impl App {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let rename_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_placeholder("New Name", window, cx);
+            state
+        });
+        let cookie_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_placeholder("Value", window, cx);
+            state
+        });
+
+        // Load config
+        let config = AppConfig::load();
+        let current_folder = config.last_opened_folder.clone();
+        let request_tree = current_folder
+            .as_deref()
+            .map(fs::scan_tree)
+            .unwrap_or_default();
+        let saved_requests = fs::flatten_tree(&request_tree);
+        let environments = current_folder
+            .as_deref()
+            .map(EnvironmentStore::load)
+            .unwrap_or_default();
+        let folder_settings = current_folder
+            .as_deref()
+            .map(FolderSettings::load)
+            .unwrap_or_default();
+        let history = current_folder
+            .as_deref()
+            .map(history::load)
+            .unwrap_or_default();
+        let cookie_jar = CookieJar::load(&RequestContext {
+            folder: current_folder.clone(),
+            environment: environments.active.clone(),
+            ..Default::default()
+        });
+
+        let mut app = Self {
+            tabs: vec![EditorTab::new(0, window, cx)],
+            active_editor: 0,
+            next_tab_id: 1,
             // Sidebar state
             sidebar_visible: true,
             current_folder,
             saved_requests,
-            selected_request: None,
+            request_tree,
+            collapsed_folders: HashSet::new(),
+            folder_watch: None,
+            reload_prompt_open: false,
+            environments,
+            folder_settings,
+            folder_defaults_editor: cx.new(|cx| SettingsEditor::new(true, window, cx)),
+            settings_scope: SettingsScope::Request,
+            config,
+            proxy_editor: cx.new(|cx| ProxyEditor::new(window, cx)),
+            proxy_target: None,
+            tls_editor: cx.new(|cx| TlsEditor::new(window, cx)),
+            clients: ClientManager::default(),
+            history,
             rename_input,
-            renaming_index: None,
+            renaming: None,
+            cookie_jar,
+            cookie_input,
+            editing_cookie: None,
             git_service: None,
             git_panel: cx.new(|cx| GitPanel::new(window, cx)),
             sidebar_tab: SidebarTab::Files,
@@ -142,9 +541,129 @@ impl App {
         };
 
         app.init_git(cx);
+        app.watch_folder(window, cx);
         app
     }
 
+    /// The tab shown in the editor
+    fn tab(&self) -> &EditorTab {
+        &self.tabs[self.active_editor]
+    }
+
+    fn tab_mut(&mut self) -> &mut EditorTab {
+        &mut self.tabs[self.active_editor]
+    }
+
+    /// Open an empty request in a new tab next to the current one
+    fn new_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let tab = EditorTab::new(self.next_tab_id, window, cx);
+        self.next_tab_id += 1;
+        // New tabs never go in front of pinned ones
+        let pinned = self.tabs.iter().filter(|tab| tab.pinned).count();
+        let index = (self.active_editor + 1).max(pinned).min(self.tabs.len());
+        self.tabs.insert(index, tab);
+        self.active_editor = index;
+        cx.notify();
+    }
+
+    /// Make room for a request to be opened, reusing the current tab while it is empty
+    fn reuse_or_open_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.tab().is_pristine(cx) {
+            self.new_tab(window, cx);
+        }
+    }
+
+    fn tab_index(&self, id: usize) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.id == id)
+    }
+
+    /// Close a tab, asking first whether to save unsaved changes
+    fn close_tab(&mut self, id: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.iter().find(|tab| tab.id == id) else {
+            return;
+        };
+        if !tab.is_dirty(cx) {
+            self.remove_tab(id, window, cx);
+            return;
+        }
+
+        let dialog = rfd::AsyncMessageDialog::new()
+            .set_level(rfd::MessageLevel::Warning)
+            .set_title("Unsaved Changes")
+            .set_description(format!(
+                "Save the changes to \"{}\" before closing?",
+                tab.title(cx)
+            ))
+            .set_buttons(rfd::MessageButtons::YesNoCancel);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = dialog.show().await;
+            let _ = this.update_in(cx, |app, window, cx| {
+                let Some(index) = app.tab_index(id) else {
+                    return;
+                };
+                match result {
+                    rfd::MessageDialogResult::Yes => {
+                        app.active_editor = index;
+                        app.save_request(window, cx);
+                        // Saving fails without an open folder; keep the tab then
+                        if !app.tab().is_dirty(cx) {
+                            app.remove_tab(id, window, cx);
+                        }
+                    }
+                    rfd::MessageDialogResult::No => app.remove_tab(id, window, cx),
+                    _ => {}
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn remove_tab(&mut self, id: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self.tab_index(id) else {
+            return;
+        };
+        self.tabs.remove(index);
+        if self.tabs.is_empty() {
+            self.active_editor = 0;
+            self.new_tab(window, cx);
+            return;
+        }
+        if index < self.active_editor || self.active_editor >= self.tabs.len() {
+            self.active_editor -= 1;
+        }
+        cx.notify();
+    }
+
+    fn toggle_pin(&mut self, id: usize, cx: &mut Context<Self>) {
+        if let Some(index) = self.tab_index(id) {
+            self.tabs[index].pinned = !self.tabs[index].pinned;
+            self.sort_pinned_first();
+            cx.notify();
+        }
+    }
+
+    /// Move a tab to where the tab `before` is, e.g. when dropping it there
+    fn move_tab(&mut self, id: usize, before: usize, cx: &mut Context<Self>) {
+        let (Some(from), Some(to)) = (self.tab_index(id), self.tab_index(before)) else {
+            return;
+        };
+        if from == to {
+            return;
+        }
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        self.sort_pinned_first();
+        cx.notify();
+    }
+
+    /// Keep pinned tabs in front, preserving the order within both groups
+    fn sort_pinned_first(&mut self) {
+        let active = self.tab().id;
+        self.tabs.sort_by_key(|tab| !tab.pinned);
+        self.active_editor = self.tab_index(active).unwrap_or(0);
+    }
+
     fn init_git(&mut self, cx: &mut Context<Self>) {
         if let Some(folder) = &self.current_folder {
             if let Ok(service) = GitService::new(folder) {
@@ -204,129 +723,442 @@ impl App {
 
     fn add_param(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let pair = Self::create_kv_pair(window, cx, "", "");
-        self.params.push(pair);
+        self.tab_mut().params.push(pair);
         cx.notify();
     }
 
     fn add_header(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let pair = Self::create_kv_pair(window, cx, "", "");
-        self.headers.push(pair);
+        self.tab_mut().headers.push(pair);
         cx.notify();
     }
 
-    fn build_url_with_params(&self, cx: &Context<Self>) -> String {
-        let base_url = self.url_input.read(cx).value().to_string();
+    fn add_form_field(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let pair = Self::create_kv_pair(window, cx, "", "");
+        self.tab_mut().form_fields.push(pair);
+        cx.notify();
+    }
 
-        let params: Vec<(String, String)> = self
-            .params
+    /// Snapshot of a key-value table, skipping rows without a key
+    fn read_kv_list(pairs: &[KeyValuePair], cx: &gpui::App) -> Vec<SavedKeyValue> {
+        pairs
             .iter()
-            .filter(|p| p.enabled)
-            .map(|p| {
-                (
-                    p.key.read(cx).value().to_string(),
-                    p.value.read(cx).value().to_string(),
-                )
+            .map(|kv| SavedKeyValue {
+                key: kv.key.read(cx).value().to_string(),
+                value: kv.value.read(cx).value().to_string(),
+                enabled: kv.enabled,
             })
-            .filter(|(k, _)| !k.is_empty())
-            .collect();
+            .filter(|kv| !kv.key.is_empty())
+            .collect()
+    }
+
+    /// Build a saved request from the editor state
+    fn current_request(&self, cx: &Context<Self>) -> SavedRequest {
+        self.tab().request(cx)
+    }
+
+    /// Variables of the active environment
+    fn variables(&self) -> HashMap<String, String> {
+        self.environments.variables()
+    }
 
-        if params.is_empty() {
-            return base_url;
+    /// Folder and environment requests are currently sent from
+    fn request_context(&self) -> RequestContext {
+        RequestContext {
+            folder: self.current_folder.clone(),
+            environment: self.environments.active.clone(),
+            clients: self.clients.clone(),
+            defaults: self.folder_settings.request_defaults.clone(),
+            proxy: self.config.proxy_for(self.environments.active.as_deref()),
+            tls: self.config.tls.clone(),
+            store_dir: None,
         }
+    }
 
-        let query = params
-            .iter()
-            .map(|(k, v)| format!("{}={}", urlencoding(k), urlencoding(v)))
-            .collect::<Vec<_>>()
-            .join("&");
+    /// Close pooled connections so the next request measures a cold start
+    fn reset_connections(&mut self, cx: &mut Context<Self>) {
+        self.clients.reset();
+        cx.notify();
+    }
 
-        if base_url.contains('?') {
-            format!("{}&{}", base_url, query)
+    /// `{{placeholders}}` used by the current request that the active environment cannot resolve
+    fn unresolved_variables(&self, cx: &Context<Self>) -> Vec<String> {
+        let vars = self.variables();
+        let request = self.current_request(cx);
+        let mut texts = vec![request.url];
+        if !request.body_mode.is_form() && request.body_mode != BodyMode::None {
+            texts.push(request.body);
+        }
+        let form = if request.body_mode.is_form() {
+            request.form
         } else {
-            format!("{}?{}", base_url, query)
+            Vec::new()
+        };
+        for kv in request
+            .params
+            .into_iter()
+            .chain(request.headers)
+            .chain(form)
+        {
+            if kv.enabled {
+                texts.push(kv.key);
+                texts.push(kv.value);
+            }
+        }
+
+        let mut names: Vec<String> = Vec::new();
+        for text in texts {
+            for name in environment::unresolved_variables(&text, &vars) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
         }
+        names
     }
 
-    fn get_headers(&self, cx: &Context<Self>) -> Vec<(String, String)> {
-        self.headers
-            .iter()
-            .filter(|h| h.enabled)
-            .map(|h| {
-                (
-                    h.key.read(cx).value().to_string(),
-                    h.value.read(cx).value().to_string(),
-                )
-            })
-            .filter(|(k, _)| !k.is_empty())
-            .collect()
+    /// Switch the active environment and persist the choice
+    fn select_environment(&mut self, name: Option<String>, cx: &mut Context<Self>) {
+        self.environments.active = name;
+        if let Some(folder) = &self.current_folder {
+            self.environments.save(folder);
+        }
+        self.reload_cookies();
+        cx.notify();
+    }
+
+    /// Open the environments file in the system editor, creating it if needed
+    fn edit_environments(&mut self, cx: &mut Context<Self>) {
+        if let Some(folder) = &self.current_folder {
+            let path = EnvironmentStore::ensure_exists(folder);
+            self.environments = EnvironmentStore::load(folder);
+            cx.open_with_system(&path);
+            cx.notify();
+        }
     }
 
     fn send_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         // Auto-save request
         self.save_request(window, cx);
 
-        let url = self.build_url_with_params(cx);
-        let body = self.body_input.read(cx).value().to_string();
-        let headers = self.get_headers(cx);
-        let method = self.method.clone();
+        let saved = self.current_request(cx);
+        let tab_id = self.tab().id;
+        self.dispatch_request(saved, None, tab_id, window, cx);
+    }
+
+    /// Send the request with its response body streamed to a file picked first
+    fn send_and_download(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.save_request(window, cx);
+        let saved = self.current_request(cx);
+        let file_name = reqwest::Url::parse(&saved.url)
+            .ok()
+            .and_then(|url| {
+                let name = url.path_segments()?.next_back()?.to_string();
+                (!name.is_empty()).then_some(name)
+            })
+            .unwrap_or_else(|| "response".to_string());
+        // Another tab may be shown by the time a file is picked
+        let tab_id = self.tab().id;
+
+        cx.spawn_in(window, async move |this, cx| {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title("Download Response")
+                .set_file_name(file_name)
+                .save_file()
+                .await;
+
+            if let Some(file) = file {
+                let path = file.path().to_path_buf();
+                let _ = cx.update(|window, cx| {
+                    this.update(cx, |app, cx| {
+                        app.dispatch_request(saved, Some(path), tab_id, window, cx);
+                    })
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Execute a request from the tab `tab_id`, show its response there and record it
+    /// in the history
+    fn dispatch_request(
+        &mut self,
+        saved: SavedRequest,
+        download_to: Option<PathBuf>,
+        tab_id: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut prepared = PreparedRequest::from_saved(&saved, &self.variables());
+        prepared.download_to = download_to;
+        let context = self.request_context();
 
-        if url.is_empty() {
+        if prepared.url.is_empty() {
             return;
         }
 
-        self.is_loading = true;
-        self.response_status = None;
-        self.response_body.clear();
-        self.response_is_large = false;
-        self.response_time = None;
+        // The response goes to the tab that sent the request, even if another is shown by then
+        let Some(index) = self.tab_index(tab_id) else {
+            return;
+        };
+        let file = self.request_file(&self.tabs[index]);
+        let mut entry = HistoryEntry::new(file, saved, prepared.url.clone());
+        self.tabs[index].start_loading();
         cx.notify();
 
-        cx.spawn_in(window, async move |this, cx| {
+        let task = cx.spawn_in(window, async move |this, cx| {
             let start = std::time::Instant::now();
-            let result = request::execute_request(&url, &method, &body, &headers).await;
+            let progress_this = this.clone();
+            let progress_cx = cx.clone();
+            let progress = move |progress: BodyProgress| {
+                let _ = progress_this.update(&mut progress_cx.clone(), |app, cx| {
+                    if let Some(tab) = app.tabs.iter_mut().find(|tab| tab.id == tab_id) {
+                        tab.show_progress(progress);
+                        cx.notify();
+                    }
+                });
+            };
+            let result = request::execute_request(&prepared, &context, Some(&progress)).await;
             let elapsed = start.elapsed().as_millis();
 
             cx.update(|_window, cx| {
                 this.update(cx, |app, cx| {
-                    app.is_loading = false;
-                    app.response_time = Some(elapsed);
+                    entry.duration_ms = elapsed as u64;
+                    let mut tab = app.tabs.iter_mut().find(|tab| tab.id == tab_id);
+                    if let Some(tab) = tab.as_deref_mut() {
+                        tab.is_loading = false;
+                        tab.response_progress = None;
+                        tab.response_time = Some(elapsed);
+                    }
                     match result {
-                        Ok((status, body)) => {
-                            let status_text = if status >= 200 && status < 300 {
-                                "OK"
-                            } else if status >= 400 && status < 500 {
-                                "Client Error"
-                            } else if status >= 500 {
-                                "Server Error"
-                            } else {
-                                "Response"
-                            };
-                            app.response_status = Some((status, status_text.to_string()));
-
-                            app.response_is_large = body.len() > MAX_RESPONSE_DISPLAY_BYTES;
+                        Ok(response) => {
+                            let status = response.status;
+                            let body = response.text();
+                            entry.status = Some(status);
+                            entry.reason = response.reason.clone();
+                            entry.headers = response.headers.clone();
+                            entry.timings = Some(response.timings.clone());
+                            entry.set_body(&body);
 
-                            // Try to format JSON response when it's safe to display.
-                            app.response_body = if app.response_is_large {
-                                body
-                            } else if let Ok(json) =
-                                serde_json::from_str::<serde_json::Value>(&body)
-                            {
-                                serde_json::to_string_pretty(&json).unwrap_or(body)
-                            } else {
-                                body
-                            };
+                            if let Some(tab) = tab {
+                                tab.response_status =
+                                    Some((status, status_text(status, &response.reason)));
+                                tab.response_version = Some(response.version.clone());
+                                tab.response_remote_addr = response.remote_addr;
+                                tab.response_unverified_tls = response.unverified_tls;
+                                tab.response_timings = Some(response.timings.clone());
+                                tab.response_truncated = response.truncated;
+                                tab.response_file = response
+                                    .body_file
+                                    .clone()
+                                    .map(|path| (path, response.body_size));
+                                tab.assertion_results = assertions::evaluate(
+                                    &entry.request.assertions,
+                                    &response,
+                                    elapsed,
+                                );
+                                tab.response_headers = response.headers;
+                                tab.show_response_body(body);
+                            }
                         }
                         Err(e) => {
-                            app.response_status = Some((0, "Error".to_string()));
-                            app.response_body = format!("Error: {}", e);
-                            app.response_is_large = false;
+                            if let Some(tab) = tab {
+                                tab.show_error(&e);
+                            }
+                            entry.error = Some(e);
                         }
                     }
+                    app.record_history(entry);
+                    app.reload_cookies();
                     cx.notify();
                 })
             })
-        })
-        .detach();
+            .ok();
+        });
+        self.tabs[index].request_task = Some(task);
+    }
+
+    /// Abort the request the current tab is sending
+    fn cancel_request(&mut self, cx: &mut Context<Self>) {
+        if self.tab().is_loading {
+            self.tab_mut().cancel();
+            cx.notify();
+        } else {
+            // Leave Escape to whatever else handles it
+            cx.propagate();
+        }
+    }
+
+    /// Path of a tab's request relative to the open folder
+    fn request_file(&self, tab: &EditorTab) -> Option<PathBuf> {
+        let folder = self.current_folder.as_ref()?;
+        tab.path
+            .as_ref()?
+            .strip_prefix(folder)
+            .ok()
+            .map(|path| path.to_path_buf())
+    }
+
+    fn record_history(&mut self, entry: HistoryEntry) {
+        if let Some(folder) = &self.current_folder {
+            history::append(folder, &entry);
+        }
+        self.history.insert(0, entry);
+        self.history.truncate(history::MAX_HISTORY_ENTRIES);
+    }
+
+    /// Load a history entry into the editor together with the response it got
+    fn open_history_entry(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.history.get(index).cloned() else {
+            return;
+        };
+
+        // Show the entry next to the file it was sent from, unless that file is
+        // already open in another tab
+        let path = entry.file.as_ref().and_then(|file| {
+            let path = self.current_folder.as_ref()?.join(file);
+            let open = self.tabs.iter().any(|tab| tab.path.as_ref() == Some(&path));
+            (!open && path.exists()).then_some(path)
+        });
+        self.reuse_or_open_tab(window, cx);
+        let tab = self.tab_mut();
+        if let Some(path) = path {
+            tab.load_file(&path, window, cx);
+        }
+        tab.apply_request(&entry.request, window, cx);
+
+        tab.response_status = Some(match entry.status {
+            Some(status) => (status, status_text(status, &entry.reason)),
+            None => (0, "Error".to_string()),
+        });
+        tab.response_cancelled = false;
+        tab.response_truncated = false;
+        tab.response_file = None;
+        tab.response_headers = entry.headers;
+        tab.response_version = None;
+        tab.response_remote_addr = None;
+        tab.response_unverified_tls = false;
+        tab.response_time = Some(entry.duration_ms as u128);
+        tab.response_timings = entry.timings;
+        tab.history_diff = None;
+        match entry.error {
+            Some(e) => tab.show_error(&e),
+            None => tab.show_response_body(entry.body),
+        }
+        cx.notify();
+    }
+
+    /// Send a past request again without overwriting the file it came from
+    fn resend_history_entry(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.history.get(index).cloned() else {
+            return;
+        };
+        self.open_history_entry(index, window, cx);
+        let tab_id = self.tab().id;
+        self.dispatch_request(entry.request, None, tab_id, window, cx);
+    }
+
+    /// Diff a history entry's body against the current response, or show what
+    /// changed since the entry before it when no response is displayed
+    fn diff_history_entry(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.history.get(index) else {
+            return;
+        };
+
+        let (title, old, new) = if self.tab().response_status.is_some() && !self.tab().is_loading {
+            (
+                format!(
+                    "{} vs current response",
+                    history::relative_time(entry.timestamp)
+                ),
+                pretty_json(entry.body.clone()),
+                self.tab().response_body.clone(),
+            )
+        } else if let Some(older) = self.history.get(index + 1) {
+            (
+                format!(
+                    "{} vs {}",
+                    history::relative_time(older.timestamp),
+                    history::relative_time(entry.timestamp)
+                ),
+                pretty_json(older.body.clone()),
+                pretty_json(entry.body.clone()),
+            )
+        } else {
+            return;
+        };
+
+        self.tab_mut().history_diff = Some((title, history::diff_lines(&old, &new)));
+        self.tab_mut().response_tab = ResponseTab::Body;
+        cx.notify();
+    }
+
+    fn clear_history(&mut self, cx: &mut Context<Self>) {
+        if let Some(folder) = &self.current_folder {
+            history::clear(folder);
+        }
+        self.history.clear();
+        self.tab_mut().history_diff = None;
+        cx.notify();
+    }
+
+    /// Pick up the cookie jar of the current folder and environment
+    fn reload_cookies(&mut self) {
+        self.cookie_jar = CookieJar::load(&self.request_context());
+        self.editing_cookie = None;
+    }
+
+    fn save_cookies(&mut self, cx: &mut Context<Self>) {
+        self.cookie_jar.save(&self.request_context());
+        cx.notify();
+    }
+
+    /// Start editing the value of a cookie
+    fn start_editing_cookie(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(cookie) = self.cookie_jar.cookies.get(index) else {
+            return;
+        };
+        let value = cookie.value.clone();
+        self.editing_cookie = Some(index);
+        self.cookie_input.update(cx, |state, cx| {
+            state.set_value(&value, window, cx);
+        });
+        cx.notify();
+    }
+
+    fn cancel_editing_cookie(&mut self, cx: &mut Context<Self>) {
+        self.editing_cookie = None;
+        cx.notify();
+    }
+
+    fn confirm_editing_cookie(&mut self, cx: &mut Context<Self>) {
+        if let Some(index) = self.editing_cookie.take() {
+            let value = self.cookie_input.read(cx).value().to_string();
+            if let Some(cookie) = self.cookie_jar.cookies.get_mut(index) {
+                cookie.value = value;
+            }
+        }
+        self.save_cookies(cx);
+    }
+
+    fn delete_cookie(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.cookie_jar.cookies.len() {
+            self.cookie_jar.cookies.remove(index);
+        }
+        self.editing_cookie = None;
+        self.save_cookies(cx);
+    }
+
+    fn clear_cookie_domain(&mut self, domain: &str, cx: &mut Context<Self>) {
+        self.cookie_jar.clear_domain(domain);
+        self.editing_cookie = None;
+        self.save_cookies(cx);
+    }
+
+    fn clear_cookies(&mut self, cx: &mut Context<Self>) {
+        self.cookie_jar.cookies.clear();
+        self.editing_cookie = None;
+        self.save_cookies(cx);
     }
 
     /// Open folder dialog and load requests
@@ -340,16 +1172,17 @@ impl App {
                 .await;
 
             if let Some(path) = folder.map(|f| f.path().to_path_buf()) {
-                let _ = this.update(cx, |app, cx| {
+                let _ = this.update_in(cx, |app, window, cx| {
                     app.current_folder = Some(path.clone());
 
                     // Save config
-                    let config = AppConfig {
-                        last_opened_folder: Some(path),
-                    };
-                    config.save();
+                    app.config.last_opened_folder = Some(path);
+                    app.config.save();
 
                     app.load_folder(cx);
+                    app.load_history();
+                    app.init_git(cx);
+                    app.watch_folder(window, cx);
                     cx.notify();
                 });
             }
@@ -357,224 +1190,612 @@ impl App {
         .detach();
     }
 
+    /// Start watching the open folder, replacing the watch of the previous one
+    fn watch_folder(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.folder_watch = None;
+        let Some(folder) = &self.current_folder else {
+            return;
+        };
+        let mut watcher = match FolderWatcher::new(folder) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("Failed to watch folder {:?}: {}", folder, e);
+                return;
+            }
+        };
+
+        self.folder_watch = Some(cx.spawn_in(window, async move |this, cx| {
+            while let Some(mut paths) = watcher.next().await {
+                // Let bursts such as a git checkout settle before refreshing
+                cx.background_executor().timer(WATCH_DEBOUNCE).await;
+                paths.extend(watcher.drain());
+                let updated = this.update_in(cx, |app, window, cx| {
+                    app.folder_changed(&paths, window, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Refresh after files in the open folder changed on disk
+    fn folder_changed(&mut self, paths: &[PathBuf], window: &mut Window, cx: &mut Context<Self>) {
+        self.load_folder(cx);
+        self.refresh_git_status(cx);
+
+        let mut changed = Vec::new();
+        for tab in &mut self.tabs {
+            let Some(path) = tab.path.clone() else {
+                continue;
+            };
+            if !path.exists() {
+                // Keep the editor contents; saving writes a new file
+                tab.path = None;
+                tab.file_content = None;
+            } else if paths.contains(&path) {
+                match std::fs::read_to_string(&path) {
+                    Ok(content) if tab.file_content.as_ref() != Some(&content) => {
+                        changed.push((tab.id, content));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for (id, content) in changed {
+            let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == id) else {
+                continue;
+            };
+            if tab.is_dirty(cx) {
+                self.prompt_reload(id, content, window, cx);
+            } else if let Some(path) = tab.path.clone() {
+                // Nothing would be lost, so follow the file without asking
+                tab.load_file(&path, window, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Ask before replacing a tab with unsaved changes by its file that changed on disk
+    fn prompt_reload(
+        &mut self,
+        tab_id: usize,
+        content: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.reload_prompt_open {
+            return;
+        }
+        let Some(tab) = self.tabs.iter().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        self.reload_prompt_open = true;
+
+        let dialog = rfd::AsyncMessageDialog::new()
+            .set_level(rfd::MessageLevel::Info)
+            .set_title("Request Changed on Disk")
+            .set_description(format!(
+                "\"{}\" was changed outside the app. Reload it and discard the changes in the editor?",
+                tab.title(cx)
+            ))
+            .set_buttons(rfd::MessageButtons::YesNo);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let reload = dialog.show().await == rfd::MessageDialogResult::Yes;
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.reload_prompt_open = false;
+                let Some(tab) = app.tabs.iter_mut().find(|tab| tab.id == tab_id) else {
+                    return;
+                };
+                match tab.path.clone() {
+                    Some(path) if reload => {
+                        tab.load_file(&path, window, cx);
+                    }
+                    // Don't ask again until the file changes once more
+                    _ => tab.file_content = Some(content),
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
     /// Load requests from current folder
     fn load_folder(&mut self, _cx: &mut Context<Self>) {
         if let Some(folder) = &self.current_folder {
-            self.saved_requests = fs::scan_folder(folder);
+            self.request_tree = fs::scan_tree(folder);
+            self.saved_requests = fs::flatten_tree(&self.request_tree);
+            self.environments = EnvironmentStore::load(folder);
+            self.folder_settings = FolderSettings::load(folder);
         } else {
+            self.request_tree.clear();
             self.saved_requests.clear();
+            self.environments = EnvironmentStore::default();
+            self.folder_settings = FolderSettings::default();
         }
+        self.reload_cookies();
+    }
+
+    /// Read the history of the open folder; done once when the folder is opened, as
+    /// sends are then recorded in memory too
+    fn load_history(&mut self) {
+        self.history = self
+            .current_folder
+            .as_deref()
+            .map(history::load)
+            .unwrap_or_default();
     }
 
     /// Save current request to file
     fn save_request(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         if let Some(folder) = &self.current_folder {
-            let url = self.url_input.read(cx).value().to_string();
-            let body = self.body_input.read(cx).value().to_string();
-            let method = self.method.as_str().to_string();
-            let name = self.name_input.read(cx).value().to_string();
-
-            let mut headers = std::collections::HashMap::new();
-            for kv in &self.headers {
-                let key = kv.key.read(cx).value().to_string();
-                let value = kv.value.read(cx).value().to_string();
-                if !key.is_empty() {
-                    headers.insert(key, value);
-                }
-            }
+            let mut request = self.current_request(cx);
 
             // If name is empty, provide a default
-            let name = if name.is_empty() {
-                format!("New Request {}", self.saved_requests.len() + 1)
+            if request.name.is_empty() {
+                request.name = format!("New Request {}", self.saved_requests.len() + 1);
+            }
+
+            let path = if let Some(selected) = &self.tab().path {
+                // Overwrite existing file
+                selected.clone()
             } else {
-                name
+                // Create new file, never over one that already exists
+                let format = self.folder_settings.request_format;
+                fs::unique_request_path(folder, &request.name, format)
             };
 
-            let request = SavedRequest {
-                name: name.clone(),
-                method,
-                url,
-                headers,
-                body,
-            };
+            if fs::save_request(&path, &request).is_ok() {
+                self.load_folder(cx);
 
-            if let Ok(json) = serde_json::to_string_pretty(&request) {
-                let path = if let Some(idx) = self.selected_request {
-                    // Overwrite existing file
-                    self.saved_requests[idx].path.clone()
-                } else {
-                    // Create new file
-                    let safe_name: String = name
-                        .chars()
-                        .map(|c| if c.is_alphanumeric() { c } else { '-' })
-                        .collect();
-                    folder.join(format!("{}.json", safe_name))
-                };
+                // Select the file we just saved to
+                let tab = self.tab_mut();
+                tab.path = Some(path);
+                tab.remember_file_content();
+                tab.mark_clean(cx);
+            }
+        }
+    }
 
-                if std::fs::write(&path, json).is_ok() {
-                    self.load_folder(cx);
+    /// Choose the format new request files of the open folder are saved in
+    fn set_request_format(&mut self, format: RequestFormat, cx: &mut Context<Self>) {
+        if let Some(folder) = &self.current_folder {
+            self.folder_settings.request_format = format;
+            self.folder_settings.save(folder);
+            cx.notify();
+        }
+    }
 
-                    // If we just saved to a specific path, find it and select it
-                    if let Some(idx) = self.saved_requests.iter().position(|r| r.path == path) {
-                        self.selected_request = Some(idx);
-                    }
-                }
+    /// Switch what the Settings tab edits, filling the editor from what is saved
+    fn set_settings_scope(
+        &mut self,
+        scope: SettingsScope,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match scope {
+            SettingsScope::Request => {}
+            SettingsScope::FolderDefaults => {
+                let defaults = self.folder_settings.request_defaults.clone();
+                self.folder_defaults_editor.update(cx, |editor, cx| {
+                    editor.set_settings(&defaults, window, cx);
+                });
+            }
+            SettingsScope::Proxy => {
+                // Start with the proxy that currently applies
+                let target = self.environments.active.clone();
+                self.edit_proxy(target, window, cx);
+            }
+            SettingsScope::Tls => {
+                let hosts = self.config.tls.clone();
+                self.tls_editor.update(cx, |editor, cx| {
+                    editor.set_hosts(&hosts, window, cx);
+                });
+            }
+        }
+        self.settings_scope = scope;
+        cx.notify();
+    }
+
+    /// Edit the proxy of all requests, or of the environment named `target`
+    fn edit_proxy(&mut self, target: Option<String>, window: &mut Window, cx: &mut Context<Self>) {
+        let proxy = match &target {
+            Some(name) => self.config.environment_proxies.get(name).cloned(),
+            None => Some(self.config.proxy.clone()),
+        };
+        let inheritable = target.is_some();
+        self.proxy_editor.update(cx, |editor, cx| {
+            editor.set_proxy(proxy.as_ref(), inheritable, window, cx);
+        });
+        self.proxy_target = target;
+        cx.notify();
+    }
+
+    fn save_proxy(&mut self, cx: &mut Context<Self>) {
+        let proxy = self.proxy_editor.read(cx).proxy(cx);
+        match (&self.proxy_target, proxy) {
+            (None, proxy) => self.config.proxy = proxy.unwrap_or_default(),
+            (Some(name), Some(proxy)) => {
+                self.config.environment_proxies.insert(name.clone(), proxy);
+            }
+            (Some(name), None) => {
+                self.config.environment_proxies.remove(name);
             }
         }
+        self.config.save();
+        cx.notify();
+    }
+
+    fn save_tls(&mut self, cx: &mut Context<Self>) {
+        self.config.tls = self.tls_editor.read(cx).hosts(cx);
+        self.config.save();
+        cx.notify();
+    }
+
+    fn save_folder_defaults(&mut self, cx: &mut Context<Self>) {
+        if let Some(folder) = &self.current_folder {
+            self.folder_settings.request_defaults =
+                self.folder_defaults_editor.read(cx).settings(cx);
+            self.folder_settings.save(folder);
+            cx.notify();
+        }
     }
 
     /// Save as new request
     fn save_new_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        self.selected_request = None;
+        self.tab_mut().path = None;
         self.save_request(window, cx);
     }
 
-    /// Load a saved request into the editor
-    fn load_request(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(entry) = self.saved_requests.get(index) {
-            if let Ok(content) = std::fs::read_to_string(&entry.path) {
-                if let Ok(request) = serde_json::from_str::<SavedRequest>(&content) {
-                    // Set name
-                    self.name_input.update(cx, |state, cx| {
-                        state.set_value(&request.name, window, cx);
-                    });
-
-                    // Set method
-                    self.method = match request.method.to_uppercase().as_str() {
-                        "GET" => HttpMethod::Get,
-                        "POST" => HttpMethod::Post,
-                        "PUT" => HttpMethod::Put,
-                        "DELETE" => HttpMethod::Delete,
-                        "PATCH" => HttpMethod::Patch,
-                        _ => HttpMethod::Get,
-                    };
+    /// Copy the resolved request to the clipboard as a code snippet
+    fn copy_as_code(&mut self, target: CodeTarget, cx: &mut Context<Self>) {
+        let prepared = PreparedRequest::from_saved(&self.current_request(cx), &self.variables());
+        let code = codegen::generate(target, &prepared);
+        cx.write_to_clipboard(ClipboardItem::new_string(code));
+    }
 
-                    // Set URL
-                    self.url_input.update(cx, |state, cx| {
-                        state.set_value(&request.url, window, cx);
-                    });
+    /// Show a native message box
+    fn show_message(
+        &self,
+        level: rfd::MessageLevel,
+        title: &str,
+        message: String,
+        cx: &mut Context<Self>,
+    ) {
+        let dialog = rfd::AsyncMessageDialog::new()
+            .set_level(level)
+            .set_title(title)
+            .set_description(message);
+        cx.spawn(async move |_, _| {
+            dialog.show().await;
+        })
+        .detach();
+    }
 
-                    // Set body
-                    if !request.body.is_empty() {
-                        self.body_input.update(cx, |state, cx| {
-                            state.set_value(&request.body, window, cx);
-                        });
-                    }
+    /// Import a curl command from the clipboard into the editor, optionally saving it
+    /// straight away as a new request file
+    fn import_curl(&mut self, save_as_new: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let text = cx
+            .read_from_clipboard()
+            .and_then(|item| item.text())
+            .unwrap_or_default();
 
-                    // Clear and set headers
-                    self.headers.clear();
-                    for (key, value) in request.headers.iter() {
-                        self.headers
-                            .push(Self::create_kv_pair(window, cx, key, value));
-                    }
-                    // Add empty row for new headers
-                    self.headers.push(Self::create_kv_pair(window, cx, "", ""));
+        let mut request = match curl::parse(&text, "Imported Request") {
+            Ok(request) => request,
+            Err(e) => {
+                self.show_message(
+                    rfd::MessageLevel::Error,
+                    "Import cURL",
+                    format!("Could not import from clipboard: {}", e),
+                    cx,
+                );
+                return;
+            }
+        };
+
+        // Name the request after the last path segment, e.g. "GET users"
+        let segment = request
+            .url
+            .split(['?', '#'])
+            .next()
+            .and_then(|url| url.trim_end_matches('/').rsplit('/').next())
+            .filter(|segment| !segment.is_empty() && !segment.contains(':'))
+            .unwrap_or("request")
+            .to_string();
+        request.name = format!("{} {}", request.method, segment);
 
-                    self.selected_request = Some(index);
+        if let Some(folder) = &self.current_folder {
+            let format = self.folder_settings.request_format;
+            request.name = fs::unique_request_name(folder, &request.name, format);
+        }
+
+        self.reuse_or_open_tab(window, cx);
+        self.tab_mut().apply_request(&request, window, cx);
+        if save_as_new {
+            self.save_request(window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Pick a file and generate request files from it in the open folder
+    fn import_file(
+        &mut self,
+        title: &'static str,
+        extensions: &'static [&'static str],
+        import: fn(&Path, &Path) -> Result<fs::ImportSummary, String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(folder) = self.current_folder.clone() else {
+            self.show_message(
+                rfd::MessageLevel::Warning,
+                title,
+                "Open a folder to import into first.".to_string(),
+                cx,
+            );
+            return;
+        };
+
+        cx.spawn_in(window, async move |this, cx| {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title(title)
+                .add_filter(title, extensions)
+                .pick_file()
+                .await;
+
+            if let Some(path) = file.map(|f| f.path().to_path_buf()) {
+                let _ = this.update(cx, |app, cx| {
+                    let (level, message) = match import(&path, &folder) {
+                        Ok(summary) => (rfd::MessageLevel::Info, summary.describe()),
+                        Err(e) => (rfd::MessageLevel::Error, e),
+                    };
+                    app.load_folder(cx);
+                    app.show_message(level, title, message, cx);
                     cx.notify();
-                }
+                });
             }
+        })
+        .detach();
+    }
+
+    /// Save the open folder as a Postman collection
+    fn export_postman(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(folder) = self.current_folder.clone() else {
+            return;
+        };
+        let file_name = format!(
+            "{}.postman_collection.json",
+            folder
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("collection")
+        );
+
+        cx.spawn_in(window, async move |this, cx| {
+            let file = rfd::AsyncFileDialog::new()
+                .set_title("Export Postman Collection")
+                .set_file_name(file_name)
+                .save_file()
+                .await;
+
+            if let Some(path) = file.map(|f| f.path().to_path_buf()) {
+                let _ = this.update(cx, |app, cx| {
+                    let (level, message) = match postman::export(&folder, &path) {
+                        Ok(count) => (
+                            rfd::MessageLevel::Info,
+                            format!("Exported {} request(s) to {}", count, path.display()),
+                        ),
+                        Err(e) => (rfd::MessageLevel::Error, e),
+                    };
+                    app.show_message(level, "Export Postman Collection", message, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Show a saved request, switching to its tab when it is already open
+    fn load_request(&mut self, path: &Path, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self
+            .tabs
+            .iter()
+            .position(|tab| tab.path.as_deref() == Some(path))
+        {
+            self.active_editor = index;
+        } else if fs::is_request_file(path) {
+            self.reuse_or_open_tab(window, cx);
+            self.tab_mut().load_file(path, window, cx);
         }
+        cx.notify();
     }
 
     /// Delete a request
-    fn delete_request(&mut self, index: usize, _window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(folder) = &self.current_folder {
-            if let Some(request) = self.saved_requests.get(index) {
-                let name = if request.name.ends_with(".json") {
-                    request.name.clone()
-                } else {
-                    format!("{}.json", request.name)
-                };
-                let path = folder.join(&name);
+    fn delete_request(&mut self, path: &Path, _window: &mut Window, cx: &mut Context<Self>) {
+        // Attempt to delete file
+        if let Err(e) = std::fs::remove_file(path) {
+            eprintln!("Failed to delete file {:?}: {}", path, e);
+            return;
+        }
 
-                // Attempt to delete file
-                if let Err(e) = std::fs::remove_file(&path) {
-                    eprintln!("Failed to delete file {:?}: {}", path, e);
-                    return;
-                }
+        // Open tabs keep their contents; saving writes a new file
+        for tab in &mut self.tabs {
+            if tab.path.as_deref() == Some(path) {
+                tab.path = None;
+            }
+        }
+        self.load_folder(cx);
+        cx.notify();
+    }
 
-                // Remove from list
-                self.saved_requests.remove(index);
+    /// Create a subfolder and start renaming it
+    fn new_folder(&mut self, parent: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let path = fs::unique_dir_path(&parent, "New Folder");
+        if let Err(e) = std::fs::create_dir_all(&path) {
+            eprintln!("Failed to create folder {:?}: {}", path, e);
+            return;
+        }
+        self.collapsed_folders.remove(&parent);
+        self.load_folder(cx);
+        self.start_renaming(path, window, cx);
+    }
+
+    /// Delete a folder and everything in it after asking for confirmation
+    fn delete_folder(&mut self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("folder")
+            .to_string();
+        let dialog = rfd::AsyncMessageDialog::new()
+            .set_level(rfd::MessageLevel::Warning)
+            .set_title("Delete Folder")
+            .set_description(format!(
+                "Delete \"{}\" and all requests inside it? This cannot be undone.",
+                name
+            ))
+            .set_buttons(rfd::MessageButtons::YesNo);
 
-                // Update selected index
-                if let Some(selected) = self.selected_request {
-                    if selected == index {
-                        self.selected_request = None;
-                    } else if selected > index {
-                        self.selected_request = Some(selected - 1);
+        cx.spawn_in(window, async move |this, cx| {
+            if dialog.show().await != rfd::MessageDialogResult::Yes {
+                return;
+            }
+            let _ = this.update(cx, |app, cx| {
+                if let Err(e) = std::fs::remove_dir_all(&path) {
+                    eprintln!("Failed to delete folder {:?}: {}", path, e);
+                    return;
+                }
+                for tab in &mut app.tabs {
+                    if tab
+                        .path
+                        .as_ref()
+                        .is_some_and(|file| file.starts_with(&path))
+                    {
+                        tab.path = None;
                     }
                 }
-
+                app.load_folder(cx);
                 cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Move a request file or folder into `dir` on disk
+    fn move_entry(&mut self, path: &Path, dir: &Path, cx: &mut Context<Self>) {
+        // Dropping onto its own folder, or a folder into itself, does nothing
+        if path.parent() == Some(dir) || dir.starts_with(path) {
+            return;
+        }
+        match fs::move_into(path, dir) {
+            Ok(target) => {
+                self.remap_paths(path, &target);
+                self.collapsed_folders.remove(dir);
+                self.load_folder(cx);
             }
+            Err(e) => self.show_message(
+                rfd::MessageLevel::Error,
+                "Move",
+                format!("Could not move {}: {}", path.display(), e),
+                cx,
+            ),
         }
+        cx.notify();
     }
 
-    /// Start renaming a request
-    fn start_renaming(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(request) = self.saved_requests.get(index) {
-            self.renaming_index = Some(index);
-            // remove .json extension for editing
-            let name_str = if request.name.ends_with(".json") {
-                &request.name[..request.name.len() - 5]
-            } else {
-                &request.name
-            };
-            let name = name_str.to_string();
+    /// Keep open tabs and folder state pointing at entries that moved from `from` to `to`
+    fn remap_paths(&mut self, from: &Path, to: &Path) {
+        let remap = |path: &Path| -> Option<PathBuf> {
+            path.strip_prefix(from).ok().map(|rest| {
+                if rest.as_os_str().is_empty() {
+                    to.to_path_buf()
+                } else {
+                    to.join(rest)
+                }
+            })
+        };
+        for tab in &mut self.tabs {
+            if let Some(path) = tab.path.as_deref().and_then(remap) {
+                tab.path = Some(path);
+            }
+        }
+        self.collapsed_folders = self
+            .collapsed_folders
+            .iter()
+            .map(|path| remap(path).unwrap_or_else(|| path.clone()))
+            .collect();
+    }
 
-            let input_entity = self.rename_input.clone();
-            input_entity.update(cx, |state, cx| {
-                state.set_value(&name, window, cx);
-                // state.focus_handle(cx).focus(window); // Keeping focus commented for safety first, can enable later
-            });
-            cx.notify();
+    fn toggle_folder(&mut self, path: &Path, cx: &mut Context<Self>) {
+        if !self.collapsed_folders.remove(path) {
+            self.collapsed_folders.insert(path.to_path_buf());
         }
+        cx.notify();
+    }
+
+    /// Start renaming a request or folder
+    fn start_renaming(&mut self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        // Requests are renamed without their extension
+        let name = if path.is_dir() {
+            path.file_name()
+        } else {
+            path.file_stem()
+        }
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
+        self.renaming = Some(path);
+
+        let input_entity = self.rename_input.clone();
+        input_entity.update(cx, |state, cx| {
+            state.set_value(&name, window, cx);
+            // state.focus_handle(cx).focus(window); // Keeping focus commented for safety first, can enable later
+        });
+        cx.notify();
     }
 
     /// Cancel renaming
     fn cancel_renaming(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
-        self.renaming_index = None;
+        self.renaming = None;
         cx.notify();
     }
 
     /// Confirm renaming
     fn confirm_renaming(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(index) = self.renaming_index {
-            if let Some(folder) = &self.current_folder {
-                if let Some(request) = self.saved_requests.get(index) {
-                    let new_name = self.rename_input.read(cx).value().to_string();
-                    let safe_name = urlencoding(&new_name)
-                        .replace("%", "")
-                        .replace("/", "")
-                        .replace("\\", "");
-
-                    if safe_name.is_empty() {
-                        return;
-                    }
-
-                    let old_filename = if request.name.ends_with(".json") {
-                        request.name.clone()
-                    } else {
-                        format!("{}.json", request.name)
-                    };
+        if let Some(old_path) = self.renaming.take() {
+            let new_name = self.rename_input.read(cx).value().to_string();
+            let parent = old_path.parent().map(Path::to_path_buf).unwrap_or_default();
 
-                    let new_filename = format!("{}.json", safe_name);
-                    let old_path = folder.join(&old_filename);
-                    let new_path = folder.join(&new_filename);
+            let new_path = if old_path.is_dir() {
+                let name = new_name.trim();
+                if name.is_empty() {
+                    cx.notify();
+                    return;
+                }
+                parent.join(fs::safe_dir_name(name))
+            } else {
+                let name = new_name.trim();
+                if name.is_empty() {
+                    cx.notify();
+                    return;
+                }
+                // Named the way a new request would be, keeping the file's format
+                let format = RequestFormat::from_path(&old_path).unwrap_or_default();
+                parent.join(fs::request_file_name(name, format))
+            };
 
-                    if let Err(e) = std::fs::rename(&old_path, &new_path) {
-                        eprintln!("Failed to rename file: {}", e);
-                    } else {
-                        // Update the entry in the list
-                        if let Some(entry) = self.saved_requests.get_mut(index) {
-                            entry.name = new_filename;
-                        }
-                    }
+            if new_path != old_path {
+                if new_path.exists() {
+                    eprintln!("Failed to rename: {:?} already exists", new_path);
+                } else if let Err(e) = std::fs::rename(&old_path, &new_path) {
+                    eprintln!("Failed to rename file: {}", e);
+                } else {
+                    self.remap_paths(&old_path, &new_path);
+                    self.load_folder(cx);
                 }
             }
         }
-        self.renaming_index = None;
         cx.notify();
     }
 
@@ -609,32 +1830,157 @@ impl App {
                             .text_sm()
                             .font_weight(FontWeight::SEMIBOLD)
                             .text_color(cx.theme().sidebar_foreground)
-                            .child(if self.sidebar_tab == SidebarTab::Files {
-                                "Requests"
-                            } else {
-                                "Git Changes"
+                            .child(match self.sidebar_tab {
+                                SidebarTab::Files => "Requests",
+                                SidebarTab::Git => "Git Changes",
+                                SidebarTab::History => "History",
+                                SidebarTab::Cookies => "Cookies",
                             }),
                     )
-                    .child(if self.sidebar_tab == SidebarTab::Files {
+                    .child(if self.sidebar_tab == SidebarTab::History {
                         div()
-                            .id("open-folder-btn")
+                            .id("clear-history-btn")
                             .p_1()
                             .rounded(px(4.0))
                             .cursor_pointer()
+                            .when(self.history.is_empty(), |this| this.invisible())
                             .hover(|s| s.bg(cx.theme().sidebar_accent))
                             .on_mouse_down(
                                 MouseButton::Left,
-                                cx.listener(|this, _, window, cx| {
-                                    this.open_folder(window, cx);
-                                    cx.notify();
+                                cx.listener(|this, _, _, cx| {
+                                    this.clear_history(cx);
+                                }),
+                            )
+                            .tooltip(|window, cx| Tooltip::new("Clear History").build(window, cx))
+                            .child(
+                                Icon::new(IconName::Delete)
+                                    .text_color(cx.theme().sidebar_foreground),
+                            )
+                            .into_any_element()
+                    } else if self.sidebar_tab == SidebarTab::Cookies {
+                        div()
+                            .id("clear-cookies-btn")
+                            .p_1()
+                            .rounded(px(4.0))
+                            .cursor_pointer()
+                            .when(self.cookie_jar.cookies.is_empty(), |this| this.invisible())
+                            .hover(|s| s.bg(cx.theme().sidebar_accent))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _, _, cx| {
+                                    this.clear_cookies(cx);
                                 }),
                             )
-                            .tooltip(|window, cx| Tooltip::new("Open Folder").build(window, cx))
+                            .tooltip(|window, cx| Tooltip::new("Clear Cookies").build(window, cx))
                             .child(
-                                Icon::new(IconName::FolderOpen)
+                                Icon::new(IconName::Delete)
                                     .text_color(cx.theme().sidebar_foreground),
                             )
                             .into_any_element()
+                    } else if self.sidebar_tab == SidebarTab::Files {
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .when(self.current_folder.is_some(), |this| {
+                                let view = cx.entity().downgrade();
+                                let current = self.folder_settings.request_format;
+                                this.child(
+                                    Button::new("folder-settings-btn")
+                                        .ghost()
+                                        .xsmall()
+                                        .icon(IconName::Settings)
+                                        .tooltip("Folder Settings")
+                                        .dropdown_menu(move |mut menu, _, _| {
+                                            for format in RequestFormat::ALL {
+                                                let view = view.clone();
+                                                menu = menu.item(
+                                                    PopupMenuItem::new(format!(
+                                                        "Save New Requests as {}",
+                                                        format.label()
+                                                    ))
+                                                    .checked(format == current)
+                                                    .on_click(move |_, _, cx| {
+                                                        let _ = view.update(cx, |app, cx| {
+                                                            app.set_request_format(format, cx);
+                                                        });
+                                                    }),
+                                                );
+                                            }
+                                            menu
+                                        }),
+                                )
+                            })
+                            .when_some(self.current_folder.clone(), |this, folder| {
+                                this.child(
+                                    div()
+                                        .id("new-folder-btn")
+                                        .p_1()
+                                        .rounded(px(4.0))
+                                        .cursor_pointer()
+                                        .hover(|s| s.bg(cx.theme().sidebar_accent))
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(move |this, _, window, cx| {
+                                                this.new_folder(folder.clone(), window, cx);
+                                            }),
+                                        )
+                                        .tooltip(|window, cx| {
+                                            Tooltip::new("New Folder").build(window, cx)
+                                        })
+                                        .child(
+                                            Icon::new(IconName::Folder)
+                                                .text_color(cx.theme().sidebar_foreground),
+                                        ),
+                                )
+                            })
+                            .when(self.current_folder.is_some(), |this| {
+                                this.child(
+                                    div()
+                                        .id("export-postman-btn")
+                                        .p_1()
+                                        .rounded(px(4.0))
+                                        .cursor_pointer()
+                                        .hover(|s| s.bg(cx.theme().sidebar_accent))
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _, window, cx| {
+                                                this.export_postman(window, cx);
+                                            }),
+                                        )
+                                        .tooltip(|window, cx| {
+                                            Tooltip::new("Export Postman Collection")
+                                                .build(window, cx)
+                                        })
+                                        .child(
+                                            Icon::new(IconName::ExternalLink)
+                                                .text_color(cx.theme().sidebar_foreground),
+                                        ),
+                                )
+                            })
+                            .child(
+                                div()
+                                    .id("open-folder-btn")
+                                    .p_1()
+                                    .rounded(px(4.0))
+                                    .cursor_pointer()
+                                    .hover(|s| s.bg(cx.theme().sidebar_accent))
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _, window, cx| {
+                                            this.open_folder(window, cx);
+                                            cx.notify();
+                                        }),
+                                    )
+                                    .tooltip(|window, cx| {
+                                        Tooltip::new("Open Folder").build(window, cx)
+                                    })
+                                    .child(
+                                        Icon::new(IconName::FolderOpen)
+                                            .text_color(cx.theme().sidebar_foreground),
+                                    ),
+                            )
+                            .into_any_element()
                     } else {
                         div().into_any_element()
                     }),
@@ -648,10 +1994,43 @@ impl App {
                     .text_color(cx.theme().sidebar_foreground.opacity(0.7))
                     .child(folder_name.clone()),
             )
+            .child(
+                div().px_3().pb_2().child(
+                    TabBar::new("sidebar-tabs")
+                        .pill()
+                        .small()
+                        .selected_index(match self.sidebar_tab {
+                            SidebarTab::Files => 0,
+                            SidebarTab::Git => 1,
+                            SidebarTab::History => 2,
+                            SidebarTab::Cookies => 3,
+                        })
+                        .on_click(cx.listener(|this, index, _, cx| {
+                            this.sidebar_tab = match index {
+                                0 => SidebarTab::Files,
+                                1 => SidebarTab::Git,
+                                2 => SidebarTab::History,
+                                _ => SidebarTab::Cookies,
+                            };
+                            if this.sidebar_tab == SidebarTab::Git {
+                                this.refresh_git_status(cx);
+                            }
+                            cx.notify();
+                        }))
+                        .child(Tab::new().child("Files"))
+                        .child(Tab::new().child("Git"))
+                        .child(Tab::new().child("History"))
+                        .child(Tab::new().child("Cookies")),
+                ),
+            )
             // File list
             // File list or Empty State
-            .child(if self.sidebar_tab == SidebarTab::Files {
-                if self.saved_requests.is_empty() {
+            .child(if self.sidebar_tab == SidebarTab::History {
+                self.render_history_list(cx).into_any_element()
+            } else if self.sidebar_tab == SidebarTab::Cookies {
+                self.render_cookie_list(cx).into_any_element()
+            } else if self.sidebar_tab == SidebarTab::Files {
+                if self.request_tree.is_empty() {
                     let (message, sub_message, icon) = if self.current_folder.is_some() {
                         (
                             "No requests",
@@ -667,228 +2046,776 @@ impl App {
                     };
 
                     div()
-                        .flex_1()
+                        .flex_1()
+                        .flex()
+                        .flex_col()
+                        .items_center()
+                        .justify_center()
+                        .gap_3()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(
+                            Icon::new(icon)
+                                .size(px(32.0))
+                                .text_color(cx.theme().muted_foreground.opacity(0.5)),
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .items_center()
+                                .gap_1()
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .child(message),
+                                )
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground.opacity(0.7))
+                                        .child(sub_message),
+                                ),
+                        )
+                        .into_any_element()
+                } else {
+                    self.render_request_tree(cx).into_any_element()
+                }
+            } else {
+                div()
+                    .id("git-panel")
+                    .size_full()
+                    .child(self.git_panel.clone())
+                    .into_any_element()
+            })
+    }
+
+    /// Render the requests and folders of the open folder as an indented tree
+    fn render_request_tree(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows = Vec::new();
+        self.visible_rows(&self.request_tree, 0, &mut rows);
+        let root = self.current_folder.clone();
+
+        div()
+            .id("request-tree")
+            .flex_1()
+            .drag_over::<DraggedEntry>(|style, _, _, cx| style.bg(cx.theme().accent.opacity(0.05)))
+            .on_drop(cx.listener(move |this, dragged: &DraggedEntry, _, cx| {
+                if let Some(root) = &root {
+                    this.move_entry(&dragged.path, root, cx);
+                }
+            }))
+            .overflow_y_scrollbar()
+            .children(rows.into_iter().map(|(depth, node)| {
+                match node {
+                    TreeNode::Folder { name, path, .. } => self
+                        .render_folder_row(name, path, depth, cx)
+                        .into_any_element(),
+                    TreeNode::Request(entry) => {
+                        self.render_request_row(entry, depth, cx).into_any_element()
+                    }
+                }
+            }))
+    }
+
+    /// Collect the rows that are not hidden inside a collapsed folder
+    fn visible_rows<'a>(
+        &self,
+        nodes: &'a [TreeNode],
+        depth: usize,
+        rows: &mut Vec<(usize, &'a TreeNode)>,
+    ) {
+        for node in nodes {
+            rows.push((depth, node));
+            if let TreeNode::Folder { path, children, .. } = node {
+                if !self.collapsed_folders.contains(path) {
+                    self.visible_rows(children, depth + 1, rows);
+                }
+            }
+        }
+    }
+
+    /// Inline name editor shown in place of a row being renamed
+    fn render_rename_input(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .flex_1()
+            .child(
+                div()
+                    .flex_1()
+                    .child(Input::new(&self.rename_input).appearance(false)),
+            )
+            .child(
+                div()
+                    .cursor_pointer()
+                    .child(
+                        Icon::new(IconName::Check)
+                            .size(px(14.0))
+                            .text_color(cx.theme().primary),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            this.confirm_renaming(window, cx);
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .cursor_pointer()
+                    .child(
+                        Icon::new(IconName::Close)
+                            .size(px(14.0))
+                            .text_color(hsla(0.0, 0.6, 0.4, 1.0)),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            this.cancel_renaming(window, cx);
+                        }),
+                    ),
+            )
+    }
+
+    /// Small icon button revealed when hovering a tree row
+    fn render_row_action(
+        &self,
+        icon: IconName,
+        danger: bool,
+        cx: &mut Context<Self>,
+        on_click: impl Fn(&mut Self, &mut Window, &mut Context<Self>) + 'static,
+    ) -> Div {
+        let hover_bg = if danger {
+            hsla(0.0, 0.6, 0.4, 0.2)
+        } else {
+            cx.theme().muted
+        };
+
+        div()
+            .p_1()
+            .rounded_sm()
+            .hover(move |s| s.bg(hover_bg))
+            .child(
+                Icon::new(icon)
+                    .size(px(14.0))
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _, window, cx| {
+                    cx.stop_propagation();
+                    on_click(this, window, cx);
+                }),
+            )
+    }
+
+    fn render_folder_row(
+        &self,
+        name: &str,
+        path: &Path,
+        depth: usize,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_collapsed = self.collapsed_folders.contains(path);
+        let is_renaming = self.renaming.as_deref() == Some(path);
+        let dragged = DraggedEntry {
+            path: path.to_path_buf(),
+            name: name.to_string(),
+        };
+        let toggle_path = path.to_path_buf();
+        let drop_path = path.to_path_buf();
+        let new_path = path.to_path_buf();
+        let rename_path = path.to_path_buf();
+        let delete_path = path.to_path_buf();
+
+        div()
+            .id(ElementId::Name(format!("folder-{}", path.display()).into()))
+            .group("folder-item")
+            .flex()
+            .items_center()
+            .gap_2()
+            .pl(px(12.0 + depth as f32 * 14.0))
+            .pr_3()
+            .py(px(6.0))
+            .cursor_pointer()
+            .hover(|s| s.bg(cx.theme().muted.opacity(0.5)))
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.toggle_folder(&toggle_path, cx);
+            }))
+            .on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+            .drag_over::<DraggedEntry>(|style, _, _, cx| style.bg(cx.theme().accent.opacity(0.15)))
+            .on_drop(cx.listener(move |this, dragged: &DraggedEntry, _, cx| {
+                this.move_entry(&dragged.path, &drop_path, cx);
+            }))
+            .child(
+                Icon::new(if is_collapsed {
+                    IconName::ChevronRight
+                } else {
+                    IconName::ChevronDown
+                })
+                .size(px(14.0))
+                .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                Icon::new(if is_collapsed {
+                    IconName::Folder
+                } else {
+                    IconName::FolderOpen
+                })
+                .size(px(14.0))
+                .text_color(cx.theme().muted_foreground),
+            )
+            .child(if is_renaming {
+                self.render_rename_input(cx)
+            } else {
+                div()
+                    .flex_1()
+                    .text_sm()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .child(name.to_string())
+            })
+            .when(!is_renaming, |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_1()
+                        .invisible()
+                        .group_hover("folder-item", |s| s.visible())
+                        .child(self.render_row_action(
+                            IconName::Plus,
+                            false,
+                            cx,
+                            move |this, window, cx| {
+                                this.new_folder(new_path.clone(), window, cx);
+                            },
+                        ))
+                        .child(self.render_row_action(
+                            IconName::Settings,
+                            false,
+                            cx,
+                            move |this, window, cx| {
+                                this.start_renaming(rename_path.clone(), window, cx);
+                            },
+                        ))
+                        .child(self.render_row_action(
+                            IconName::Delete,
+                            true,
+                            cx,
+                            move |this, window, cx| {
+                                this.delete_folder(delete_path.clone(), window, cx);
+                            },
+                        )),
+                )
+            })
+    }
+
+    fn render_request_row(
+        &self,
+        entry: &FileEntry,
+        depth: usize,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_selected = self.tab().path.as_ref() == Some(&entry.path);
+        let method_color = entry
+            .method
+            .as_ref()
+            .map(|m| m.color())
+            .unwrap_or(cx.theme().muted_foreground);
+        let method_str = entry.method.as_ref().map(|m| m.as_str()).unwrap_or("???");
+        let is_renaming = self.renaming.as_ref() == Some(&entry.path);
+        let dragged = DraggedEntry {
+            path: entry.path.clone(),
+            name: entry.name.clone(),
+        };
+        let load_path = entry.path.clone();
+        let rename_path = entry.path.clone();
+        let delete_path = entry.path.clone();
+
+        div()
+            .id(ElementId::Name(
+                format!("request-{}", entry.path.display()).into(),
+            ))
+            .group("request-item")
+            .flex()
+            .items_center()
+            .gap_2()
+            // Requests line up with the name of their folder, past the chevron
+            .pl(px(12.0 + depth as f32 * 14.0))
+            .pr_3()
+            .py(px(6.0)) // Tighter, refined spacing
+            .cursor_pointer()
+            .bg(if is_selected {
+                cx.theme().accent.opacity(0.15)
+            } else {
+                gpui::transparent_black()
+            })
+            .hover(|s| s.bg(cx.theme().muted.opacity(0.5)))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _, window, cx| {
+                    this.load_request(&load_path, window, cx);
+                }),
+            )
+            .on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .w_full()
+                    .child(if is_renaming {
+                        self.render_rename_input(cx)
+                    } else {
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_3()
+                            .flex_1()
+                            .child(
+                                Tag::new()
+                                    .small()
+                                    .bg(method_color.opacity(0.15))
+                                    .text_color(method_color)
+                                    .child(method_str),
+                            )
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .overflow_hidden()
+                                    .whitespace_nowrap()
+                                    .text_ellipsis()
+                                    .child(entry.name.clone()),
+                            )
+                    })
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .invisible()
+                            .group_hover("request-item", |s| s.visible())
+                            .when(!is_renaming, |this| {
+                                this.child(self.render_row_action(
+                                    IconName::Settings,
+                                    false,
+                                    cx,
+                                    move |this, window, cx| {
+                                        this.start_renaming(rename_path.clone(), window, cx);
+                                    },
+                                ))
+                                .child(self.render_row_action(
+                                    IconName::Delete,
+                                    true,
+                                    cx,
+                                    move |this, window, cx| {
+                                        this.delete_request(&delete_path, window, cx);
+                                    },
+                                ))
+                            }),
+                    ),
+            )
+    }
+
+    /// Render past sends of the open folder, newest first
+    fn render_cookie_list(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.cookie_jar.cookies.is_empty() {
+            return div()
+                .flex_1()
+                .flex()
+                .flex_col()
+                .items_center()
+                .justify_center()
+                .gap_3()
+                .text_color(cx.theme().muted_foreground)
+                .child(
+                    Icon::new(IconName::Globe)
+                        .size(px(32.0))
+                        .text_color(cx.theme().muted_foreground.opacity(0.5)),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .items_center()
+                        .gap_1()
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_weight(FontWeight::MEDIUM)
+                                .child("No cookies"),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground.opacity(0.7))
+                                .child("Cookies set by responses will show up here"),
+                        ),
+                )
+                .into_any_element();
+        }
+
+        let mut list = div().id("cookie-list").flex_1().overflow_y_scrollbar();
+        for domain in self.cookie_jar.domains() {
+            let clear_domain = domain.clone();
+            list = list.child(
+                div()
+                    .group("cookie-domain")
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_3()
+                    .py(px(6.0))
+                    .child(
+                        Icon::new(IconName::Globe)
+                            .size(px(14.0))
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .overflow_hidden()
+                            .whitespace_nowrap()
+                            .text_ellipsis()
+                            .child(domain.clone()),
+                    )
+                    .child(
+                        div()
+                            .invisible()
+                            .group_hover("cookie-domain", |s| s.visible())
+                            .child(self.render_row_action(
+                                IconName::Delete,
+                                true,
+                                cx,
+                                move |this, _, cx| {
+                                    this.clear_cookie_domain(&clear_domain, cx);
+                                },
+                            )),
+                    ),
+            );
+
+            for (i, cookie) in self.cookie_jar.cookies.iter().enumerate() {
+                if cookie.domain != domain {
+                    continue;
+                }
+                let mut details = vec![if cookie.path == "/" {
+                    String::new()
+                } else {
+                    cookie.path.clone()
+                }];
+                details.push(match cookie.expires {
+                    Some(expires) => chrono::DateTime::from_timestamp_millis(expires as i64)
+                        .map(|time| {
+                            time.with_timezone(&chrono::Local)
+                                .format("until %Y-%m-%d %H:%M")
+                                .to_string()
+                        })
+                        .unwrap_or_default(),
+                    None => "session".to_string(),
+                });
+                if cookie.secure {
+                    details.push("secure".to_string());
+                }
+                if cookie.http_only {
+                    details.push("http-only".to_string());
+                }
+                details.retain(|detail| !detail.is_empty());
+
+                let is_editing = self.editing_cookie == Some(i);
+                list = list.child(
+                    div()
+                        .id(ElementId::Name(format!("cookie-{}", i).into()))
+                        .group("cookie-item")
                         .flex()
-                        .flex_col()
                         .items_center()
-                        .justify_center()
-                        .gap_3()
-                        .text_color(cx.theme().muted_foreground)
-                        .child(
-                            Icon::new(icon)
-                                .size(px(32.0))
-                                .text_color(cx.theme().muted_foreground.opacity(0.5)),
-                        )
+                        .gap_2()
+                        .pl(px(34.0))
+                        .pr_3()
+                        .py(px(4.0))
+                        .hover(|s| s.bg(cx.theme().muted.opacity(0.5)))
                         .child(
                             div()
+                                .flex_1()
                                 .flex()
                                 .flex_col()
-                                .items_center()
-                                .gap_1()
+                                .overflow_hidden()
                                 .child(
                                     div()
                                         .text_sm()
-                                        .font_weight(FontWeight::MEDIUM)
-                                        .child(message),
+                                        .overflow_hidden()
+                                        .whitespace_nowrap()
+                                        .text_ellipsis()
+                                        .child(cookie.name.clone()),
                                 )
+                                .child(if is_editing {
+                                    self.render_cookie_input(cx).into_any_element()
+                                } else {
+                                    div()
+                                        .text_xs()
+                                        .font_family("monospace")
+                                        .text_color(cx.theme().muted_foreground)
+                                        .overflow_hidden()
+                                        .whitespace_nowrap()
+                                        .text_ellipsis()
+                                        .child(cookie.value.clone())
+                                        .into_any_element()
+                                })
                                 .child(
                                     div()
                                         .text_xs()
                                         .text_color(cx.theme().muted_foreground.opacity(0.7))
-                                        .child(sub_message),
+                                        .overflow_hidden()
+                                        .whitespace_nowrap()
+                                        .text_ellipsis()
+                                        .child(details.join(" · ")),
                                 ),
                         )
-                        .into_any_element()
-                } else {
-                    div()
-                        .flex_1()
-                        .overflow_y_scrollbar()
-                        .children(self.saved_requests.iter().enumerate().map(|(i, entry)| {
-                            let is_selected = self.selected_request == Some(i);
-                            let method_color = entry
-                                .method
-                                .as_ref()
-                                .map(|m| m.color())
-                                .unwrap_or(cx.theme().muted_foreground);
-                            let method_str =
-                                entry.method.as_ref().map(|m| m.as_str()).unwrap_or("???");
-                            let name = entry.name.clone();
-                            let is_renaming = self.renaming_index == Some(i);
+                        .when(!is_editing, |this| {
+                            this.child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_1()
+                                    .invisible()
+                                    .group_hover("cookie-item", |s| s.visible())
+                                    .child(self.render_row_action(
+                                        IconName::Settings,
+                                        false,
+                                        cx,
+                                        move |this, window, cx| {
+                                            this.start_editing_cookie(i, window, cx);
+                                        },
+                                    ))
+                                    .child(self.render_row_action(
+                                        IconName::Delete,
+                                        true,
+                                        cx,
+                                        move |this, _, cx| {
+                                            this.delete_cookie(i, cx);
+                                        },
+                                    )),
+                            )
+                        }),
+                );
+            }
+        }
+        list.into_any_element()
+    }
+
+    /// Inline value editor shown in place of a cookie being edited
+    fn render_cookie_input(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(
+                div()
+                    .flex_1()
+                    .child(Input::new(&self.cookie_input).appearance(false)),
+            )
+            .child(
+                div()
+                    .cursor_pointer()
+                    .child(
+                        Icon::new(IconName::Check)
+                            .size(px(14.0))
+                            .text_color(cx.theme().primary),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, cx| {
+                            cx.stop_propagation();
+                            this.confirm_editing_cookie(cx);
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .cursor_pointer()
+                    .child(
+                        Icon::new(IconName::Close)
+                            .size(px(14.0))
+                            .text_color(hsla(0.0, 0.6, 0.4, 1.0)),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, cx| {
+                            cx.stop_propagation();
+                            this.cancel_editing_cookie(cx);
+                        }),
+                    ),
+            )
+    }
 
+    fn render_history_list(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.history.is_empty() {
+            return div()
+                .flex_1()
+                .flex()
+                .flex_col()
+                .items_center()
+                .justify_center()
+                .gap_3()
+                .text_color(cx.theme().muted_foreground)
+                .child(
+                    Icon::new(IconName::Inbox)
+                        .size(px(32.0))
+                        .text_color(cx.theme().muted_foreground.opacity(0.5)),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .items_center()
+                        .gap_1()
+                        .child(
                             div()
-                                .id(ElementId::Name(format!("request-{}", i).into()))
-                                .group("request-item")
-                                .flex()
-                                .items_center()
-                                .gap_2()
-                                .px_3()
-                                .py(px(6.0)) // Tighter, refined spacing
-                                .cursor_pointer()
-                                .bg(if is_selected {
-                                    cx.theme().accent.opacity(0.15)
-                                } else {
-                                    gpui::transparent_black()
-                                })
-                                .hover(|s| s.bg(cx.theme().muted.opacity(0.5)))
-                                .on_mouse_down(
-                                    MouseButton::Left,
-                                    cx.listener(move |this, _, window, cx| {
-                                        this.load_request(i, window, cx);
-                                    }),
-                                )
-                                .child(
-                                    div()
-                                        .flex()
-                                        .items_center()
-                                        .justify_between()
-                                        .w_full()
-                                        .child(if is_renaming {
-                                            div()
-                                                .flex()
-                                                .items_center()
-                                                .gap_2()
-                                                .flex_1()
-                                                .child(
-                                                    div().flex_1().child(
-                                                        Input::new(&self.rename_input)
-                                                            .appearance(false),
-                                                    ),
-                                                )
-                                                .child(
-                                                    div()
-                                                        .cursor_pointer()
-                                                        .child(
-                                                            Icon::new(IconName::Check)
-                                                                .size(px(14.0))
-                                                                .text_color(cx.theme().primary),
-                                                        )
-                                                        .on_mouse_down(
-                                                            MouseButton::Left,
-                                                            cx.listener(
-                                                                move |this, _, window, cx| {
-                                                                    cx.stop_propagation();
-                                                                    this.confirm_renaming(
-                                                                        window, cx,
-                                                                    );
-                                                                },
-                                                            ),
-                                                        ),
-                                                )
-                                                .child(
-                                                    div()
-                                                        .cursor_pointer()
-                                                        .child(
-                                                            Icon::new(IconName::Close)
-                                                                .size(px(14.0))
-                                                                .text_color(hsla(
-                                                                    0.0, 0.6, 0.4, 1.0,
-                                                                )),
-                                                        )
-                                                        .on_mouse_down(
-                                                            MouseButton::Left,
-                                                            cx.listener(
-                                                                move |this, _, window, cx| {
-                                                                    cx.stop_propagation();
-                                                                    this.cancel_renaming(
-                                                                        window, cx,
-                                                                    );
-                                                                },
-                                                            ),
-                                                        ),
-                                                )
-                                        } else {
-                                            div()
-                                                .flex()
-                                                .items_center()
-                                                .gap_3()
-                                                .flex_1()
-                                                .child(
-                                                    Tag::new()
-                                                        .small()
-                                                        .bg(method_color.opacity(0.15))
-                                                        .text_color(method_color)
-                                                        .child(method_str),
-                                                )
-                                                .child(
-                                                    div()
-                                                        .text_sm()
-                                                        .overflow_hidden()
-                                                        .whitespace_nowrap()
-                                                        .text_ellipsis()
-                                                        .child(name),
-                                                )
-                                        })
-                                        .child(
-                                            div()
-                                                .flex()
-                                                .items_center()
-                                                .gap_1()
-                                                .invisible()
-                                                .group_hover("request-item", |s| s.visible())
-                                                .when(!is_renaming, |this| {
-                                                    this.child(
-                                                        div()
-                                                            .p_1()
-                                                            .rounded_sm()
-                                                            .hover(|s| s.bg(cx.theme().muted))
-                                                            .child(
-                                                                Icon::new(IconName::Settings)
-                                                                    .size(px(14.0))
-                                                                    .text_color(
-                                                                        cx.theme().muted_foreground,
-                                                                    ),
-                                                            )
-                                                            .on_mouse_down(
-                                                                MouseButton::Left,
-                                                                cx.listener(
-                                                                    move |this, _, window, cx| {
-                                                                        cx.stop_propagation();
-                                                                        this.start_renaming(
-                                                                            i, window, cx,
-                                                                        );
-                                                                    },
-                                                                ),
-                                                            ),
-                                                    )
-                                                    .child(
-                                                        div()
-                                                            .p_1()
-                                                            .rounded_sm()
-                                                            .hover(|s| {
-                                                                s.bg(hsla(0.0, 0.6, 0.4, 0.2))
-                                                            })
-                                                            .child(
-                                                                Icon::new(IconName::Delete)
-                                                                    .size(px(14.0))
-                                                                    .text_color(
-                                                                        cx.theme().muted_foreground,
-                                                                    ),
-                                                            )
-                                                            .on_mouse_down(
-                                                                MouseButton::Left,
-                                                                cx.listener(
-                                                                    move |this, _, window, cx| {
-                                                                        cx.stop_propagation();
-                                                                        this.delete_request(
-                                                                            i, window, cx,
-                                                                        );
-                                                                    },
-                                                                ),
-                                                            ),
-                                                    )
-                                                }),
-                                        ),
-                                )
-                        }))
-                        .into_any_element()
-                }
-            } else {
+                                .text_sm()
+                                .font_weight(FontWeight::MEDIUM)
+                                .child("No history"),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground.opacity(0.7))
+                                .child("Sent requests will show up here"),
+                        ),
+                )
+                .into_any_element();
+        }
+
+        div()
+            .id("history-list")
+            .flex_1()
+            .overflow_y_scrollbar()
+            .children(self.history.iter().enumerate().map(|(i, entry)| {
+                let method = entry.request.http_method();
+                let method_color = method.color();
+                let status_tag = match entry.status {
+                    Some(status) if status < 300 => {
+                        Tag::success().small().child(status.to_string())
+                    }
+                    Some(status) if status < 400 => {
+                        Tag::secondary().small().child(status.to_string())
+                    }
+                    Some(status) => Tag::danger().small().child(status.to_string()),
+                    None => Tag::danger().small().child("ERR"),
+                };
+
                 div()
-                    .id("git-panel")
-                    .size_full()
-                    .child(self.git_panel.clone())
-                    .into_any_element()
-            })
+                    .id(ElementId::Name(format!("history-{}", i).into()))
+                    .group("history-item")
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_3()
+                    .py(px(6.0))
+                    .cursor_pointer()
+                    .hover(|s| s.bg(cx.theme().muted.opacity(0.5)))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _, window, cx| {
+                            this.open_history_entry(i, window, cx);
+                        }),
+                    )
+                    .child(
+                        Tag::new()
+                            .small()
+                            .bg(method_color.opacity(0.15))
+                            .text_color(method_color)
+                            .child(method.as_str()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .overflow_hidden()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .overflow_hidden()
+                                    .whitespace_nowrap()
+                                    .text_ellipsis()
+                                    .child(entry.request.name.clone()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .overflow_hidden()
+                                    .whitespace_nowrap()
+                                    .text_ellipsis()
+                                    .child(format!(
+                                        "{} · {} ms",
+                                        history::relative_time(entry.timestamp),
+                                        entry.duration_ms
+                                    )),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .invisible()
+                            .group_hover("history-item", |s| s.visible())
+                            .child(
+                                div()
+                                    .id(ElementId::Name(format!("history-diff-{}", i).into()))
+                                    .p_1()
+                                    .rounded_sm()
+                                    .hover(|s| s.bg(cx.theme().muted))
+                                    .tooltip(|window, cx| {
+                                        Tooltip::new("Diff Response").build(window, cx)
+                                    })
+                                    .child(
+                                        Icon::new(IconName::Replace)
+                                            .size(px(14.0))
+                                            .text_color(cx.theme().muted_foreground),
+                                    )
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(move |this, _, _, cx| {
+                                            cx.stop_propagation();
+                                            this.diff_history_entry(i, cx);
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                div()
+                                    .id(ElementId::Name(format!("history-resend-{}", i).into()))
+                                    .p_1()
+                                    .rounded_sm()
+                                    .hover(|s| s.bg(cx.theme().muted))
+                                    .tooltip(|window, cx| {
+                                        Tooltip::new("Send Again").build(window, cx)
+                                    })
+                                    .child(
+                                        Icon::new(IconName::Redo)
+                                            .size(px(14.0))
+                                            .text_color(cx.theme().muted_foreground),
+                                    )
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(move |this, _, window, cx| {
+                                            cx.stop_propagation();
+                                            this.resend_history_entry(i, window, cx);
+                                        }),
+                                    ),
+                            ),
+                    )
+                    .child(status_tag)
+            }))
+            .into_any_element()
     }
 
     fn render_title_bar(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
@@ -962,6 +2889,15 @@ impl App {
                             "No folder opened".to_string()
                         } else {
                             format!("Workspace: {}", folder_name)
+                        })
+                        .when(self.current_folder.is_some(), |this| {
+                            this.child(
+                                div()
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| {
+                                        cx.stop_propagation()
+                                    })
+                                    .child(self.render_environment_picker(cx)),
+                            )
                         }),
                 )
                 // Right Section: Theme Toggle + Version
@@ -1056,18 +2992,213 @@ impl App {
                                     Icon::new(if cx.theme().mode.is_dark() {
                                         IconName::Sun
                                     } else {
-                                        IconName::Moon
+                                        IconName::Moon
+                                    })
+                                    .text_color(cx.theme().muted_foreground),
+                                ),
+                        )
+                        .child(Badge::new().child("v0.1.0")),
+                ),
+        )
+    }
+
+    fn render_environment_picker(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let active = self.environments.active.clone();
+        let names: Vec<String> = self
+            .environments
+            .environments
+            .iter()
+            .map(|e| e.name.clone())
+            .collect();
+
+        Button::new("environment-picker")
+            .ghost()
+            .small()
+            .icon(IconName::Globe)
+            .label(
+                active
+                    .clone()
+                    .unwrap_or_else(|| "No Environment".to_string()),
+            )
+            .dropdown_menu(move |menu, _, _| {
+                let mut menu = menu.item(
+                    PopupMenuItem::new("No Environment")
+                        .checked(active.is_none())
+                        .on_click({
+                            let view = view.clone();
+                            move |_, _, cx| {
+                                let _ = view.update(cx, |app, cx| {
+                                    app.select_environment(None, cx);
+                                });
+                            }
+                        }),
+                );
+                for name in &names {
+                    let view = view.clone();
+                    let selected = name.clone();
+                    menu = menu.item(
+                        PopupMenuItem::new(name.clone())
+                            .checked(active.as_ref() == Some(name))
+                            .on_click(move |_, _, cx| {
+                                let _ = view.update(cx, |app, cx| {
+                                    app.select_environment(Some(selected.clone()), cx);
+                                });
+                            }),
+                    );
+                }
+                let view = view.clone();
+                menu.separator()
+                    .item(
+                        PopupMenuItem::new("Edit Environments...").on_click(move |_, _, cx| {
+                            let _ = view.update(cx, |app, cx| {
+                                app.edit_environments(cx);
+                            });
+                        }),
+                    )
+            })
+    }
+
+    /// Strip of open requests above the request bar
+    fn render_editor_tabs(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let active_id = self.tab().id;
+        let active_pinned = self.tab().pinned;
+
+        TabBar::new("editor-tabs")
+            .small()
+            .selected_index(self.active_editor)
+            .on_click(cx.listener(|this, index: &usize, _, cx| {
+                this.active_editor = (*index).min(this.tabs.len() - 1);
+                cx.notify();
+            }))
+            .children(self.tabs.iter().map(|tab| {
+                let id = tab.id;
+                let title = tab.title(cx);
+                let dirty = tab.is_dirty(cx);
+                let method_color = tab.method.color();
+                let pinned = tab.pinned;
+
+                Tab::new()
+                    .prefix(
+                        div()
+                            .pl_2()
+                            .text_xs()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(method_color)
+                            .child(tab.method.as_str()),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .max_w(px(160.0))
+                            .child(
+                                div()
+                                    .overflow_hidden()
+                                    .whitespace_nowrap()
+                                    .text_ellipsis()
+                                    .child(title.clone()),
+                            )
+                            .when(dirty, |this| {
+                                this.child(div().text_color(cx.theme().warning).child("●"))
+                            }),
+                    )
+                    .suffix(
+                        div()
+                            .id(ElementId::Name(format!("close-tab-{}", id).into()))
+                            .mr_1()
+                            .p_0p5()
+                            .rounded_sm()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(cx.theme().muted))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _, window, cx| {
+                                    cx.stop_propagation();
+                                    if pinned {
+                                        this.toggle_pin(id, cx);
+                                    } else {
+                                        this.close_tab(id, window, cx);
+                                    }
+                                }),
+                            )
+                            .child(
+                                Icon::new(if pinned {
+                                    IconName::Star
+                                } else {
+                                    IconName::Close
+                                })
+                                .size(px(12.0))
+                                .text_color(cx.theme().muted_foreground),
+                            )
+                            .tooltip(move |window, cx| {
+                                Tooltip::new(if pinned { "Unpin" } else { "Close" })
+                                    .build(window, cx)
+                            }),
+                    )
+                    .on_drag(DraggedTab { id, title }, |dragged, _, _, cx| {
+                        cx.new(|_| dragged.clone())
+                    })
+                    .drag_over::<DraggedTab>(|style, _, _, cx| {
+                        style.bg(cx.theme().accent.opacity(0.15))
+                    })
+                    .on_drop(cx.listener(move |this, dragged: &DraggedTab, _, cx| {
+                        this.move_tab(dragged.id, id, cx);
+                    }))
+            }))
+            .suffix(
+                h_flex()
+                    .gap_1()
+                    .px_1()
+                    .child(
+                        Button::new("new-tab")
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::Plus)
+                            .tooltip("New Tab")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.new_tab(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("tab-actions")
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::Ellipsis)
+                            .dropdown_menu(move |menu, _, _| {
+                                let pin = view.clone();
+                                let close = view.clone();
+                                menu.item(
+                                    PopupMenuItem::new(if active_pinned {
+                                        "Unpin Tab"
+                                    } else {
+                                        "Pin Tab"
                                     })
-                                    .text_color(cx.theme().muted_foreground),
-                                ),
-                        )
-                        .child(Badge::new().child("v0.1.0")),
-                ),
-        )
+                                    .on_click(
+                                        move |_, _, cx| {
+                                            let _ = pin.update(cx, |app, cx| {
+                                                app.toggle_pin(active_id, cx);
+                                            });
+                                        },
+                                    ),
+                                )
+                                .item(
+                                    PopupMenuItem::new("Close Tab").on_click(
+                                        move |_, window, cx| {
+                                            let _ = close.update(cx, |app, cx| {
+                                                app.close_tab(active_id, window, cx);
+                                            });
+                                        },
+                                    ),
+                                )
+                            }),
+                    ),
+            )
     }
 
     fn render_request_bar(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let (method_bg, method_color, method_text) = match self.method {
+        let tab = self.tab();
+        let (method_bg, method_color, method_text) = match tab.method {
             HttpMethod::Get => (
                 hsla(0.35, 0.6, 0.15, 1.0),
                 hsla(0.35, 0.8, 0.65, 1.0),
@@ -1082,11 +3213,12 @@ impl App {
             ),
             HttpMethod::Patch => (hsla(0.5, 0.6, 0.15, 1.0), hsla(0.5, 0.8, 0.65, 1.0), "PTCH"),
         };
-        let method_text = if self.method == HttpMethod::Delete {
+        let method_text = if tab.method == HttpMethod::Delete {
             "DEL"
         } else {
             method_text
         };
+        let unresolved = self.unresolved_variables(cx);
 
         div()
             .flex()
@@ -1116,7 +3248,18 @@ impl App {
                             .bg(cx.theme().input)
                             .border_1()
                             .border_color(cx.theme().border)
-                            .child(Input::new(&self.name_input).appearance(false)),
+                            .child(Input::new(&tab.name_input).appearance(false)),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_3()
+                            .py_1()
+                            .rounded(px(8.0))
+                            .bg(cx.theme().input)
+                            .border_1()
+                            .border_color(cx.theme().border)
+                            .child(Input::new(&tab.description_input).appearance(false)),
                     ),
             )
             // Row 2: Request Details
@@ -1149,7 +3292,7 @@ impl App {
                             .border_1()
                             .border_color(method_color.opacity(0.3))
                             .on_click(cx.listener(|this, _, _, cx| {
-                                this.method = this.method.next();
+                                this.tab_mut().method = this.tab().method.next();
                                 cx.notify();
                             })),
                     )
@@ -1163,7 +3306,7 @@ impl App {
                             .border_1()
                             .border_color(cx.theme().border)
                             .child(
-                                Input::new(&self.url_input).appearance(false).prefix(
+                                Input::new(&tab.url_input).appearance(false).prefix(
                                     Icon::new(IconName::Globe)
                                         .small()
                                         .text_color(cx.theme().muted_foreground),
@@ -1193,23 +3336,148 @@ impl App {
                                         this.save_new_request(window, cx);
                                     })),
                             )
-                            .child(
+                            .child({
+                                let view = cx.entity().downgrade();
+                                Button::new("import")
+                                    .icon(IconName::ArrowDown)
+                                    .label("Import")
+                                    .ghost()
+                                    .dropdown_menu(move |menu, _, _| {
+                                        let paste = view.clone();
+                                        let save = view.clone();
+                                        let postman = view.clone();
+                                        let openapi = view.clone();
+                                        menu.item(PopupMenuItem::new("Paste cURL").on_click(
+                                            move |_, window, cx| {
+                                                let _ = paste.update(cx, |app, cx| {
+                                                    app.import_curl(false, window, cx);
+                                                });
+                                            },
+                                        ))
+                                        .item(
+                                            PopupMenuItem::new("Save cURL as New Request")
+                                                .on_click(move |_, window, cx| {
+                                                    let _ = save.update(cx, |app, cx| {
+                                                        app.import_curl(true, window, cx);
+                                                    });
+                                                }),
+                                        )
+                                        .separator()
+                                        .item(PopupMenuItem::new("Postman Collection...").on_click(
+                                            move |_, window, cx| {
+                                                let _ = postman.update(cx, |app, cx| {
+                                                    app.import_file(
+                                                        "Import Postman Collection",
+                                                        &["json"],
+                                                        postman::import,
+                                                        window,
+                                                        cx,
+                                                    );
+                                                });
+                                            },
+                                        ))
+                                        .item(
+                                            PopupMenuItem::new("OpenAPI / Swagger...").on_click(
+                                                move |_, window, cx| {
+                                                    let _ = openapi.update(cx, |app, cx| {
+                                                        app.import_file(
+                                                            "Import OpenAPI Document",
+                                                            &["json", "yaml", "yml"],
+                                                            openapi::import,
+                                                            window,
+                                                            cx,
+                                                        );
+                                                    });
+                                                },
+                                            ),
+                                        )
+                                    })
+                            })
+                            .child({
+                                let view = cx.entity().downgrade();
+                                Button::new("copy-as-code")
+                                    .icon(IconName::Copy)
+                                    .label("Copy as")
+                                    .ghost()
+                                    .dropdown_menu(move |mut menu, _, _| {
+                                        for target in CodeTarget::ALL {
+                                            let view = view.clone();
+                                            menu = menu.item(
+                                                PopupMenuItem::new(target.label()).on_click(
+                                                    move |_, _, cx| {
+                                                        let _ = view.update(cx, |app, cx| {
+                                                            app.copy_as_code(target, cx);
+                                                        });
+                                                    },
+                                                ),
+                                            );
+                                        }
+                                        menu
+                                    })
+                            })
+                            .child(if tab.is_loading {
+                                Button::new("cancel")
+                                    .danger()
+                                    .icon(IconName::Close)
+                                    .label("Cancel")
+                                    .tooltip("Cancel (Esc)")
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.cancel_request(cx);
+                                    }))
+                            } else {
                                 Button::new("send")
                                     .primary()
                                     .icon(IconName::ArrowRight)
                                     .label("Send")
-                                    .loading(self.is_loading)
                                     .on_click(cx.listener(|this, _, window, cx| {
                                         this.send_request(window, cx);
-                                    })),
-                            ),
+                                    }))
+                            })
+                            .when(!tab.is_loading, |this| {
+                                this.child(
+                                    Button::new("send-download")
+                                        .outline()
+                                        .icon(IconName::ArrowDown)
+                                        .tooltip("Send and Download to File")
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.send_and_download(window, cx);
+                                        })),
+                                )
+                            }),
                     ),
             )
+            // Row 3: Unresolved environment variables
+            .when(!unresolved.is_empty(), |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_wrap()
+                        .items_center()
+                        .gap_2()
+                        .child(
+                            Icon::new(IconName::TriangleAlert)
+                                .size(px(14.0))
+                                .text_color(hsla(0.12, 0.7, 0.5, 1.0)),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("Unresolved variables:"),
+                        )
+                        .children(
+                            unresolved.into_iter().map(|name| {
+                                Tag::warning().small().child(format!("{{{{{}}}}}", name))
+                            }),
+                        ),
+                )
+            })
     }
 
     fn render_tabs(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let active_tab = self.active_tab.clone();
+        let active_tab = self.tab().active_tab.clone();
         let param_count = self
+            .tab()
             .params
             .iter()
             .filter(|p| {
@@ -1218,6 +3486,7 @@ impl App {
             })
             .count();
         let header_count = self
+            .tab()
             .headers
             .iter()
             .filter(|h| {
@@ -1225,6 +3494,13 @@ impl App {
                 !key.is_empty()
             })
             .count();
+        let has_auth = self.tab().auth_editor.read(cx).kind != AuthKind::None;
+        let has_settings = !self
+            .tab()
+            .settings_editor
+            .read(cx)
+            .settings(cx)
+            .is_default();
 
         div()
             .flex()
@@ -1241,12 +3517,16 @@ impl App {
                         RequestTab::Params => 0,
                         RequestTab::Headers => 1,
                         RequestTab::Body => 2,
+                        RequestTab::Auth => 3,
+                        RequestTab::Settings => 4,
                     })
                     .on_click(cx.listener(|this, index, _, cx| {
-                        this.active_tab = match index {
+                        this.tab_mut().active_tab = match index {
                             0 => RequestTab::Params,
                             1 => RequestTab::Headers,
-                            _ => RequestTab::Body,
+                            2 => RequestTab::Body,
+                            3 => RequestTab::Auth,
+                            _ => RequestTab::Settings,
                         };
                         cx.notify();
                     }))
@@ -1300,6 +3580,40 @@ impl App {
                                 .child(Icon::new(IconName::File).size(px(14.0)))
                                 .child("Body"),
                         ),
+                    )
+                    .child(
+                        Tab::new().child(
+                            h_flex()
+                                .items_center()
+                                .gap_2()
+                                .child(Icon::new(IconName::User).size(px(14.0)))
+                                .child("Auth")
+                                .when(has_auth, |this| {
+                                    this.child(
+                                        div()
+                                            .size(px(6.0))
+                                            .rounded_full()
+                                            .bg(cx.theme().accent_foreground),
+                                    )
+                                }),
+                        ),
+                    )
+                    .child(
+                        Tab::new().child(
+                            h_flex()
+                                .items_center()
+                                .gap_2()
+                                .child(Icon::new(IconName::Inspector).size(px(14.0)))
+                                .child("Settings")
+                                .when(has_settings, |this| {
+                                    this.child(
+                                        div()
+                                            .size(px(6.0))
+                                            .rounded_full()
+                                            .bg(cx.theme().accent_foreground),
+                                    )
+                                }),
+                        ),
                     ),
             )
     }
@@ -1308,17 +3622,13 @@ impl App {
         &self,
         index: usize,
         pair: &KeyValuePair,
-        is_param: bool,
+        list: KvList,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        let id = if is_param {
-            format!("param-{}", index)
-        } else {
-            format!("header-{}", index)
-        };
+        let prefix = list.id_prefix();
 
         div()
-            .id(ElementId::Name(id.into()))
+            .id(ElementId::Name(format!("{}-{}", prefix, index).into()))
             .flex()
             .items_center()
             .gap_3()
@@ -1328,6 +3638,18 @@ impl App {
             .bg(cx.theme().muted)
             .border_1()
             .border_color(cx.theme().border)
+            .child(
+                Checkbox::new(ElementId::Name(
+                    format!("enable-{}-{}", prefix, index).into(),
+                ))
+                .checked(pair.enabled)
+                .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                    if let Some(pair) = this.tab_mut().kv_list_mut(list).get_mut(index) {
+                        pair.enabled = *checked;
+                    }
+                    cx.notify();
+                })),
+            )
             .child(
                 div()
                     .flex_1()
@@ -1341,24 +3663,14 @@ impl App {
             )
             .child(
                 Button::new(ElementId::Name(
-                    format!(
-                        "delete-{}-{}",
-                        if is_param { "param" } else { "header" },
-                        index
-                    )
-                    .into(),
+                    format!("delete-{}-{}", prefix, index).into(),
                 ))
                 .icon(IconName::Delete)
                 .ghost()
                 .on_click(cx.listener(move |this, _, _, cx| {
-                    if is_param {
-                        if this.params.len() > 1 {
-                            this.params.remove(index);
-                        }
-                    } else {
-                        if this.headers.len() > 1 {
-                            this.headers.remove(index);
-                        }
+                    let pairs = this.tab_mut().kv_list_mut(list);
+                    if pairs.len() > 1 {
+                        pairs.remove(index);
                     }
                     cx.notify();
                 })),
@@ -1370,13 +3682,14 @@ impl App {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        let content = match self.active_tab {
+        let tab = self.tab();
+        let content = match tab.active_tab {
             RequestTab::Params => {
-                let rows: Vec<_> = self
+                let rows: Vec<_> = tab
                     .params
                     .iter()
                     .enumerate()
-                    .map(|(i, pair)| self.render_kv_row(i, pair, true, cx))
+                    .map(|(i, pair)| self.render_kv_row(i, pair, KvList::Params, cx))
                     .collect();
 
                 div()
@@ -1408,6 +3721,7 @@ impl App {
                             .gap_3()
                             .mb_2()
                             .px_2()
+                            .child(div().w(px(16.0)))
                             .child(
                                 div()
                                     .flex_1()
@@ -1448,11 +3762,11 @@ impl App {
                     .into_any_element()
             }
             RequestTab::Headers => {
-                let rows: Vec<_> = self
+                let rows: Vec<_> = tab
                     .headers
                     .iter()
                     .enumerate()
-                    .map(|(i, pair)| self.render_kv_row(i, pair, false, cx))
+                    .map(|(i, pair)| self.render_kv_row(i, pair, KvList::Headers, cx))
                     .collect();
 
                 div()
@@ -1464,17 +3778,32 @@ impl App {
                         div()
                             .flex()
                             .items_center()
-                            .gap_2()
+                            .justify_between()
                             .mb_4()
                             .child(
-                                Icon::new(IconName::Settings)
-                                    .text_color(cx.theme().muted_foreground),
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .child(
+                                        Icon::new(IconName::Settings)
+                                            .text_color(cx.theme().muted_foreground),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child("HTTP headers to include in the request"),
+                                    ),
                             )
                             .child(
-                                div()
-                                    .text_xs()
-                                    .text_color(cx.theme().muted_foreground)
-                                    .child("HTTP headers to include in the request"),
+                                Checkbox::new("use-cookie-jar")
+                                    .label("Use cookie jar")
+                                    .checked(tab.use_cookie_jar)
+                                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                        this.tab_mut().use_cookie_jar = *checked;
+                                        cx.notify();
+                                    })),
                             ),
                     )
                     // Column headers
@@ -1485,6 +3814,7 @@ impl App {
                             .gap_3()
                             .mb_2()
                             .px_2()
+                            .child(div().w(px(16.0)))
                             .child(
                                 div()
                                     .flex_1()
@@ -1524,37 +3854,304 @@ impl App {
                     )
                     .into_any_element()
             }
-            RequestTab::Body => div()
+            RequestTab::Body => {
+                let view = cx.entity().downgrade();
+                let body_mode = tab.body_mode;
+                let form_rows: Vec<_> = if body_mode.is_form() {
+                    self.tab()
+                        .form_fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, pair)| self.render_kv_row(i, pair, KvList::Form, cx))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+
+                div()
+                    .size_full()
+                    .flex()
+                    .flex_col()
+                    .pb_4()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .mb_4()
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .child(
+                                        Icon::new(IconName::File)
+                                            .text_color(cx.theme().muted_foreground),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child("Request body for POST, PUT, PATCH requests"),
+                                    ),
+                            )
+                            .child(
+                                Button::new("body-mode")
+                                    .outline()
+                                    .small()
+                                    .label(body_mode.label())
+                                    .dropdown_menu(move |menu, _, _| {
+                                        let mut menu = menu;
+                                        for mode in BodyMode::ALL {
+                                            let view = view.clone();
+                                            menu = menu.item(
+                                                PopupMenuItem::new(mode.label())
+                                                    .checked(mode == body_mode)
+                                                    .on_click(move |_, _, cx| {
+                                                        let _ = view.update(cx, |app, cx| {
+                                                            app.tab_mut().body_mode = mode;
+                                                            cx.notify();
+                                                        });
+                                                    }),
+                                            );
+                                        }
+                                        menu
+                                    }),
+                            ),
+                    )
+                    .child(match body_mode {
+                        BodyMode::None => div()
+                            .flex_1()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("This request does not have a body")
+                            .into_any_element(),
+                        BodyMode::FormUrlEncoded | BodyMode::Multipart => div()
+                            .flex()
+                            .flex_col()
+                            .children(form_rows)
+                            .when(body_mode == BodyMode::Multipart, |this| {
+                                this.child(
+                                    div()
+                                        .mb_2()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child("Prefix a value with @ to upload a file"),
+                                )
+                            })
+                            .child(
+                                div().mb_4().child(
+                                    Button::new("add-form-field")
+                                        .icon(IconName::Plus)
+                                        .label("Add Field")
+                                        .outline()
+                                        .w_full()
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.add_form_field(window, cx);
+                                        })),
+                                ),
+                            )
+                            .into_any_element(),
+                        BodyMode::Json | BodyMode::Text | BodyMode::Xml => div()
+                            .flex_1()
+                            .p_3()
+                            .mb_4()
+                            .rounded(px(8.0))
+                            .bg(cx.theme().muted)
+                            .border_1()
+                            .border_color(cx.theme().border)
+                            .child(Input::new(&tab.body_input).appearance(false))
+                            .into_any_element(),
+                    })
+                    .into_any_element()
+            }
+            RequestTab::Auth => div()
                 .size_full()
-                .flex()
-                .flex_col()
-                .pb_4()
-                .child(
-                    div()
-                        .flex()
-                        .items_center()
-                        .gap_2()
-                        .mb_4()
-                        .child(Icon::new(IconName::File).text_color(cx.theme().muted_foreground))
-                        .child(
-                            div()
-                                .text_xs()
-                                .text_color(cx.theme().muted_foreground)
-                                .child("Request body for POST, PUT, PATCH requests"),
-                        ),
-                )
-                .child(
-                    div()
-                        .flex_1()
-                        .p_3()
-                        .mb_4()
-                        .rounded(px(8.0))
-                        .bg(cx.theme().muted)
-                        .border_1()
-                        .border_color(cx.theme().border)
-                        .child(Input::new(&self.body_input).appearance(false)),
-                )
+                .child(tab.auth_editor.clone())
                 .into_any_element(),
+            RequestTab::Settings => {
+                let scope = self.settings_scope;
+                let scopes: Vec<SettingsScope> = [
+                    SettingsScope::Request,
+                    SettingsScope::FolderDefaults,
+                    SettingsScope::Proxy,
+                    SettingsScope::Tls,
+                ]
+                .into_iter()
+                .filter(|s| *s != SettingsScope::FolderDefaults || self.current_folder.is_some())
+                .collect();
+                let hint = match scope {
+                    SettingsScope::Request => {
+                        "Timeouts, redirects and HTTP version for this request"
+                    }
+                    SettingsScope::FolderDefaults => {
+                        "Used by requests in this folder that leave a setting empty"
+                    }
+                    SettingsScope::Proxy => {
+                        "Proxy for all requests, or while an environment is active"
+                    }
+                    SettingsScope::Tls => {
+                        "CA bundles, client certificates and verification per host"
+                    }
+                };
+
+                div()
+                    .size_full()
+                    .flex()
+                    .flex_col()
+                    .pb_4()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .mb_4()
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .child(
+                                        Icon::new(IconName::Inspector)
+                                            .text_color(cx.theme().muted_foreground),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child(hint),
+                                    ),
+                            )
+                            .child(
+                                TabBar::new("settings-scope")
+                                    .pill()
+                                    .small()
+                                    .selected_index(
+                                        scopes.iter().position(|s| *s == scope).unwrap_or(0),
+                                    )
+                                    .on_click({
+                                        let scopes = scopes.clone();
+                                        cx.listener(move |this, index: &usize, window, cx| {
+                                            if let Some(scope) = scopes.get(*index) {
+                                                this.set_settings_scope(*scope, window, cx);
+                                            }
+                                        })
+                                    })
+                                    .children(scopes.iter().map(|s| Tab::new().child(s.label()))),
+                            ),
+                    )
+                    .child(match scope {
+                        SettingsScope::Request => tab.settings_editor.clone().into_any_element(),
+                        SettingsScope::FolderDefaults => div()
+                            .child(self.folder_defaults_editor.clone())
+                            .child(
+                                div().mt_4().child(
+                                    Button::new("save-folder-defaults")
+                                        .outline()
+                                        .small()
+                                        .label("Save Folder Defaults")
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.save_folder_defaults(cx);
+                                        })),
+                                ),
+                            )
+                            .into_any_element(),
+                        SettingsScope::Proxy => {
+                            let view = cx.entity().downgrade();
+                            let target = self.proxy_target.clone();
+                            let names: Vec<String> = self
+                                .environments
+                                .environments
+                                .iter()
+                                .map(|e| e.name.clone())
+                                .collect();
+                            let target_label = |target: &Option<String>| match target {
+                                Some(name) => format!("Environment: {}", name),
+                                None => "All Requests".to_string(),
+                            };
+
+                            div()
+                                .child(
+                                    div()
+                                        .flex()
+                                        .items_center()
+                                        .gap_3()
+                                        .mb_2()
+                                        .child(
+                                            div()
+                                                .w(px(96.0))
+                                                .flex_shrink_0()
+                                                .text_xs()
+                                                .font_weight(FontWeight::SEMIBOLD)
+                                                .text_color(cx.theme().muted_foreground)
+                                                .child("Applies To"),
+                                        )
+                                        .child(
+                                            Button::new("proxy-target")
+                                                .outline()
+                                                .small()
+                                                .label(target_label(&target))
+                                                .dropdown_menu(move |menu, _, _| {
+                                                    let mut menu = menu;
+                                                    let options = std::iter::once(None)
+                                                        .chain(names.iter().cloned().map(Some));
+                                                    for option in options {
+                                                        let view = view.clone();
+                                                        menu = menu.item(
+                                                            PopupMenuItem::new(target_label(
+                                                                &option,
+                                                            ))
+                                                            .checked(option == target)
+                                                            .on_click(move |_, window, cx| {
+                                                                let _ =
+                                                                    view.update(cx, |app, cx| {
+                                                                        app.edit_proxy(
+                                                                            option.clone(),
+                                                                            window,
+                                                                            cx,
+                                                                        );
+                                                                    });
+                                                            }),
+                                                        );
+                                                    }
+                                                    menu
+                                                }),
+                                        ),
+                                )
+                                .child(self.proxy_editor.clone())
+                                .child(
+                                    div().mt_4().child(
+                                        Button::new("save-proxy")
+                                            .outline()
+                                            .small()
+                                            .label("Save Proxy Settings")
+                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                this.save_proxy(cx);
+                                            })),
+                                    ),
+                                )
+                                .into_any_element()
+                        }
+                        SettingsScope::Tls => div()
+                            .child(self.tls_editor.clone())
+                            .child(
+                                div().mt_4().child(
+                                    Button::new("save-tls")
+                                        .outline()
+                                        .small()
+                                        .label("Save TLS Settings")
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.save_tls(cx);
+                                        })),
+                                ),
+                            )
+                            .into_any_element(),
+                    })
+                    .into_any_element()
+            }
         };
 
         div().flex_1().p_4().bg(cx.theme().muted).child(content)
@@ -1565,10 +4162,18 @@ impl App {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        let has_response = !self.response_body.is_empty();
-        let response_too_large = self.response_is_large;
-        let status_badge = if let Some((code, text)) = &self.response_status {
-            let (bg_color, text_color, icon) = if *code >= 200 && *code < 300 {
+        let tab = self.tab();
+        let has_response = !tab.response_body.is_empty();
+        let response_too_large = tab.response_is_large;
+        let header_count = tab.response_headers.len();
+        let status_badge = if let Some((code, text)) = &tab.response_status {
+            let (bg_color, text_color, icon) = if tab.response_cancelled {
+                (
+                    cx.theme().muted,
+                    cx.theme().muted_foreground,
+                    IconName::CircleX,
+                )
+            } else if *code >= 200 && *code < 300 {
                 (
                     hsla(0.35, 0.6, 0.25, 1.0),
                     hsla(0.35, 0.8, 0.65, 1.0),
@@ -1613,25 +4218,101 @@ impl App {
                                 .text_xs()
                                 .font_weight(FontWeight::BOLD)
                                 .text_color(text_color)
-                                .child(format!("{} {}", code, text)),
+                                .child(if tab.response_cancelled {
+                                    text.clone()
+                                } else {
+                                    format!("{} {}", code, text)
+                                }),
                         ),
                 )
-                .when(self.response_time.is_some(), |this| {
+                .when_some(tab.response_time, |this, time| {
+                    match tab.response_timings.clone() {
+                        // Entries recorded before timings were kept only have the total
+                        None => this.child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap_1()
+                                .px_2()
+                                .py_1()
+                                .rounded(px(6.0))
+                                .bg(cx.theme().muted)
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(format!("{}ms", time)),
+                                ),
+                        ),
+                        Some(timings) => this.child(
+                            Popover::new("response-timings")
+                                .trigger(
+                                    Button::new("response-time")
+                                        .outline()
+                                        .xsmall()
+                                        .icon(IconName::ChartPie)
+                                        .label(format!("{}ms", time)),
+                                )
+                                .content(move |_, _, cx| render_timings(&timings, cx)),
+                        ),
+                    }
+                })
+                .when(tab.response_truncated, |this| {
+                    let color = hsla(0.12, 0.8, 0.65, 1.0);
                     this.child(
                         div()
+                            .id("response-truncated")
                             .flex()
                             .items_center()
                             .gap_1()
                             .px_2()
                             .py_1()
                             .rounded(px(6.0))
-                            .bg(cx.theme().muted)
+                            .bg(hsla(0.12, 0.6, 0.25, 1.0))
+                            .child(Icon::new(IconName::TriangleAlert).text_color(color))
                             .child(
                                 div()
                                     .text_xs()
-                                    .text_color(cx.theme().muted_foreground)
-                                    .child(format!("{}ms", self.response_time.unwrap_or(0))),
-                            ),
+                                    .font_weight(FontWeight::BOLD)
+                                    .text_color(color)
+                                    .child("Truncated"),
+                            )
+                            .tooltip(|window, cx| {
+                                Tooltip::new(
+                                    "The body passed the maximum download size; raise it in \
+                                     the Settings tab or download to a file",
+                                )
+                                .build(window, cx)
+                            }),
+                    )
+                })
+                .when(tab.response_unverified_tls, |this| {
+                    let color = hsla(0.12, 0.8, 0.65, 1.0);
+                    this.child(
+                        div()
+                            .id("unverified-tls")
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .px_2()
+                            .py_1()
+                            .rounded(px(6.0))
+                            .bg(hsla(0.12, 0.6, 0.25, 1.0))
+                            .child(Icon::new(IconName::TriangleAlert).text_color(color))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .font_weight(FontWeight::BOLD)
+                                    .text_color(color)
+                                    .child("TLS Unverified"),
+                            )
+                            .tooltip(|window, cx| {
+                                Tooltip::new(
+                                    "Certificate verification is off for this host; \
+                                     the server's identity was not checked",
+                                )
+                                .build(window, cx)
+                            }),
                     )
                 })
                 .into_any_element()
@@ -1642,7 +4323,8 @@ impl App {
         let response_lines: Vec<_> = if response_too_large {
             Vec::new()
         } else {
-            self.response_body
+            self.tab()
+                .response_body
                 .lines()
                 .enumerate()
                 .map(|(i, line)| {
@@ -1690,6 +4372,64 @@ impl App {
                                     .font_weight(FontWeight::SEMIBOLD)
                                     .text_color(cx.theme().foreground)
                                     .child("Response"),
+                            )
+                            .child(
+                                TabBar::new("response-tabs")
+                                    .pill()
+                                    .small()
+                                    .selected_index(match tab.response_tab {
+                                        ResponseTab::Body => 0,
+                                        ResponseTab::Headers => 1,
+                                        ResponseTab::Tests => 2,
+                                    })
+                                    .on_click(cx.listener(|this, index, _, cx| {
+                                        this.tab_mut().response_tab = match index {
+                                            0 => ResponseTab::Body,
+                                            1 => ResponseTab::Headers,
+                                            _ => ResponseTab::Tests,
+                                        };
+                                        cx.notify();
+                                    }))
+                                    .child(Tab::new().child("Body"))
+                                    .child(Tab::new().child(
+                                        h_flex().items_center().gap_2().child("Headers").when(
+                                            header_count > 0,
+                                            |this| {
+                                                this.child(
+                                                    div()
+                                                        .px_1()
+                                                        .py_0p5()
+                                                        .text_xs()
+                                                        .bg(cx.theme().accent)
+                                                        .text_color(cx.theme().accent_foreground)
+                                                        .rounded_sm()
+                                                        .child(format!("{}", header_count)),
+                                                )
+                                            },
+                                        ),
+                                    ))
+                                    .child(Tab::new().child(
+                                        h_flex().items_center().gap_2().child("Tests").when(
+                                            !tab.assertion_results.is_empty(),
+                                            |this| {
+                                                let failed = tab
+                                                    .assertion_results
+                                                    .iter()
+                                                    .filter(|r| !r.passed)
+                                                    .count();
+                                                let passed = tab.assertion_results.len() - failed;
+                                                this.child(if failed > 0 {
+                                                    Tag::danger()
+                                                        .small()
+                                                        .child(format!("{} failed", failed))
+                                                } else {
+                                                    Tag::success()
+                                                        .small()
+                                                        .child(format!("{} passed", passed))
+                                                })
+                                            },
+                                        ),
+                                    )),
                             ),
                     )
                     .child(
@@ -1719,10 +4459,26 @@ impl App {
                                         })),
                                 )
                             })
+                            .when_some(tab.response_progress, |this, received| {
+                                this.child(
+                                    div()
+                                        .px_2()
+                                        .py_1()
+                                        .rounded(px(6.0))
+                                        .bg(cx.theme().muted)
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(format!(
+                                            "{} received",
+                                            format_size(received as usize)
+                                        )),
+                                )
+                            })
                             .child(status_badge),
                     ),
             )
-            .child(if self.is_loading {
+            // A body streaming in is shown as it arrives, unless it is too large
+            .child(if tab.is_loading && (!has_response || response_too_large) {
                 // Show loading spinner while request is in progress
                 div()
                     .id("response-loading")
@@ -1738,11 +4494,62 @@ impl App {
                         div()
                             .text_sm()
                             .text_color(cx.theme().muted_foreground)
-                            .child("Sending request..."),
+                            .child(match tab.response_progress {
+                                Some(received) => {
+                                    format!("Receiving... {}", format_size(received as usize))
+                                }
+                                None => "Sending request...".to_string(),
+                            }),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Press Esc to cancel"),
+                    )
+                    .into_any_element()
+            } else if let Some((title, lines)) = &tab.history_diff {
+                self.render_history_diff(title, lines, cx)
+                    .into_any_element()
+            } else if tab.response_tab == ResponseTab::Headers && tab.response_status.is_some() {
+                self.render_response_headers(cx).into_any_element()
+            } else if tab.response_tab == ResponseTab::Tests && tab.response_status.is_some() {
+                self.render_assertion_results(cx).into_any_element()
+            } else if let Some((path, size)) = &tab.response_file {
+                let path = path.clone();
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .justify_center()
+                    .gap_2()
+                    .p_4()
+                    .bg(cx.theme().muted)
+                    .child(Icon::new(IconName::ArrowDown).text_color(cx.theme().muted_foreground))
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().foreground)
+                            .child(format!("Saved {} to file", format_size(*size as usize))),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(path.display().to_string()),
+                    )
+                    .child(
+                        Button::new("open-download")
+                            .outline()
+                            .small()
+                            .label("Open")
+                            .on_click(move |_, _, cx| cx.open_with_system(&path)),
                     )
                     .into_any_element()
             } else if response_too_large {
-                let response_size = format_size(self.response_body.len());
+                let response_size = format_size(tab.response_body.len());
                 div()
                     .id("response-scroll")
                     .flex_1()
@@ -1768,7 +4575,7 @@ impl App {
                             .child(format!("Size: {}", response_size)),
                     )
                     .into_any_element()
-            } else if !has_response && self.response_status.is_none() {
+            } else if !has_response && tab.response_status.is_none() {
                 div()
                     .flex_1()
                     .flex()
@@ -1821,8 +4628,178 @@ impl App {
                     .children(response_lines)
                     .into_any_element()
             })
-            .child(Scrollbar::vertical(&self.scroll_handle))
+            .child(Scrollbar::vertical(&tab.scroll_handle))
+    }
+
+    fn render_history_diff(
+        &self,
+        title: &str,
+        lines: &[DiffLine],
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let added = hsla(0.35, 0.8, 0.45, 1.0);
+        let removed = hsla(0.0, 0.8, 0.50, 1.0);
+
+        div()
+            .id("history-diff-scroll")
+            .flex_1()
+            .overflow_y_scrollbar()
+            .p_4()
+            .bg(cx.theme().muted)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .mb_2()
+                    .child(
+                        div()
+                            .text_xs()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("Diff: {}", title)),
+                    )
+                    .child(
+                        Button::new("close-diff")
+                            .icon(IconName::Close)
+                            .ghost()
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.tab_mut().history_diff = None;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .children(lines.iter().enumerate().map(|(i, line)| {
+                let (prefix, text, color, bg) = match line {
+                    DiffLine::Same(text) => (" ", text, cx.theme().foreground, transparent_black()),
+                    DiffLine::Removed(text) => ("-", text, removed, removed.opacity(0.1)),
+                    DiffLine::Added(text) => ("+", text, added, added.opacity(0.1)),
+                };
+                div()
+                    .id(ElementId::Name(format!("diff-line-{}", i).into()))
+                    .text_xs()
+                    .font_family("monospace")
+                    .text_color(color)
+                    .bg(bg)
+                    .child(format!("{} {}", prefix, text))
+            }))
+    }
+
+    fn render_response_headers(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let tab = self.tab();
+        let meta = [
+            ("Version", tab.response_version.clone()),
+            (
+                "Remote Address",
+                tab.response_remote_addr.map(|addr| addr.to_string()),
+            ),
+        ];
+
+        div()
+            .id("response-headers-scroll")
+            .flex_1()
+            .overflow_y_scrollbar()
+            .p_4()
+            .bg(cx.theme().muted)
+            .children(meta.into_iter().filter_map(|(label, value)| {
+                value.map(|value| {
+                    div()
+                        .flex()
+                        .gap_3()
+                        .mb_1()
+                        .text_xs()
+                        .child(
+                            div()
+                                .w(px(140.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(cx.theme().muted_foreground)
+                                .child(label),
+                        )
+                        .child(div().text_color(cx.theme().foreground).child(value))
+                })
+            }))
+            .child(div().my_2().child(Divider::horizontal()))
+            .children(
+                self.tab()
+                    .response_headers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (name, value))| {
+                        div()
+                            .id(ElementId::Name(format!("response-header-{}", i).into()))
+                            .flex()
+                            .gap_3()
+                            .py_1()
+                            .text_xs()
+                            .font_family("monospace")
+                            .child(
+                                div()
+                                    .w(px(220.0))
+                                    .flex_shrink_0()
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(cx.theme().primary)
+                                    .child(name.clone()),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_color(cx.theme().foreground)
+                                    .child(value.clone()),
+                            )
+                    }),
+            )
+    }
+
+    fn render_assertion_results(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("response-tests-scroll")
+            .flex_1()
+            .overflow_y_scrollbar()
+            .p_4()
+            .bg(cx.theme().muted)
+            .when(self.tab().assertion_results.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("No assertions defined for this request"),
+                )
+            })
+            .children(
+                self.tab()
+                    .assertion_results
+                    .iter()
+                    .enumerate()
+                    .map(|(i, result)| {
+                        let (icon, color) = if result.passed {
+                            (IconName::Check, hsla(0.35, 0.8, 0.45, 1.0))
+                        } else {
+                            (IconName::Close, hsla(0.0, 0.8, 0.50, 1.0))
+                        };
+
+                        div()
+                            .id(ElementId::Name(format!("assertion-{}", i).into()))
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .py_1()
+                            .text_xs()
+                            .child(Icon::new(icon).size(px(14.0)).text_color(color))
+                            .child(
+                                div()
+                                    .text_color(cx.theme().foreground)
+                                    .child(result.description.clone()),
+                            )
+                            .child(
+                                div()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(result.message.clone()),
+                            )
+                    }),
+            )
     }
+
     fn render_status_bar(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let branch_name = self
             .current_branch
@@ -1871,29 +4848,148 @@ impl App {
                             .child(branch_name),
                     )
                     .child(Divider::vertical())
-                    .child(if self.is_loading {
+                    .child(if self.tab().is_loading {
                         "Sending request..."
                     } else {
                         "Ready"
                     }),
             )
-            .child(div().flex().items_center().gap_2().child("v0.1.0"))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .child(
+                        div()
+                            .id("reset-connections-btn")
+                            .cursor_pointer()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .hover(|s| s.text_color(cx.theme().foreground))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _, _, cx| {
+                                    this.reset_connections(cx);
+                                }),
+                            )
+                            .tooltip(|window, cx| {
+                                Tooltip::new(
+                                    "Close kept-alive connections so the next request starts cold",
+                                )
+                                .build(window, cx)
+                            })
+                            .child(Icon::new(IconName::Undo).size(px(14.0)))
+                            .child("Reset Connections"),
+                    )
+                    .child(Divider::vertical())
+                    .child("v0.1.0"),
+            )
             .into_any_element()
     }
 }
 
-/// Simple URL encoding helper
-fn urlencoding(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-            ' ' => "+".to_string(),
-            _ => format!("%{:02X}", c as u32),
-        })
-        .collect()
+const MAX_RESPONSE_DISPLAY_BYTES: usize = 100_000;
+
+/// How long to wait for more file changes before refreshing the sidebar
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Status text shown next to the code, falling back when the reason is unknown
+fn status_text(status: u16, reason: &str) -> String {
+    let text = if !reason.is_empty() {
+        reason
+    } else if (200..300).contains(&status) {
+        "OK"
+    } else if (400..500).contains(&status) {
+        "Client Error"
+    } else if status >= 500 {
+        "Server Error"
+    } else {
+        "Response"
+    };
+    text.to_string()
 }
 
-const MAX_RESPONSE_DISPLAY_BYTES: usize = 100_000;
+/// Pretty-print a JSON body, leaving anything else untouched
+fn pretty_json(body: String) -> String {
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(json) => serde_json::to_string_pretty(&json).unwrap_or(body),
+        Err(_) => body,
+    }
+}
+
+/// Waterfall of the phases of a response, with what was sent and received
+fn render_timings(timings: &Timings, cx: &gpui::App) -> Div {
+    let phases = timings.phases();
+    let total: f64 = phases.iter().map(|(_, ms)| ms).sum();
+    let mut offset = 0.0;
+
+    let rows = phases.into_iter().map(|(label, ms)| {
+        let (start, width) = if total > 0.0 {
+            (offset / total, ms / total)
+        } else {
+            (0.0, 0.0)
+        };
+        offset += ms;
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .child(
+                div()
+                    .w(px(130.0))
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(label),
+            )
+            .child(
+                div().w(px(200.0)).h(px(8.0)).relative().child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left(relative(start as f32))
+                        .w(relative(width as f32))
+                        .min_w(px(2.0))
+                        .h_full()
+                        .rounded(px(2.0))
+                        .bg(cx.theme().primary),
+                ),
+            )
+            .child(
+                div()
+                    .w(px(72.0))
+                    .text_xs()
+                    .text_right()
+                    .child(format!("{:.1} ms", ms)),
+            )
+    });
+    let reused = timings.connect_ms.is_none();
+
+    v_flex()
+        .gap_2()
+        .p_1()
+        .child(
+            div()
+                .text_xs()
+                .font_weight(FontWeight::SEMIBOLD)
+                .child("Timing"),
+        )
+        .children(rows)
+        .child(Divider::horizontal())
+        .child(
+            div()
+                .flex()
+                .justify_between()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(format!(
+                    "Sent {} · Received {}",
+                    format_size(timings.sent_bytes as usize),
+                    format_size(timings.received_bytes as usize)
+                ))
+                .when(reused, |this| this.child("Reused connection")),
+        )
+}
 
 fn format_size(bytes: usize) -> String {
     const KB: f64 = 1024.0;
@@ -1922,12 +5018,18 @@ impl Render for App {
             .on_action(cx.listener(|this, _: &SendRequest, window, cx| {
                 this.send_request(window, cx);
             }))
+            .on_action(cx.listener(|this, _: &CancelRequest, _, cx| {
+                this.cancel_request(cx);
+            }))
             .on_action(cx.listener(|this, _: &SaveRequest, window, cx| {
                 this.save_request(window, cx);
             }))
             .on_action(cx.listener(|this, _: &NewRequest, window, cx| {
                 this.save_new_request(window, cx);
             }))
+            .on_action(cx.listener(|this, _: &NewTab, window, cx| {
+                this.new_tab(window, cx);
+            }))
             .on_action(cx.listener(|this, _: &OpenFolder, window, cx| {
                 this.open_folder(window, cx);
             }))
@@ -1964,6 +5066,7 @@ impl Render for App {
                                             .size_full()
                                             .flex()
                                             .flex_col()
+                                            .child(self.render_editor_tabs(cx))
                                             .child(self.render_request_bar(window, cx))
                                             .child(self.render_tabs(window, cx))
                                             .child(self.render_request_panel(window, cx)),
@@ -1981,18 +5084,19 @@ impl Render for App {
 
 impl App {
     fn copy_response(&self, cx: &mut Context<Self>) {
-        if self.response_body.is_empty() {
+        let tab = self.tab();
+        if tab.response_body.is_empty() {
             return;
         }
-        cx.write_to_clipboard(ClipboardItem::new_string(self.response_body.clone()));
+        cx.write_to_clipboard(ClipboardItem::new_string(tab.response_body.clone()));
     }
 
     fn save_response_to_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if self.response_body.is_empty() {
+        if self.tab().response_body.is_empty() {
             return;
         }
 
-        let response_text = self.response_body.clone();
+        let response_text = self.tab().response_body.clone();
         cx.spawn_in(window, async move |_this, _cx| {
             let file = rfd::AsyncFileDialog::new()
                 .set_title("Save Response")
@@ -2008,3 +5112,43 @@ impl App {
         .detach();
     }
 }
+
+/// Request file or folder being dragged in the sidebar tree
+#[derive(Clone)]
+struct DraggedEntry {
+    path: PathBuf,
+    name: String,
+}
+
+impl Render for DraggedEntry {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        drag_preview(self.name.clone(), cx)
+    }
+}
+
+/// Editor tab being dragged to a new position
+#[derive(Clone)]
+struct DraggedTab {
+    id: usize,
+    title: String,
+}
+
+impl Render for DraggedTab {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        drag_preview(self.title.clone(), cx)
+    }
+}
+
+/// Label following the cursor while something is dragged
+fn drag_preview(label: String, cx: &gpui::App) -> Div {
+    div()
+        .px_2()
+        .py_1()
+        .rounded(px(4.0))
+        .bg(cx.theme().popover)
+        .border_1()
+        .border_color(cx.theme().border)
+        .text_sm()
+        .text_color(cx.theme().popover_foreground)
+        .child(label)
+}
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::request::HttpResponse;
+
+/// A check run against the response after a request completes
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Assertion {
+    Status {
+        equals: u16,
+    },
+    HeaderEquals {
+        name: String,
+        value: String,
+    },
+    HeaderContains {
+        name: String,
+        value: String,
+    },
+    /// Without `equals` the path only has to exist
+    JsonPath {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        equals: Option<Value>,
+    },
+    BodyMatches {
+        pattern: String,
+    },
+    MaxResponseTime {
+        ms: u64,
+    },
+}
+
+impl Assertion {
+    pub fn describe(&self) -> String {
+        match self {
+            Assertion::Status { equals } => format!("Status is {}", equals),
+            Assertion::HeaderEquals { name, value } => format!("{} equals \"{}\"", name, value),
+            Assertion::HeaderContains { name, value } => {
+                format!("{} contains \"{}\"", name, value)
+            }
+            Assertion::JsonPath { path, equals: None } => format!("{} exists", path),
+            Assertion::JsonPath {
+                path,
+                equals: Some(value),
+            } => format!("{} equals {}", path, value),
+            Assertion::BodyMatches { pattern } => format!("Body matches /{}/", pattern),
+            Assertion::MaxResponseTime { ms } => format!("Response time below {} ms", ms),
+        }
+    }
+}
+
+/// Outcome of a single assertion
+#[derive(Clone, Debug)]
+pub struct AssertionResult {
+    pub description: String,
+    pub passed: bool,
+    /// Actual value or error explaining a failure
+    pub message: String,
+}
+
+/// Evaluate every assertion against a response received after `elapsed_ms`
+pub fn evaluate(
+    assertions: &[Assertion],
+    response: &HttpResponse,
+    elapsed_ms: u128,
+) -> Vec<AssertionResult> {
+    let body = response.text();
+    let json = serde_json::from_str::<Value>(&body).ok();
+
+    assertions
+        .iter()
+        .map(|assertion| {
+            let (passed, message) = check(assertion, response, &body, json.as_ref(), elapsed_ms);
+            AssertionResult {
+                description: assertion.describe(),
+                passed,
+                message,
+            }
+        })
+        .collect()
+}
+
+fn check(
+    assertion: &Assertion,
+    response: &HttpResponse,
+    body: &str,
+    json: Option<&Value>,
+    elapsed_ms: u128,
+) -> (bool, String) {
+    let header_values = |name: &str| -> Vec<&str> {
+        response
+            .headers
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+            .collect()
+    };
+
+    match assertion {
+        Assertion::Status { equals } => (
+            response.status == *equals,
+            format!("got {}", response.status),
+        ),
+        Assertion::HeaderEquals { name, value } => {
+            let values = header_values(name);
+            if values.is_empty() {
+                (false, "header not present".to_string())
+            } else {
+                (
+                    values.iter().any(|v| v == value),
+                    format!("got \"{}\"", values.join(", ")),
+                )
+            }
+        }
+        Assertion::HeaderContains { name, value } => {
+            let values = header_values(name);
+            if values.is_empty() {
+                (false, "header not present".to_string())
+            } else {
+                (
+                    values.iter().any(|v| v.contains(value.as_str())),
+                    format!("got \"{}\"", values.join(", ")),
+                )
+            }
+        }
+        Assertion::JsonPath { path, equals } => {
+            let Some(json) = json else {
+                return (false, "body is not JSON".to_string());
+            };
+            match select(json, path) {
+                Err(e) => (false, e),
+                Ok(None) => (false, "path not found".to_string()),
+                Ok(Some(actual)) => match equals {
+                    None => (true, format!("got {}", actual)),
+                    Some(expected) => (actual == expected, format!("got {}", actual)),
+                },
+            }
+        }
+        Assertion::BodyMatches { pattern } => match regex::Regex::new(pattern) {
+            Ok(re) => match re.find(body) {
+                Some(m) => (true, format!("matched \"{}\"", m.as_str())),
+                None => (false, "no match".to_string()),
+            },
+            Err(e) => (false, format!("invalid pattern: {}", e)),
+        },
+        Assertion::MaxResponseTime { ms } => {
+            (elapsed_ms <= *ms as u128, format!("took {} ms", elapsed_ms))
+        }
+    }
+}
+
+/// Resolve a JSONPath subset: `$`, `.name`, `['name']` and `[index]` (negative from the end)
+pub fn select<'a>(root: &'a Value, path: &str) -> Result<Option<&'a Value>, String> {
+    let rest = path
+        .trim()
+        .strip_prefix('$')
+        .ok_or_else(|| format!("path must start with $: {}", path))?;
+    let mut chars = rest.chars().peekable();
+    let mut current = root;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next == '.' || next == '[' {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                if name.is_empty() {
+                    return Err(format!("empty field name in {}", path));
+                }
+                match current.get(&name) {
+                    Some(value) => current = value,
+                    None => return Ok(None),
+                }
+            }
+            '[' => {
+                let mut segment = String::new();
+                for next in chars.by_ref() {
+                    if next == ']' {
+                        break;
+                    }
+                    segment.push(next);
+                }
+                let segment = segment.trim();
+                let quoted = segment
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| segment.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+
+                let next = if let Some(name) = quoted {
+                    current.get(name)
+                } else {
+                    let index: i64 = segment
+                        .parse()
+                        .map_err(|_| format!("invalid index [{}] in {}", segment, path))?;
+                    current.as_array().and_then(|items| {
+                        let index = if index < 0 {
+                            items.len() as i64 + index
+                        } else {
+                            index
+                        };
+                        usize::try_from(index).ok().and_then(|i| items.get(i))
+                    })
+                };
+                match next {
+                    Some(value) => current = value,
+                    None => return Ok(None),
+                }
+            }
+            other => return Err(format!("unexpected '{}' in {}", other, path)),
+        }
+    }
+
+    Ok(Some(current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn selects_fields_indexes_and_quoted_names() {
+        let root = json!({
+            "users": [{"name": "ada", "tags": ["admin"]}, {"name": "bob"}],
+            "odd key": {"a.b": 1}
+        });
+        assert_eq!(select(&root, "$").unwrap(), Some(&root));
+        assert_eq!(
+            select(&root, "$.users[0].name").unwrap(),
+            Some(&json!("ada"))
+        );
+        assert_eq!(
+            select(&root, " $.users[-1].name ").unwrap(),
+            Some(&json!("bob"))
+        );
+        assert_eq!(
+            select(&root, "$.users[0].tags[0]").unwrap(),
+            Some(&json!("admin"))
+        );
+        assert_eq!(
+            select(&root, "$['odd key'][\"a.b\"]").unwrap(),
+            Some(&json!(1))
+        );
+
+        assert_eq!(select(&root, "$.missing").unwrap(), None);
+        assert_eq!(select(&root, "$.users[2]").unwrap(), None);
+        assert_eq!(select(&root, "$.users[-3]").unwrap(), None);
+        assert_eq!(select(&root, "$.users.name").unwrap(), None);
+
+        assert!(select(&root, "users").is_err());
+        assert!(select(&root, "$.users[x]").is_err());
+        assert!(select(&root, "$..users").is_err());
+        assert!(select(&root, "$users").is_err());
+    }
+
+    #[test]
+    fn evaluates_each_kind_of_assertion() {
+        let response = HttpResponse {
+            status: 201,
+            reason: "Created".to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("Set-Cookie".to_string(), "a=1".to_string()),
+                ("set-cookie".to_string(), "b=2".to_string()),
+            ],
+            remote_addr: None,
+            body: br#"{"id": 7, "name": "ada"}"#.to_vec(),
+            body_file: None,
+            body_size: 24,
+            truncated: false,
+            unverified_tls: false,
+            timings: Default::default(),
+        };
+        let assertions = [
+            Assertion::Status { equals: 201 },
+            Assertion::Status { equals: 200 },
+            Assertion::HeaderEquals {
+                name: "content-type".to_string(),
+                value: "application/json".to_string(),
+            },
+            Assertion::HeaderEquals {
+                name: "Set-Cookie".to_string(),
+                value: "b=2".to_string(),
+            },
+            Assertion::HeaderContains {
+                name: "X-Missing".to_string(),
+                value: "".to_string(),
+            },
+            Assertion::HeaderContains {
+                name: "Content-Type".to_string(),
+                value: "json".to_string(),
+            },
+            Assertion::JsonPath {
+                path: "$.id".to_string(),
+                equals: Some(json!(7)),
+            },
+            Assertion::JsonPath {
+                path: "$.name".to_string(),
+                equals: Some(json!("bob")),
+            },
+            Assertion::JsonPath {
+                path: "$.email".to_string(),
+                equals: None,
+            },
+            Assertion::BodyMatches {
+                pattern: r#""name":\s*"\w+""#.to_string(),
+            },
+            Assertion::BodyMatches {
+                pattern: "(".to_string(),
+            },
+            Assertion::MaxResponseTime { ms: 100 },
+            Assertion::MaxResponseTime { ms: 10 },
+        ];
+
+        let results = evaluate(&assertions, &response, 50);
+        let outcomes: Vec<(bool, &str)> = results
+            .iter()
+            .map(|r| (r.passed, r.message.as_str()))
+            .collect();
+        assert_eq!(outcomes[0], (true, "got 201"));
+        assert_eq!(outcomes[1], (false, "got 201"));
+        assert_eq!(outcomes[2], (true, "got \"application/json\""));
+        assert_eq!(outcomes[3], (true, "got \"a=1, b=2\""));
+        assert_eq!(outcomes[4], (false, "header not present"));
+        assert!(outcomes[5].0);
+        assert_eq!(outcomes[6], (true, "got 7"));
+        assert_eq!(outcomes[7], (false, "got \"ada\""));
+        assert_eq!(outcomes[8], (false, "path not found"));
+        assert_eq!(outcomes[9], (true, "matched \"\"name\": \"ada\"\""));
+        assert!(!outcomes[10].0 && outcomes[10].1.starts_with("invalid pattern"));
+        assert_eq!(outcomes[11], (true, "took 50 ms"));
+        assert_eq!(outcomes[12], (false, "took 50 ms"));
+        assert_eq!(results[6].description, "$.id equals 7");
+
+        let text = HttpResponse {
+            body: b"not json".to_vec(),
+            ..response
+        };
+        let path = Assertion::JsonPath {
+            path: "$".to_string(),
+            equals: None,
+        };
+        let results = evaluate(&[path], &text, 0);
+        assert_eq!(
+            (results[0].passed, results[0].message.as_str()),
+            (false, "body is not JSON")
+        );
+    }
+}
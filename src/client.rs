@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+
+use crate::config::{ProxyConfig, ProxyMode, TlsConfig};
+use crate::timing::{TimedResolver, TimingLayer};
+use crate::types::HttpVersion;
+
+/// Options fixed when a client is built; requests with equal settings share a client
+/// and its open connections
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ClientSettings {
+    pub connect_timeout: Option<Duration>,
+    pub http_version: HttpVersion,
+    pub proxy: ProxyConfig,
+    /// Certificate settings of the host the request goes to
+    pub tls: Option<TlsConfig>,
+}
+
+/// Long-lived HTTP clients keyed by their settings, so connections are pooled and
+/// kept alive between requests. Clones share the same clients.
+#[derive(Clone, Debug, Default)]
+pub struct ClientManager {
+    clients: Arc<Mutex<HashMap<ClientSettings, reqwest::Client>>>,
+}
+
+impl ClientManager {
+    /// The client for `settings`, built on first use
+    pub fn client(&self, settings: &ClientSettings) -> Result<reqwest::Client, String> {
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(client) = clients.get(settings) {
+            return Ok(client.clone());
+        }
+        let client = build(settings)?;
+        clients.insert(settings.clone(), client.clone());
+        Ok(client)
+    }
+
+    /// Drop every client along with its idle connections, so the next requests
+    /// start cold; requests already in flight finish on their own connection
+    pub fn reset(&self) {
+        self.clients
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+fn build(settings: &ClientSettings) -> Result<reqwest::Client, String> {
+    // Redirects are followed by `execute_request` so every hop goes through the
+    // cookie jar
+    let mut builder = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(TimedResolver))
+        .connector_layer(TimingLayer);
+    if let Some(timeout) = settings.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    builder = match settings.http_version {
+        HttpVersion::Auto => builder,
+        HttpVersion::Http1 => builder.http1_only(),
+        HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+    };
+    builder = match settings.proxy.mode {
+        // reqwest reads the proxy variables of the process environment by default
+        ProxyMode::System => builder,
+        ProxyMode::Direct => builder.no_proxy(),
+        ProxyMode::Manual => manual_proxy(builder.no_proxy(), &settings.proxy)?,
+    };
+    builder =
+        builder.use_preconfigured_tls(tls_config(settings.tls.as_ref(), settings.http_version)?);
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// rustls settings for a client: the system's CAs plus any configured for the host,
+/// a client certificate, and the protocols offered for `http_version`
+fn tls_config(
+    tls: Option<&TlsConfig>,
+    http_version: HttpVersion,
+) -> Result<rustls::ClientConfig, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?;
+
+    let builder = if tls.is_some_and(|tls| tls.skip_verification) {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+    } else {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add_parsable_certificates(native_roots().iter().cloned());
+        for path in tls.map(|tls| tls.ca_files.as_slice()).unwrap_or_default() {
+            for certificate in CertificateDer::pem_slice_iter(&read(path)?) {
+                certificate
+                    .map_err(|e| e.to_string())
+                    .and_then(|certificate| roots.add(certificate).map_err(|e| e.to_string()))
+                    .map_err(|e| format!("Invalid CA bundle {}: {}", path, e))?;
+            }
+        }
+        builder.with_root_certificates(roots)
+    };
+
+    let mut config = match tls.filter(|tls| !tls.client_cert.is_empty()) {
+        Some(tls) => {
+            let (chain, key) = identity(tls)?;
+            builder
+                .with_client_auth_cert(chain, key)
+                .map_err(|e| format!("Invalid client certificate {}: {}", tls.client_cert, e))?
+        }
+        None => builder.with_no_client_auth(),
+    };
+    // reqwest leaves ALPN to a preconfigured TLS client, and without it servers
+    // only ever answer in HTTP/1.1
+    config.alpn_protocols = match http_version {
+        HttpVersion::Auto => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+        HttpVersion::Http1 => vec![b"http/1.1".to_vec()],
+        HttpVersion::Http2PriorKnowledge => vec![b"h2".to_vec()],
+    };
+    Ok(config)
+}
+
+/// Certificate authorities trusted by the system, read once
+fn native_roots() -> &'static [CertificateDer<'static>] {
+    static ROOTS: OnceLock<Vec<CertificateDer<'static>>> = OnceLock::new();
+    ROOTS.get_or_init(|| rustls_native_certs::load_native_certs().certs)
+}
+
+/// Client certificate chain and key, from a PKCS#12 archive or PEM files
+fn identity(
+    tls: &TlsConfig,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    let cert = read(&tls.client_cert)?;
+    if !String::from_utf8_lossy(&cert).contains("-----BEGIN") {
+        let store = p12_keystore::KeyStore::from_pkcs12(&cert, &tls.passphrase)
+            .map_err(|e| format!("Could not open {}: {}", tls.client_cert, e))?;
+        let (_, chain) = store
+            .private_key_chain()
+            .ok_or_else(|| format!("No private key in {}", tls.client_cert))?;
+        let certificates = chain
+            .chain()
+            .iter()
+            .map(|certificate| CertificateDer::from(certificate.as_der().to_vec()))
+            .collect();
+        let key = PrivatePkcs8KeyDer::from(chain.key().to_vec());
+        return Ok((certificates, key.into()));
+    }
+
+    let certificates = CertificateDer::pem_slice_iter(&cert)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid client certificate {}: {}", tls.client_cert, e))?;
+    let (key_path, key) = if tls.client_key.is_empty() {
+        (&tls.client_cert, cert)
+    } else {
+        (&tls.client_key, read(&tls.client_key)?)
+    };
+    let key = PrivateKeyDer::from_pem_slice(&key).map_err(|_| {
+        if String::from_utf8_lossy(&key).contains("ENCRYPTED PRIVATE KEY") {
+            format!(
+                "The key in {} is encrypted; use a PKCS#12 archive with a passphrase instead",
+                key_path
+            )
+        } else {
+            format!("No private key in {}", key_path)
+        }
+    })?;
+    Ok((certificates, key))
+}
+
+/// Accepts any server certificate, for hosts with verification turned off. Handshake
+/// signatures are still checked, so the connection is encrypted to whoever answered.
+#[derive(Debug)]
+struct NoVerification(Arc<rustls::crypto::CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+fn manual_proxy(
+    mut builder: reqwest::ClientBuilder,
+    config: &ProxyConfig,
+) -> Result<reqwest::ClientBuilder, String> {
+    let https = if config.https.is_empty() {
+        &config.http
+    } else {
+        &config.https
+    };
+    let proxy = |built: reqwest::Result<reqwest::Proxy>, url: &str| {
+        let mut proxy = built.map_err(|e| format!("Invalid proxy {}: {}", url, e))?;
+        // SOCKS4 has no authentication
+        if !config.username.is_empty() && !url.starts_with("socks4") {
+            proxy = proxy.basic_auth(&config.username, &config.password);
+        }
+        Ok::<_, String>(proxy.no_proxy(reqwest::NoProxy::from_string(&config.no_proxy)))
+    };
+    if !config.http.is_empty() {
+        builder = builder.proxy(proxy(reqwest::Proxy::http(&config.http), &config.http)?);
+    }
+    if !https.is_empty() {
+        builder = builder.proxy(proxy(reqwest::Proxy::https(https), https)?);
+    }
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offers_the_protocols_of_the_http_version() {
+        let protocols = |version| tls_config(None, version).unwrap().alpn_protocols;
+        assert_eq!(
+            protocols(HttpVersion::Auto),
+            [b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+        assert_eq!(protocols(HttpVersion::Http1), [b"http/1.1".to_vec()]);
+        assert_eq!(
+            protocols(HttpVersion::Http2PriorKnowledge),
+            [b"h2".to_vec()]
+        );
+    }
+}
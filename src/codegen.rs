@@ -0,0 +1,554 @@
+use crate::request::{self, PreparedRequest, RequestBody};
+use crate::types::{AuthConfig, HttpMethod};
+
+/// Languages and tools a request can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeTarget {
+    Curl,
+    Httpie,
+    PythonRequests,
+    JsFetch,
+    RustReqwest,
+}
+
+impl CodeTarget {
+    pub const ALL: [CodeTarget; 5] = [
+        CodeTarget::Curl,
+        CodeTarget::Httpie,
+        CodeTarget::PythonRequests,
+        CodeTarget::JsFetch,
+        CodeTarget::RustReqwest,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CodeTarget::Curl => "cURL",
+            CodeTarget::Httpie => "HTTPie",
+            CodeTarget::PythonRequests => "Python (requests)",
+            CodeTarget::JsFetch => "JavaScript (fetch)",
+            CodeTarget::RustReqwest => "Rust (reqwest)",
+        }
+    }
+}
+
+/// Generate a snippet that sends `request` the same way the app does
+pub fn generate(target: CodeTarget, request: &PreparedRequest) -> String {
+    let code = match target {
+        CodeTarget::Curl => curl(request),
+        CodeTarget::Httpie => httpie(request),
+        CodeTarget::PythonRequests => python(request),
+        CodeTarget::JsFetch => fetch(request),
+        CodeTarget::RustReqwest => reqwest(request),
+    };
+    match unsupported_auth(target, request) {
+        Some(note) => {
+            let comment = match target {
+                CodeTarget::Curl | CodeTarget::Httpie | CodeTarget::PythonRequests => "#",
+                CodeTarget::JsFetch | CodeTarget::RustReqwest => "//",
+            };
+            format!("{} {}\n{}", comment, note, code)
+        }
+        None => code,
+    }
+}
+
+/// Note on auth the snippet can't reproduce, shown as a comment above it
+fn unsupported_auth(target: CodeTarget, request: &PreparedRequest) -> Option<&'static str> {
+    match (&request.auth, target) {
+        (AuthConfig::Digest { .. }, CodeTarget::JsFetch | CodeTarget::RustReqwest) => Some(
+            "Digest authentication is not built in; answer the server's 401 challenge yourself",
+        ),
+        (AuthConfig::OAuth2(_), _) => {
+            Some("OAuth 2.0: fetch an access token and send it as Authorization: Bearer <token>")
+        }
+        (AuthConfig::AwsSigV4(_), target) if target != CodeTarget::Curl => {
+            Some("AWS Signature v4 is not included; sign the request with an AWS SDK")
+        }
+        _ => None,
+    }
+}
+
+/// Quote a word for POSIX shells
+fn shell_quote(text: &str) -> String {
+    let safe = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c));
+    if safe {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+/// Double-quoted string literal, valid in Python and JavaScript
+fn string_literal(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn file_name(path: &str) -> &str {
+    std::path::Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("file")
+}
+
+/// Digest credentials, which curl, HTTPie and requests answer on their own
+fn digest_credentials(request: &PreparedRequest) -> Option<(&str, &str)> {
+    match &request.auth {
+        AuthConfig::Digest { username, password } => Some((username, password)),
+        _ => None,
+    }
+}
+
+/// Join a shell command, putting every argument after the first line on its own line
+fn shell_command(first_line: Vec<String>, args: Vec<String>) -> String {
+    std::iter::once(first_line.join(" "))
+        .chain(args)
+        .collect::<Vec<_>>()
+        .join(" \\\n  ")
+}
+
+fn curl(request: &PreparedRequest) -> String {
+    let mut first_line = vec!["curl".to_string()];
+    if request.method != HttpMethod::Get {
+        first_line.push(format!("-X {}", request.method.as_str()));
+    }
+    first_line.push(shell_quote(&request.url));
+
+    let mut args = Vec::new();
+    if let Some((username, password)) = digest_credentials(request) {
+        args.push(format!(
+            "--digest -u {}",
+            shell_quote(&format!("{}:{}", username, password))
+        ));
+    }
+    if let AuthConfig::AwsSigV4(config) = &request.auth {
+        args.push(format!(
+            "--aws-sigv4 {}",
+            shell_quote(&format!("aws:amz:{}:{}", config.region, config.service))
+        ));
+        args.push(format!(
+            "-u {}",
+            shell_quote(&format!("{}:{}", config.access_key, config.secret_key))
+        ));
+        if !config.session_token.is_empty() {
+            args.push(format!(
+                "-H {}",
+                shell_quote(&format!("X-Amz-Security-Token: {}", config.session_token))
+            ));
+        }
+        if config.unsigned_payload {
+            args.push("-H 'X-Amz-Content-SHA256: UNSIGNED-PAYLOAD'".to_string());
+        }
+    }
+    for (key, value) in &request.headers {
+        args.push(format!(
+            "-H {}",
+            shell_quote(&format!("{}: {}", key, value))
+        ));
+    }
+    match request.effective_body() {
+        RequestBody::None => {}
+        RequestBody::Raw(body) => args.push(format!("--data-raw {}", shell_quote(body))),
+        RequestBody::Form(fields) => {
+            for (key, value) in fields {
+                let field = format!(
+                    "{}={}",
+                    request::urlencoding(key),
+                    request::urlencoding(value)
+                );
+                args.push(format!("-d {}", shell_quote(&field)));
+            }
+        }
+        RequestBody::Multipart(fields) => {
+            for (key, value) in fields {
+                // --form-string keeps `@`, `<` and `;` in text values literal
+                let option = if value.starts_with('@') {
+                    "-F"
+                } else {
+                    "--form-string"
+                };
+                args.push(format!(
+                    "{} {}",
+                    option,
+                    shell_quote(&format!("{}={}", key, value))
+                ));
+            }
+        }
+    }
+    shell_command(first_line, args)
+}
+
+/// Escape HTTPie item separators in a key
+fn httpie_key(key: &str) -> String {
+    let mut escaped = String::new();
+    for c in key.chars() {
+        if matches!(c, '\\' | ':' | '=' | '@') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn httpie(request: &PreparedRequest) -> String {
+    let mut first_line = vec!["http".to_string()];
+    match request.effective_body() {
+        RequestBody::Form(_) => first_line.push("--form".to_string()),
+        RequestBody::Multipart(_) => first_line.push("--multipart".to_string()),
+        RequestBody::None | RequestBody::Raw(_) => {}
+    }
+    if let Some((username, password)) = digest_credentials(request) {
+        first_line.push(format!(
+            "-A digest -a {}",
+            shell_quote(&format!("{}:{}", username, password))
+        ));
+    }
+    first_line.push(request.method.as_str().to_string());
+    first_line.push(shell_quote(&request.url));
+
+    let mut args = Vec::new();
+    for (key, value) in &request.headers {
+        args.push(shell_quote(&format!("{}:{}", httpie_key(key), value)));
+    }
+    match request.effective_body() {
+        RequestBody::None => {}
+        RequestBody::Raw(body) => args.push(format!("--raw {}", shell_quote(body))),
+        RequestBody::Form(fields) => {
+            for (key, value) in fields {
+                args.push(shell_quote(&format!("{}={}", httpie_key(key), value)));
+            }
+        }
+        RequestBody::Multipart(fields) => {
+            for (key, value) in fields {
+                let item = match value.strip_prefix('@') {
+                    Some(path) => format!("{}@{}", httpie_key(key), path),
+                    None => format!("{}={}", httpie_key(key), value),
+                };
+                args.push(shell_quote(&item));
+            }
+        }
+    }
+    shell_command(first_line, args)
+}
+
+fn python(request: &PreparedRequest) -> String {
+    let mut code = String::from("import requests\n");
+    if digest_credentials(request).is_some() {
+        code.push_str("from requests.auth import HTTPDigestAuth\n");
+    }
+    code.push('\n');
+    code.push_str(&format!("url = {}\n", string_literal(&request.url)));
+
+    let mut arguments = vec![string_literal(request.method.as_str()), "url".to_string()];
+    if let Some((username, password)) = digest_credentials(request) {
+        arguments.push(format!(
+            "auth=HTTPDigestAuth({}, {})",
+            string_literal(username),
+            string_literal(password)
+        ));
+    }
+
+    if !request.headers.is_empty() {
+        code.push_str("headers = {\n");
+        for (key, value) in &request.headers {
+            code.push_str(&format!(
+                "    {}: {},\n",
+                string_literal(key),
+                string_literal(value)
+            ));
+        }
+        code.push_str("}\n");
+        arguments.push("headers=headers".to_string());
+    }
+
+    match request.effective_body() {
+        RequestBody::None => {}
+        RequestBody::Raw(body) => {
+            code.push_str(&format!("payload = {}\n", string_literal(body)));
+            arguments.push("data=payload.encode(\"utf-8\")".to_string());
+        }
+        RequestBody::Form(fields) => {
+            code.push_str("data = [\n");
+            for (key, value) in fields {
+                code.push_str(&format!(
+                    "    ({}, {}),\n",
+                    string_literal(key),
+                    string_literal(value)
+                ));
+            }
+            code.push_str("]\n");
+            arguments.push("data=data".to_string());
+        }
+        RequestBody::Multipart(fields) => {
+            code.push_str("files = [\n");
+            for (key, value) in fields {
+                let part = match value.strip_prefix('@') {
+                    Some(path) => format!(
+                        "({}, open({}, \"rb\"))",
+                        string_literal(file_name(path)),
+                        string_literal(path)
+                    ),
+                    None => format!("(None, {})", string_literal(value)),
+                };
+                code.push_str(&format!("    ({}, {}),\n", string_literal(key), part));
+            }
+            code.push_str("]\n");
+            arguments.push("files=files".to_string());
+        }
+    }
+
+    code.push_str(&format!(
+        "\nresponse = requests.request({})\n",
+        arguments.join(", ")
+    ));
+    code.push_str("print(response.status_code)\nprint(response.text)\n");
+    code
+}
+
+fn fetch(request: &PreparedRequest) -> String {
+    let mut code = String::new();
+    let mut body = None;
+
+    match request.effective_body() {
+        RequestBody::None => {}
+        RequestBody::Raw(raw) => body = Some(string_literal(raw)),
+        RequestBody::Form(fields) => {
+            let pairs: Vec<String> = fields
+                .iter()
+                .map(|(k, v)| format!("[{}, {}]", string_literal(k), string_literal(v)))
+                .collect();
+            body = Some(format!("new URLSearchParams([{}])", pairs.join(", ")));
+        }
+        RequestBody::Multipart(fields) => {
+            if fields.iter().any(|(_, v)| v.starts_with('@')) {
+                code.push_str("import { openAsBlob } from \"node:fs\";\n\n");
+            }
+            code.push_str("const form = new FormData();\n");
+            for (key, value) in fields {
+                match value.strip_prefix('@') {
+                    Some(path) => code.push_str(&format!(
+                        "form.append({}, await openAsBlob({}), {});\n",
+                        string_literal(key),
+                        string_literal(path),
+                        string_literal(file_name(path))
+                    )),
+                    None => code.push_str(&format!(
+                        "form.append({}, {});\n",
+                        string_literal(key),
+                        string_literal(value)
+                    )),
+                }
+            }
+            code.push('\n');
+            body = Some("form".to_string());
+        }
+    }
+
+    code.push_str(&format!(
+        "const response = await fetch({}, {{\n",
+        string_literal(&request.url)
+    ));
+    code.push_str(&format!(
+        "  method: {},\n",
+        string_literal(request.method.as_str())
+    ));
+    if !request.headers.is_empty() {
+        code.push_str("  headers: {\n");
+        for (key, value) in &request.headers {
+            code.push_str(&format!(
+                "    {}: {},\n",
+                string_literal(key),
+                string_literal(value)
+            ));
+        }
+        code.push_str("  },\n");
+    }
+    if let Some(body) = body {
+        code.push_str(&format!("  body: {},\n", body));
+    }
+    code.push_str("});\n\nconsole.log(response.status);\nconsole.log(await response.text());\n");
+    code
+}
+
+fn reqwest(request: &PreparedRequest) -> String {
+    let method = match request.method {
+        HttpMethod::Get => "get",
+        HttpMethod::Post => "post",
+        HttpMethod::Put => "put",
+        HttpMethod::Delete => "delete",
+        HttpMethod::Patch => "patch",
+    };
+
+    let mut code = String::from("let client = reqwest::Client::new();\n");
+
+    if let RequestBody::Multipart(fields) = request.effective_body() {
+        code.push_str("let form = reqwest::multipart::Form::new()");
+        for (key, value) in fields {
+            match value.strip_prefix('@') {
+                Some(path) => code.push_str(&format!(
+                    "\n    .part(\n        {:?},\n        reqwest::multipart::Part::bytes(std::fs::read({:?})?).file_name({:?}),\n    )",
+                    key,
+                    path,
+                    file_name(path)
+                )),
+                None => code.push_str(&format!("\n    .text({:?}, {:?})", key, value)),
+            }
+        }
+        code.push_str(";\n");
+    }
+
+    code.push_str(&format!(
+        "let response = client\n    .{}({:?})",
+        method, request.url
+    ));
+    for (key, value) in &request.headers {
+        code.push_str(&format!("\n    .header({:?}, {:?})", key, value));
+    }
+    match request.effective_body() {
+        RequestBody::None => {}
+        RequestBody::Raw(body) => code.push_str(&format!("\n    .body({:?})", body)),
+        RequestBody::Form(fields) => {
+            let pairs: Vec<String> = fields
+                .iter()
+                .map(|(k, v)| format!("({:?}, {:?})", k, v))
+                .collect();
+            code.push_str(&format!("\n    .form(&[{}])", pairs.join(", ")));
+        }
+        RequestBody::Multipart(_) => code.push_str("\n    .multipart(form)"),
+    }
+    code.push_str("\n    .send()\n    .await?;\n\n");
+    code.push_str("println!(\"{}\", response.status());\n");
+    code.push_str("println!(\"{}\", response.text().await?);\n");
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RequestSettings;
+
+    fn request(method: HttpMethod, body: RequestBody) -> PreparedRequest {
+        PreparedRequest {
+            method,
+            url: "https://example.com/users?q=a b".to_string(),
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body,
+            auth: AuthConfig::None,
+            use_cookie_jar: true,
+            settings: RequestSettings::default(),
+            download_to: None,
+        }
+    }
+
+    fn json_post() -> PreparedRequest {
+        request(
+            HttpMethod::Post,
+            RequestBody::Raw(r#"{"name": "O'Brien"}"#.to_string()),
+        )
+    }
+
+    #[test]
+    fn curl_quotes_for_the_shell() {
+        assert_eq!(
+            generate(CodeTarget::Curl, &json_post()),
+            r#"curl -X POST 'https://example.com/users?q=a b' \
+  -H 'Content-Type: application/json' \
+  --data-raw '{"name": "O'\''Brien"}'"#
+        );
+    }
+
+    #[test]
+    fn httpie_sends_forms_as_items() {
+        let form = request(
+            HttpMethod::Patch,
+            RequestBody::Form(vec![("a b".to_string(), "1&2".to_string())]),
+        );
+        assert_eq!(
+            generate(CodeTarget::Httpie, &form),
+            r#"http --form PATCH 'https://example.com/users?q=a b' \
+  Content-Type:application/json \
+  'a b=1&2'"#
+        );
+    }
+
+    #[test]
+    fn python_answers_digest_challenges() {
+        let mut digest = request(HttpMethod::Get, RequestBody::Raw("not sent".to_string()));
+        digest.auth = AuthConfig::Digest {
+            username: "ada".to_string(),
+            password: "s3cret".to_string(),
+        };
+        assert_eq!(
+            generate(CodeTarget::PythonRequests, &digest),
+            r#"import requests
+from requests.auth import HTTPDigestAuth
+
+url = "https://example.com/users?q=a b"
+headers = {
+    "Content-Type": "application/json",
+}
+
+response = requests.request("GET", url, auth=HTTPDigestAuth("ada", "s3cret"), headers=headers)
+print(response.status_code)
+print(response.text)
+"#
+        );
+    }
+
+    #[test]
+    fn fetch_reads_multipart_files() {
+        let multipart = request(
+            HttpMethod::Put,
+            RequestBody::Multipart(vec![
+                ("name".to_string(), "ada".to_string()),
+                ("photo".to_string(), "@/tmp/me.png".to_string()),
+            ]),
+        );
+        assert_eq!(
+            generate(CodeTarget::JsFetch, &multipart),
+            r#"import { openAsBlob } from "node:fs";
+
+const form = new FormData();
+form.append("name", "ada");
+form.append("photo", await openAsBlob("/tmp/me.png"), "me.png");
+
+const response = await fetch("https://example.com/users?q=a b", {
+  method: "PUT",
+  headers: {
+    "Content-Type": "application/json",
+  },
+  body: form,
+});
+
+console.log(response.status);
+console.log(await response.text());
+"#
+        );
+    }
+
+    #[test]
+    fn reqwest_notes_unsupported_auth() {
+        let mut post = json_post();
+        post.auth = AuthConfig::Digest {
+            username: "ada".to_string(),
+            password: "s3cret".to_string(),
+        };
+        assert_eq!(
+            generate(CodeTarget::RustReqwest, &post),
+            r#"// Digest authentication is not built in; answer the server's 401 challenge yourself
+let client = reqwest::Client::new();
+let response = client
+    .post("https://example.com/users?q=a b")
+    .header("Content-Type", "application/json")
+    .body("{\"name\": \"O'Brien\"}")
+    .send()
+    .await?;
+
+println!("{}", response.status());
+println!("{}", response.text().await?);
+"#
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use crate::types::{
+    ApiKeyLocation, AuthConfig, AuthKind, AwsSigV4Config, OAuth2Config, OAuthGrant,
+};
+use gpui::prelude::*;
+use gpui::*;
+use gpui_component::{
+    button::Button,
+    checkbox::Checkbox,
+    input::{Input, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
+    v_flex, ActiveTheme, Icon, IconName, Sizable,
+};
+
+/// Scheme picker and credential inputs of a request's Auth tab
+pub struct AuthEditor {
+    pub kind: AuthKind,
+    pub api_key_location: ApiKeyLocation,
+    pub oauth_grant: OAuthGrant,
+    pub unsigned_payload: bool,
+    /// Shared by Basic, Digest and the OAuth 2.0 password grant so switching between
+    /// them keeps the credentials
+    username: Entity<InputState>,
+    password: Entity<InputState>,
+    token: Entity<InputState>,
+    api_key: Entity<InputState>,
+    api_value: Entity<InputState>,
+    auth_url: Entity<InputState>,
+    token_url: Entity<InputState>,
+    client_id: Entity<InputState>,
+    client_secret: Entity<InputState>,
+    scope: Entity<InputState>,
+    redirect_uri: Entity<InputState>,
+    access_key: Entity<InputState>,
+    secret_key: Entity<InputState>,
+    session_token: Entity<InputState>,
+    region: Entity<InputState>,
+    service: Entity<InputState>,
+}
+
+impl AuthEditor {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let username = cx.new(|cx| InputState::new(window, cx).placeholder("Username"));
+        let password = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Password")
+                .masked(true)
+        });
+        let token = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Token")
+                .masked(true)
+        });
+        let api_key = cx.new(|cx| InputState::new(window, cx).placeholder("X-API-Key"));
+        let api_value = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Value")
+                .masked(true)
+        });
+        let auth_url = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("https://example.com/oauth/authorize")
+        });
+        let token_url =
+            cx.new(|cx| InputState::new(window, cx).placeholder("https://example.com/oauth/token"));
+        let client_id = cx.new(|cx| InputState::new(window, cx).placeholder("Client ID"));
+        let client_secret = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Client Secret")
+                .masked(true)
+        });
+        let scope = cx.new(|cx| InputState::new(window, cx).placeholder("read write"));
+        let redirect_uri = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("http://127.0.0.1:<any port>/callback")
+        });
+        let access_key = cx.new(|cx| InputState::new(window, cx).placeholder("Access Key ID"));
+        let secret_key = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Secret Access Key")
+                .masked(true)
+        });
+        let session_token = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Optional, for temporary credentials")
+                .masked(true)
+        });
+        let region = cx.new(|cx| InputState::new(window, cx).placeholder("us-east-1"));
+        let service = cx.new(|cx| InputState::new(window, cx).placeholder("execute-api"));
+        Self {
+            kind: AuthKind::None,
+            api_key_location: ApiKeyLocation::Header,
+            oauth_grant: OAuthGrant::ClientCredentials,
+            unsigned_payload: false,
+            username,
+            password,
+            token,
+            api_key,
+            api_value,
+            auth_url,
+            token_url,
+            client_id,
+            client_secret,
+            scope,
+            redirect_uri,
+            access_key,
+            secret_key,
+            session_token,
+            region,
+            service,
+        }
+    }
+
+    /// Auth settings as currently entered
+    pub fn auth(&self, cx: &App) -> AuthConfig {
+        let value = |input: &Entity<InputState>| input.read(cx).value().to_string();
+        match self.kind {
+            AuthKind::None => AuthConfig::None,
+            AuthKind::Basic => AuthConfig::Basic {
+                username: value(&self.username),
+                password: value(&self.password),
+            },
+            AuthKind::Bearer => AuthConfig::Bearer {
+                token: value(&self.token),
+            },
+            AuthKind::ApiKey => AuthConfig::ApiKey {
+                key: value(&self.api_key),
+                value: value(&self.api_value),
+                location: self.api_key_location,
+            },
+            AuthKind::Digest => AuthConfig::Digest {
+                username: value(&self.username),
+                password: value(&self.password),
+            },
+            AuthKind::OAuth2 => {
+                let grant = self.oauth_grant;
+                let code = grant == OAuthGrant::AuthorizationCode;
+                let password = grant == OAuthGrant::Password;
+                // Settings the grant doesn't use are left out of the saved request
+                let value_if = |used: bool, input| if used { value(input) } else { String::new() };
+                AuthConfig::OAuth2(OAuth2Config {
+                    grant,
+                    auth_url: value_if(code, &self.auth_url),
+                    token_url: value(&self.token_url),
+                    client_id: value(&self.client_id),
+                    client_secret: value(&self.client_secret),
+                    scope: value(&self.scope),
+                    username: value_if(password, &self.username),
+                    password: value_if(password, &self.password),
+                    redirect_uri: value_if(code, &self.redirect_uri),
+                })
+            }
+            AuthKind::AwsSigV4 => AuthConfig::AwsSigV4(AwsSigV4Config {
+                access_key: value(&self.access_key),
+                secret_key: value(&self.secret_key),
+                session_token: value(&self.session_token),
+                region: value(&self.region),
+                service: value(&self.service),
+                unsigned_payload: self.unsigned_payload,
+            }),
+        }
+    }
+
+    /// Fill the inputs from saved auth settings
+    pub fn set_auth(&mut self, auth: &AuthConfig, window: &mut Window, cx: &mut Context<Self>) {
+        let mut values: Vec<(&Entity<InputState>, &str)> = Vec::new();
+        match auth {
+            AuthConfig::None => {}
+            AuthConfig::Basic { username, password }
+            | AuthConfig::Digest { username, password } => {
+                values.push((&self.username, username));
+                values.push((&self.password, password));
+            }
+            AuthConfig::Bearer { token } => values.push((&self.token, token)),
+            AuthConfig::ApiKey {
+                key,
+                value,
+                location,
+            } => {
+                self.api_key_location = *location;
+                values.push((&self.api_key, key));
+                values.push((&self.api_value, value));
+            }
+            AuthConfig::OAuth2(config) => {
+                self.oauth_grant = config.grant;
+                values.push((&self.auth_url, &config.auth_url));
+                values.push((&self.token_url, &config.token_url));
+                values.push((&self.client_id, &config.client_id));
+                values.push((&self.client_secret, &config.client_secret));
+                values.push((&self.scope, &config.scope));
+                values.push((&self.username, &config.username));
+                values.push((&self.password, &config.password));
+                values.push((&self.redirect_uri, &config.redirect_uri));
+            }
+            AuthConfig::AwsSigV4(config) => {
+                self.unsigned_payload = config.unsigned_payload;
+                values.push((&self.access_key, &config.access_key));
+                values.push((&self.secret_key, &config.secret_key));
+                values.push((&self.session_token, &config.session_token));
+                values.push((&self.region, &config.region));
+                values.push((&self.service, &config.service));
+            }
+        }
+
+        for input in [
+            &self.username,
+            &self.password,
+            &self.token,
+            &self.api_key,
+            &self.api_value,
+            &self.auth_url,
+            &self.token_url,
+            &self.client_id,
+            &self.client_secret,
+            &self.scope,
+            &self.redirect_uri,
+            &self.access_key,
+            &self.secret_key,
+            &self.session_token,
+            &self.region,
+            &self.service,
+        ] {
+            let value = values
+                .iter()
+                .find(|(entity, _)| *entity == input)
+                .map_or("", |(_, value)| *value)
+                .to_string();
+            input.update(cx, |state, cx| state.set_value(value, window, cx));
+        }
+        self.kind = auth.kind();
+        cx.notify();
+    }
+
+    fn render_label(label: &'static str, cx: &Context<Self>) -> Div {
+        div()
+            .w(px(96.0))
+            .flex_shrink_0()
+            .text_xs()
+            .font_weight(FontWeight::SEMIBOLD)
+            .text_color(cx.theme().muted_foreground)
+            .child(label)
+    }
+
+    fn render_field(
+        label: &'static str,
+        input: &Entity<InputState>,
+        secret: bool,
+        cx: &Context<Self>,
+    ) -> Div {
+        let input = Input::new(input).appearance(false);
+        let input = if secret { input.mask_toggle() } else { input };
+
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .mb_2()
+            .child(Self::render_label(label, cx))
+            .child(
+                div()
+                    .flex_1()
+                    .p_2()
+                    .rounded(px(6.0))
+                    .bg(cx.theme().muted)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(input),
+            )
+    }
+}
+
+impl Render for AuthEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let kind = self.kind;
+        let location = self.api_key_location;
+
+        let hint = match kind {
+            AuthKind::None => "This request does not use authorization",
+            AuthKind::Basic => "Sent as an Authorization: Basic header",
+            AuthKind::Bearer => "Sent as an Authorization: Bearer header",
+            AuthKind::ApiKey => "Sent as a header or query parameter",
+            AuthKind::Digest => "Answered after the server's 401 challenge",
+            AuthKind::OAuth2 => "Token fetched when sending and cached per environment",
+            AuthKind::AwsSigV4 => "Signed over the final request right before it is sent",
+        };
+
+        let fields = match kind {
+            AuthKind::None => v_flex(),
+            AuthKind::Basic | AuthKind::Digest => v_flex()
+                .child(Self::render_field("Username", &self.username, false, cx))
+                .child(Self::render_field("Password", &self.password, true, cx)),
+            AuthKind::Bearer => v_flex().child(Self::render_field("Token", &self.token, true, cx)),
+            AuthKind::ApiKey => {
+                let view = view.clone();
+                v_flex()
+                    .child(Self::render_field("Key", &self.api_key, false, cx))
+                    .child(Self::render_field("Value", &self.api_value, true, cx))
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_3()
+                            .child(Self::render_label("Add To", cx))
+                            .child(
+                                Button::new("api-key-location")
+                                    .outline()
+                                    .small()
+                                    .label(location.label())
+                                    .dropdown_menu(move |menu, _, _| {
+                                        let mut menu = menu;
+                                        for option in ApiKeyLocation::ALL {
+                                            let view = view.clone();
+                                            menu = menu.item(
+                                                PopupMenuItem::new(option.label())
+                                                    .checked(option == location)
+                                                    .on_click(move |_, _, cx| {
+                                                        let _ = view.update(cx, |editor, cx| {
+                                                            editor.api_key_location = option;
+                                                            cx.notify();
+                                                        });
+                                                    }),
+                                            );
+                                        }
+                                        menu
+                                    }),
+                            ),
+                    )
+            }
+            AuthKind::OAuth2 => {
+                let view = view.clone();
+                let grant = self.oauth_grant;
+                v_flex()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_3()
+                            .mb_2()
+                            .child(Self::render_label("Grant Type", cx))
+                            .child(
+                                Button::new("oauth-grant")
+                                    .outline()
+                                    .small()
+                                    .label(grant.label())
+                                    .dropdown_menu(move |menu, _, _| {
+                                        let mut menu = menu;
+                                        for option in OAuthGrant::ALL {
+                                            let view = view.clone();
+                                            menu = menu.item(
+                                                PopupMenuItem::new(option.label())
+                                                    .checked(option == grant)
+                                                    .on_click(move |_, _, cx| {
+                                                        let _ = view.update(cx, |editor, cx| {
+                                                            editor.oauth_grant = option;
+                                                            cx.notify();
+                                                        });
+                                                    }),
+                                            );
+                                        }
+                                        menu
+                                    }),
+                            ),
+                    )
+                    .when(grant == OAuthGrant::AuthorizationCode, |this| {
+                        this.child(Self::render_field("Auth URL", &self.auth_url, false, cx))
+                    })
+                    .child(Self::render_field("Token URL", &self.token_url, false, cx))
+                    .child(Self::render_field("Client ID", &self.client_id, false, cx))
+                    .child(Self::render_field(
+                        "Client Secret",
+                        &self.client_secret,
+                        true,
+                        cx,
+                    ))
+                    .child(Self::render_field("Scope", &self.scope, false, cx))
+                    .when(grant == OAuthGrant::Password, |this| {
+                        this.child(Self::render_field("Username", &self.username, false, cx))
+                            .child(Self::render_field("Password", &self.password, true, cx))
+                    })
+                    .when(grant == OAuthGrant::AuthorizationCode, |this| {
+                        this.child(Self::render_field(
+                            "Redirect URI",
+                            &self.redirect_uri,
+                            false,
+                            cx,
+                        ))
+                    })
+            }
+            AuthKind::AwsSigV4 => v_flex()
+                .child(Self::render_field(
+                    "Access Key",
+                    &self.access_key,
+                    false,
+                    cx,
+                ))
+                .child(Self::render_field("Secret Key", &self.secret_key, true, cx))
+                .child(Self::render_field(
+                    "Session Token",
+                    &self.session_token,
+                    true,
+                    cx,
+                ))
+                .child(Self::render_field("Region", &self.region, false, cx))
+                .child(Self::render_field("Service", &self.service, false, cx))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_3()
+                        .child(Self::render_label("", cx))
+                        .child(
+                            Checkbox::new("aws-unsigned-payload")
+                                .label("Unsigned payload (skip hashing large bodies)")
+                                .checked(self.unsigned_payload)
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    this.unsigned_payload = *checked;
+                                    cx.notify();
+                                })),
+                        ),
+                ),
+        };
+
+        v_flex()
+            .size_full()
+            .pb_4()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .mb_4()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                Icon::new(IconName::User).text_color(cx.theme().muted_foreground),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(hint),
+                            ),
+                    )
+                    .child(
+                        Button::new("auth-kind")
+                            .outline()
+                            .small()
+                            .label(kind.label())
+                            .dropdown_menu(move |menu, _, _| {
+                                let mut menu = menu;
+                                for option in AuthKind::ALL {
+                                    let view = view.clone();
+                                    menu = menu.item(
+                                        PopupMenuItem::new(option.label())
+                                            .checked(option == kind)
+                                            .on_click(move |_, _, cx| {
+                                                let _ = view.update(cx, |editor, cx| {
+                                                    editor.kind = option;
+                                                    cx.notify();
+                                                });
+                                            }),
+                                    );
+                                }
+                                menu
+                            }),
+                    ),
+            )
+            .child(fields)
+    }
+}
@@ -1 +1,5 @@
+pub mod auth_editor;
 pub mod git_panel;
+pub mod proxy_editor;
+pub mod settings_editor;
+pub mod tls_editor;
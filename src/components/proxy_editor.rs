@@ -0,0 +1,193 @@
+use crate::config::{ProxyConfig, ProxyMode};
+use gpui::prelude::*;
+use gpui::*;
+use gpui_component::{
+    button::Button,
+    input::{Input, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
+    v_flex, ActiveTheme, Sizable,
+};
+
+/// Proxy mode and manual proxy inputs for all requests or for one environment
+pub struct ProxyEditor {
+    /// `None` leaves an environment on the proxy of all requests
+    pub mode: Option<ProxyMode>,
+    /// Edits an environment, which may use the proxy of all requests
+    inheritable: bool,
+    http: Entity<InputState>,
+    https: Entity<InputState>,
+    username: Entity<InputState>,
+    password: Entity<InputState>,
+    no_proxy: Entity<InputState>,
+}
+
+impl ProxyEditor {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let http = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("http://proxy:3128 or socks5://127.0.0.1:1080")
+        });
+        let https = cx.new(|cx| InputState::new(window, cx).placeholder("Same as HTTP"));
+        let username = cx.new(|cx| InputState::new(window, cx).placeholder("Optional"));
+        let password = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Optional")
+                .masked(true)
+        });
+        let no_proxy = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("localhost, .internal.example.com, 10.0.0.0/8")
+        });
+        Self {
+            mode: Some(ProxyMode::System),
+            inheritable: false,
+            http,
+            https,
+            username,
+            password,
+            no_proxy,
+        }
+    }
+
+    /// Proxy as currently entered; `None` when an environment uses the proxy of all
+    /// requests
+    pub fn proxy(&self, cx: &App) -> Option<ProxyConfig> {
+        let value = |input: &Entity<InputState>| input.read(cx).value().trim().to_string();
+        let mode = self.mode?;
+        // Manual settings are dropped with the other modes so they don't linger unseen
+        let manual = |input| {
+            if mode == ProxyMode::Manual {
+                value(input)
+            } else {
+                String::new()
+            }
+        };
+        Some(ProxyConfig {
+            mode,
+            http: manual(&self.http),
+            https: manual(&self.https),
+            username: manual(&self.username),
+            password: manual(&self.password),
+            no_proxy: manual(&self.no_proxy),
+        })
+    }
+
+    /// Fill the inputs from a saved proxy; `inheritable` edits an environment, whose
+    /// `proxy` is `None` while it uses the proxy of all requests
+    pub fn set_proxy(
+        &mut self,
+        proxy: Option<&ProxyConfig>,
+        inheritable: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let empty = ProxyConfig::default();
+        let config = proxy.unwrap_or(&empty);
+        for (input, value) in [
+            (&self.http, &config.http),
+            (&self.https, &config.https),
+            (&self.username, &config.username),
+            (&self.password, &config.password),
+            (&self.no_proxy, &config.no_proxy),
+        ] {
+            let value = value.clone();
+            input.update(cx, |state, cx| state.set_value(value, window, cx));
+        }
+        self.inheritable = inheritable;
+        self.mode = match proxy {
+            Some(config) => Some(config.mode),
+            None if inheritable => None,
+            None => Some(ProxyMode::default()),
+        };
+        cx.notify();
+    }
+
+    fn render_label(label: &'static str, cx: &Context<Self>) -> Div {
+        div()
+            .w(px(96.0))
+            .flex_shrink_0()
+            .text_xs()
+            .font_weight(FontWeight::SEMIBOLD)
+            .text_color(cx.theme().muted_foreground)
+            .child(label)
+    }
+
+    fn render_field(
+        label: &'static str,
+        input: &Entity<InputState>,
+        secret: bool,
+        cx: &Context<Self>,
+    ) -> Div {
+        let input = Input::new(input).appearance(false);
+        let input = if secret { input.mask_toggle() } else { input };
+
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .mb_2()
+            .child(Self::render_label(label, cx))
+            .child(
+                div()
+                    .flex_1()
+                    .p_2()
+                    .rounded(px(6.0))
+                    .bg(cx.theme().muted)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(input),
+            )
+    }
+}
+
+impl Render for ProxyEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let mode = self.mode;
+        let options: Vec<Option<ProxyMode>> = self
+            .inheritable
+            .then_some(None)
+            .into_iter()
+            .chain(ProxyMode::ALL.into_iter().map(Some))
+            .collect();
+        let label = |mode: Option<ProxyMode>| mode.map_or("Same as All Requests", |m| m.label());
+
+        v_flex()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .mb_2()
+                    .child(Self::render_label("Proxy", cx))
+                    .child(
+                        Button::new("proxy-mode")
+                            .outline()
+                            .small()
+                            .label(label(mode))
+                            .dropdown_menu(move |menu, _, _| {
+                                let mut menu = menu;
+                                for option in options.iter().copied() {
+                                    let view = view.clone();
+                                    menu = menu.item(
+                                        PopupMenuItem::new(label(option))
+                                            .checked(option == mode)
+                                            .on_click(move |_, _, cx| {
+                                                let _ = view.update(cx, |editor, cx| {
+                                                    editor.mode = option;
+                                                    cx.notify();
+                                                });
+                                            }),
+                                    );
+                                }
+                                menu
+                            }),
+                    ),
+            )
+            .when(mode == Some(ProxyMode::Manual), |this| {
+                this.child(Self::render_field("HTTP Proxy", &self.http, false, cx))
+                    .child(Self::render_field("HTTPS Proxy", &self.https, false, cx))
+                    .child(Self::render_field("Username", &self.username, false, cx))
+                    .child(Self::render_field("Password", &self.password, true, cx))
+                    .child(Self::render_field("Bypass", &self.no_proxy, false, cx))
+            })
+    }
+}
@@ -0,0 +1,256 @@
+use crate::types::{HttpVersion, RequestSettings};
+use gpui::prelude::*;
+use gpui::*;
+use gpui_component::{
+    button::Button,
+    input::{Input, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
+    v_flex, ActiveTheme, Sizable,
+};
+
+/// Timeout, redirect, download size and HTTP version inputs of a request's Settings
+/// tab, or of the folder defaults. Empty inputs leave the setting to the level below.
+pub struct SettingsEditor {
+    pub http_version: Option<HttpVersion>,
+    pub keep_authorization: Option<bool>,
+    /// Edits the folder defaults, which fall back to the built-in values
+    folder: bool,
+    connect_timeout: Entity<InputState>,
+    timeout: Entity<InputState>,
+    max_redirects: Entity<InputState>,
+    max_download: Entity<InputState>,
+}
+
+impl SettingsEditor {
+    pub fn new(folder: bool, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let placeholder = |value: String| {
+            if folder {
+                value
+            } else {
+                "Folder default".to_string()
+            }
+        };
+        let connect_timeout = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(placeholder(
+                RequestSettings::DEFAULT_CONNECT_TIMEOUT_MS.to_string(),
+            ))
+        });
+        let timeout = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(placeholder(RequestSettings::DEFAULT_TIMEOUT_MS.to_string()))
+        });
+        let max_redirects = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(placeholder(
+                RequestSettings::DEFAULT_MAX_REDIRECTS.to_string(),
+            ))
+        });
+        let max_download = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(placeholder(
+                RequestSettings::DEFAULT_MAX_DOWNLOAD_MB.to_string(),
+            ))
+        });
+        Self {
+            http_version: None,
+            keep_authorization: None,
+            folder,
+            connect_timeout,
+            timeout,
+            max_redirects,
+            max_download,
+        }
+    }
+
+    /// Settings as currently entered; inputs that aren't numbers count as unset
+    pub fn settings(&self, cx: &App) -> RequestSettings {
+        let value = |input: &Entity<InputState>| input.read(cx).value().trim().to_string();
+        RequestSettings {
+            connect_timeout_ms: value(&self.connect_timeout).parse().ok(),
+            timeout_ms: value(&self.timeout).parse().ok(),
+            max_redirects: value(&self.max_redirects).parse().ok(),
+            http_version: self.http_version,
+            keep_authorization: self.keep_authorization,
+            max_download_mb: value(&self.max_download).parse().ok(),
+        }
+    }
+
+    /// Fill the inputs from saved settings
+    pub fn set_settings(
+        &mut self,
+        settings: &RequestSettings,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let text = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+        for (input, value) in [
+            (&self.connect_timeout, text(settings.connect_timeout_ms)),
+            (&self.timeout, text(settings.timeout_ms)),
+            (
+                &self.max_redirects,
+                text(settings.max_redirects.map(u64::from)),
+            ),
+            (&self.max_download, text(settings.max_download_mb)),
+        ] {
+            input.update(cx, |state, cx| state.set_value(value, window, cx));
+        }
+        self.http_version = settings.http_version;
+        self.keep_authorization = settings.keep_authorization;
+        cx.notify();
+    }
+
+    /// Label of the choice that leaves a setting to the level below
+    fn default_label(&self) -> &'static str {
+        if self.folder {
+            "Default"
+        } else {
+            "Folder Default"
+        }
+    }
+
+    fn render_label(label: &'static str, cx: &Context<Self>) -> Div {
+        div()
+            .w(px(160.0))
+            .flex_shrink_0()
+            .text_xs()
+            .font_weight(FontWeight::SEMIBOLD)
+            .text_color(cx.theme().muted_foreground)
+            .child(label)
+    }
+
+    fn render_field(
+        label: &'static str,
+        unit: &'static str,
+        input: &Entity<InputState>,
+        cx: &Context<Self>,
+    ) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .mb_2()
+            .child(Self::render_label(label, cx))
+            .child(
+                div()
+                    .w(px(160.0))
+                    .p_2()
+                    .rounded(px(6.0))
+                    .bg(cx.theme().muted)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(Input::new(input).appearance(false)),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(unit),
+            )
+    }
+}
+
+impl Render for SettingsEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let default_label = self.default_label();
+        let http_version = self.http_version;
+        let keep_authorization = self.keep_authorization;
+        let keep_label = move |keep: Option<bool>| match keep {
+            None => default_label,
+            Some(true) => "Keep",
+            Some(false) => "Remove",
+        };
+
+        v_flex()
+            .child(Self::render_field(
+                "Connect Timeout",
+                "ms, 0 for none",
+                &self.connect_timeout,
+                cx,
+            ))
+            .child(Self::render_field(
+                "Read Timeout",
+                "ms for the response and each pause in its body, 0 for none",
+                &self.timeout,
+                cx,
+            ))
+            .child(Self::render_field(
+                "Max Redirects",
+                "0 to not follow redirects",
+                &self.max_redirects,
+                cx,
+            ))
+            .child(Self::render_field(
+                "Max Download Size",
+                "MB kept in memory, 0 for no limit; file downloads are not limited",
+                &self.max_download,
+                cx,
+            ))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .mb_2()
+                    .child(Self::render_label("HTTP Version", cx))
+                    .child({
+                        let view = view.clone();
+                        Button::new("http-version")
+                            .outline()
+                            .small()
+                            .label(http_version.map_or(default_label, |v| v.label()))
+                            .dropdown_menu(move |menu, _, _| {
+                                let mut menu = menu;
+                                for option in std::iter::once(None)
+                                    .chain(HttpVersion::ALL.into_iter().map(Some))
+                                {
+                                    let view = view.clone();
+                                    menu = menu.item(
+                                        PopupMenuItem::new(
+                                            option.map_or(default_label, |v| v.label()),
+                                        )
+                                        .checked(option == http_version)
+                                        .on_click(
+                                            move |_, _, cx| {
+                                                let _ = view.update(cx, |editor, cx| {
+                                                    editor.http_version = option;
+                                                    cx.notify();
+                                                });
+                                            },
+                                        ),
+                                    );
+                                }
+                                menu
+                            })
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .child(Self::render_label("Auth on Cross-Host Redirect", cx))
+                    .child(
+                        Button::new("keep-authorization")
+                            .outline()
+                            .small()
+                            .label(keep_label(keep_authorization))
+                            .dropdown_menu(move |menu, _, _| {
+                                let mut menu = menu;
+                                for option in [None, Some(true), Some(false)] {
+                                    let view = view.clone();
+                                    menu = menu.item(
+                                        PopupMenuItem::new(keep_label(option))
+                                            .checked(option == keep_authorization)
+                                            .on_click(move |_, _, cx| {
+                                                let _ = view.update(cx, |editor, cx| {
+                                                    editor.keep_authorization = option;
+                                                    cx.notify();
+                                                });
+                                            }),
+                                    );
+                                }
+                                menu
+                            }),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,203 @@
+use crate::config::TlsConfig;
+use gpui::prelude::*;
+use gpui::*;
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    input::{Input, InputState},
+    v_flex, ActiveTheme, IconName, Sizable,
+};
+
+struct TlsRow {
+    host: Entity<InputState>,
+    ca_files: Entity<InputState>,
+    client_cert: Entity<InputState>,
+    client_key: Entity<InputState>,
+    passphrase: Entity<InputState>,
+    skip_verification: bool,
+}
+
+impl TlsRow {
+    fn new(window: &mut Window, cx: &mut App) -> Self {
+        let input = |placeholder: &'static str, window: &mut Window, cx: &mut App| {
+            cx.new(|cx| InputState::new(window, cx).placeholder(placeholder))
+        };
+        Self {
+            host: input("api.example.com or *.example.com", window, cx),
+            ca_files: input("/path/ca.pem; /path/other-ca.pem", window, cx),
+            client_cert: input("/path/client.pem or /path/client.p12", window, cx),
+            client_key: input(
+                "/path/client-key.pem, if not in the certificate",
+                window,
+                cx,
+            ),
+            passphrase: cx.new(|cx| {
+                InputState::new(window, cx)
+                    .placeholder("PKCS#12 passphrase")
+                    .masked(true)
+            }),
+            skip_verification: false,
+        }
+    }
+}
+
+/// CA bundles, client certificates and verification per host
+pub struct TlsEditor {
+    rows: Vec<TlsRow>,
+}
+
+impl TlsEditor {
+    pub fn new(_window: &mut Window, _cx: &mut Context<Self>) -> Self {
+        Self { rows: Vec::new() }
+    }
+
+    /// Hosts as currently entered; rows without a host are dropped
+    pub fn hosts(&self, cx: &App) -> Vec<TlsConfig> {
+        let value = |input: &Entity<InputState>| input.read(cx).value().trim().to_string();
+        self.rows
+            .iter()
+            .filter(|row| !value(&row.host).is_empty())
+            .map(|row| TlsConfig {
+                host: value(&row.host),
+                ca_files: value(&row.ca_files)
+                    .split(';')
+                    .map(|path| path.trim().to_string())
+                    .filter(|path| !path.is_empty())
+                    .collect(),
+                client_cert: value(&row.client_cert),
+                client_key: value(&row.client_key),
+                passphrase: value(&row.passphrase),
+                skip_verification: row.skip_verification,
+            })
+            .collect()
+    }
+
+    /// Fill the rows from saved host settings
+    pub fn set_hosts(&mut self, hosts: &[TlsConfig], window: &mut Window, cx: &mut Context<Self>) {
+        self.rows = hosts
+            .iter()
+            .map(|tls| {
+                let row = TlsRow::new(window, cx);
+                for (input, value) in [
+                    (&row.host, tls.host.clone()),
+                    (&row.ca_files, tls.ca_files.join("; ")),
+                    (&row.client_cert, tls.client_cert.clone()),
+                    (&row.client_key, tls.client_key.clone()),
+                    (&row.passphrase, tls.passphrase.clone()),
+                ] {
+                    input.update(cx, |state, cx| state.set_value(value, window, cx));
+                }
+                TlsRow {
+                    skip_verification: tls.skip_verification,
+                    ..row
+                }
+            })
+            .collect();
+        cx.notify();
+    }
+
+    fn add_host(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rows.push(TlsRow::new(window, cx));
+        cx.notify();
+    }
+
+    fn render_field(
+        label: &'static str,
+        input: &Entity<InputState>,
+        secret: bool,
+        cx: &Context<Self>,
+    ) -> Div {
+        let input = Input::new(input).appearance(false);
+        let input = if secret { input.mask_toggle() } else { input };
+
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .mb_2()
+            .child(
+                div()
+                    .w(px(96.0))
+                    .flex_shrink_0()
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().muted_foreground)
+                    .child(label),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .p_2()
+                    .rounded(px(6.0))
+                    .bg(cx.theme().muted)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(input),
+            )
+    }
+}
+
+impl Render for TlsEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_3()
+            .children(self.rows.iter().enumerate().map(|(index, row)| {
+                v_flex()
+                    .p_3()
+                    .rounded(px(6.0))
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(Self::render_field("Host", &row.host, false, cx))
+                    .child(Self::render_field("CA Files", &row.ca_files, false, cx))
+                    .child(Self::render_field(
+                        "Client Cert",
+                        &row.client_cert,
+                        false,
+                        cx,
+                    ))
+                    .child(Self::render_field("Client Key", &row.client_key, false, cx))
+                    .child(Self::render_field("Passphrase", &row.passphrase, true, cx))
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                Checkbox::new(("skip-verification", index))
+                                    .label("Skip certificate verification")
+                                    .checked(row.skip_verification)
+                                    .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                                        if let Some(row) = this.rows.get_mut(index) {
+                                            row.skip_verification = *checked;
+                                            cx.notify();
+                                        }
+                                    })),
+                            )
+                            .child(
+                                Button::new(("remove-tls-host", index))
+                                    .ghost()
+                                    .xsmall()
+                                    .icon(IconName::Delete)
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        if index < this.rows.len() {
+                                            this.rows.remove(index);
+                                            cx.notify();
+                                        }
+                                    })),
+                            ),
+                    )
+            }))
+            .child(
+                div().child(
+                    Button::new("add-tls-host")
+                        .outline()
+                        .small()
+                        .icon(IconName::Plus)
+                        .label("Add Host")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.add_host(window, cx);
+                        })),
+                ),
+            )
+    }
+}
@@ -1,10 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Application configuration
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct AppConfig {
     pub last_opened_folder: Option<PathBuf>,
+    /// Proxy for all requests
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    /// Proxies used instead of `proxy` while an environment of that name is active
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub environment_proxies: HashMap<String, ProxyConfig>,
+    /// Certificate settings per host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tls: Vec<TlsConfig>,
 }
 
 impl AppConfig {
@@ -33,4 +43,109 @@ impl AppConfig {
             let _ = std::fs::write(path, content);
         }
     }
+
+    /// Proxy for requests sent with `environment` active
+    pub fn proxy_for(&self, environment: Option<&str>) -> ProxyConfig {
+        environment
+            .and_then(|name| self.environment_proxies.get(name))
+            .unwrap_or(&self.proxy)
+            .clone()
+    }
+}
+
+/// Certificate settings for requests to one host
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Host name, or `*.example.com` for every subdomain of `example.com`
+    pub host: String,
+    /// PEM files of certificate authorities trusted on top of the system ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ca_files: Vec<String>,
+    /// Client certificate: a PKCS#12 archive, or a PEM certificate chain
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub client_cert: String,
+    /// Unencrypted PEM key (PKCS#8, PKCS#1 or SEC1) of a PEM client certificate, when
+    /// it isn't in `client_cert` itself
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub client_key: String,
+    /// Passphrase of a PKCS#12 archive
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub passphrase: String,
+    /// Accept any server certificate and host name
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_verification: bool,
+}
+
+impl TlsConfig {
+    pub fn matches(&self, host: &str) -> bool {
+        let pattern = self.host.trim().to_ascii_lowercase();
+        let host = host.to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{}", domain)),
+            None => !pattern.is_empty() && host == pattern,
+        }
+    }
+}
+
+/// Where the proxy for a request comes from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProxyMode {
+    /// `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY` from the process environment
+    #[default]
+    System,
+    Direct,
+    Manual,
+}
+
+impl ProxyMode {
+    pub const ALL: [ProxyMode; 3] = [ProxyMode::System, ProxyMode::Direct, ProxyMode::Manual];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProxyMode::System => "System (HTTP_PROXY / NO_PROXY)",
+            ProxyMode::Direct => "No Proxy",
+            ProxyMode::Manual => "Manual",
+        }
+    }
+}
+
+/// Proxy settings; proxy URLs look like `http://proxy:3128` or `socks5://127.0.0.1:1080`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    #[serde(default)]
+    pub mode: ProxyMode,
+    /// Proxy for `http://` URLs
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub http: String,
+    /// Proxy for `https://` URLs; when empty `http` is used for both
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub https: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub username: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password: String,
+    /// Comma separated hosts, domains (matching subdomains too), IPs or CIDR ranges
+    /// that are reached without the proxy
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub no_proxy: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_hosts_and_wildcards() {
+        let tls = |host: &str| TlsConfig {
+            host: host.to_string(),
+            ..Default::default()
+        };
+        assert!(tls("api.example.com").matches("API.example.com"));
+        assert!(!tls("api.example.com").matches("example.com"));
+        assert!(tls("*.example.com").matches("a.b.example.com"));
+        assert!(!tls("*.example.com").matches("example.com"));
+        assert!(!tls("*.example.com").matches("badexample.com"));
+        assert!(!tls(" ").matches(""));
+    }
 }
@@ -0,0 +1,437 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::history;
+use crate::request::RequestContext;
+
+/// A cookie received in a `Set-Cookie` header
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Host the cookie belongs to, without a leading dot
+    pub domain: String,
+    /// Set by a `Domain` attribute: subdomains of `domain` get the cookie too
+    #[serde(default)]
+    pub include_subdomains: bool,
+    pub path: String,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    /// Unix timestamp in milliseconds; session cookies have none and are kept until cleared
+    #[serde(default)]
+    pub expires: Option<u64>,
+}
+
+impl Cookie {
+    /// Parse a `Set-Cookie` header received from `url`; `None` if it is malformed or
+    /// names a domain the URL doesn't belong to
+    fn parse(header: &str, url: &reqwest::Url) -> Option<Self> {
+        let host = url.host_str()?.to_ascii_lowercase();
+        let mut parts = header.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Cookie {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain: host.clone(),
+            include_subdomains: false,
+            path: default_path(url.path()),
+            secure: false,
+            http_only: false,
+            expires: None,
+        };
+        let mut max_age = None;
+
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    // A cookie may only be set for the host itself or a parent domain
+                    let allowed = domain == host
+                        || (domain.contains('.') && host.ends_with(&format!(".{}", domain)));
+                    if !allowed {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.include_subdomains = true;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "expires" => {
+                    if let Some(expires) = parse_date(value) {
+                        cookie.expires = Some(expires);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Max-Age wins over Expires; zero or less deletes the cookie
+        if let Some(seconds) = max_age {
+            let now = history::now_millis();
+            cookie.expires = Some(if seconds <= 0 {
+                0
+            } else {
+                now.saturating_add((seconds as u64).saturating_mul(1000))
+            });
+        }
+        Some(cookie)
+    }
+
+    /// Same name, domain and path: a cookie that replaces `other` when received
+    fn same_as(&self, other: &Cookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, url: &reqwest::Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let domain_matches = host == self.domain
+            || (self.include_subdomains && host.ends_with(&format!(".{}", self.domain)));
+        let path = url.path();
+        let path_matches = path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')));
+        domain_matches && path_matches && (!self.secure || url.scheme() == "https")
+    }
+}
+
+/// Directory of a request path, used when a cookie doesn't set its own path
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => path[..end].to_string(),
+    }
+}
+
+/// Parse the date formats found in `Expires` attributes into Unix milliseconds
+fn parse_date(text: &str) -> Option<u64> {
+    const FORMATS: [&str; 4] = [
+        "%a, %d %b %Y %H:%M:%S GMT",
+        "%a, %d-%b-%Y %H:%M:%S GMT",
+        "%A, %d-%b-%y %H:%M:%S GMT",
+        "%a %b %e %H:%M:%S %Y",
+    ];
+    FORMATS.iter().find_map(|format| {
+        let time = NaiveDateTime::parse_from_str(text, format).ok()?;
+        Some(time.and_utc().timestamp_millis().max(0) as u64)
+    })
+}
+
+/// Cookies received while sending requests from one folder and environment
+#[derive(Clone, Debug, Default)]
+pub struct CookieJar {
+    pub cookies: Vec<Cookie>,
+    /// `cookies` as loaded, to tell what changed when saving
+    loaded: Vec<Cookie>,
+}
+
+/// Held while the cookie store is read, changed and written back, so requests
+/// finishing at the same time don't drop each other's cookies
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Every cookie jar on this machine, kept outside the request folder so session
+/// cookies never end up in git
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CookieStore {
+    #[serde(default)]
+    jars: HashMap<String, Vec<Cookie>>,
+}
+
+impl CookieStore {
+    fn path(context: &RequestContext) -> PathBuf {
+        context.store_path("cookies.json")
+    }
+
+    fn load(context: &RequestContext) -> Self {
+        if let Ok(content) = std::fs::read_to_string(Self::path(context)) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    fn save(&self, context: &RequestContext) {
+        let path = Self::path(context);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        // Written aside and moved into place so a reader never sees half a file
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            let temp = path.with_extension("json.tmp");
+            if std::fs::write(&temp, content).is_ok() {
+                let _ = std::fs::rename(temp, path);
+            }
+        }
+    }
+}
+
+impl CookieJar {
+    /// The jar of a folder and environment, without cookies that have expired since
+    pub fn load(context: &RequestContext) -> Self {
+        let now = history::now_millis();
+        let mut cookies = CookieStore::load(context)
+            .jars
+            .remove(&context.key())
+            .unwrap_or_default();
+        cookies.retain(|cookie| !cookie.is_expired(now));
+        Self {
+            loaded: cookies.clone(),
+            cookies,
+        }
+    }
+
+    /// Write the cookies added, changed and removed since `load` to the store. Cookies
+    /// other requests stored in the meantime are kept and show up in this jar afterwards.
+    pub fn save(&mut self, context: &RequestContext) {
+        let _lock = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let now = history::now_millis();
+        let mut store = CookieStore::load(context);
+        let mut cookies = store.jars.remove(&context.key()).unwrap_or_default();
+
+        let removed: Vec<&Cookie> = self
+            .loaded
+            .iter()
+            .filter(|loaded| !self.cookies.iter().any(|cookie| cookie.same_as(loaded)))
+            .collect();
+        cookies.retain(|stored| {
+            !stored.is_expired(now) && !removed.iter().any(|cookie| cookie.same_as(stored))
+        });
+        for cookie in self
+            .cookies
+            .iter()
+            .filter(|cookie| !self.loaded.contains(cookie))
+        {
+            match cookies.iter_mut().find(|stored| stored.same_as(cookie)) {
+                Some(stored) => *stored = cookie.clone(),
+                None => cookies.push(cookie.clone()),
+            }
+        }
+
+        if !cookies.is_empty() {
+            store.jars.insert(context.key(), cookies.clone());
+        }
+        store.save(context);
+        self.loaded = cookies.clone();
+        self.cookies = cookies;
+    }
+
+    /// `Cookie` header value for a request to `url`, longest paths first
+    pub fn header(&self, url: &reqwest::Url) -> Option<String> {
+        let now = history::now_millis();
+        let mut matching: Vec<&Cookie> = self
+            .cookies
+            .iter()
+            .filter(|cookie| !cookie.is_expired(now) && cookie.matches(url))
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        matching.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        Some(
+            matching
+                .iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    /// Take in the `Set-Cookie` headers of a response to `url`
+    pub fn store_response(&mut self, url: &reqwest::Url, headers: &[(String, String)]) {
+        let now = history::now_millis();
+        for (_, value) in headers
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case("set-cookie"))
+        {
+            let Some(cookie) = Cookie::parse(value, url) else {
+                continue;
+            };
+            self.cookies.retain(|existing| !existing.same_as(&cookie));
+            if !cookie.is_expired(now) {
+                self.cookies.push(cookie);
+            }
+        }
+    }
+
+    /// Domains that have cookies, sorted
+    pub fn domains(&self) -> Vec<String> {
+        let mut domains: Vec<String> = self
+            .cookies
+            .iter()
+            .map(|cookie| cookie.domain.clone())
+            .collect();
+        domains.sort();
+        domains.dedup();
+        domains
+    }
+
+    pub fn clear_domain(&mut self, domain: &str) {
+        self.cookies.retain(|cookie| cookie.domain != domain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_jars_keep_each_others_cookies() {
+        let dir = std::env::temp_dir().join(format!("api-client-cookies-{}", std::process::id()));
+        let context = RequestContext {
+            folder: Some(dir.clone()),
+            store_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+        let url = reqwest::Url::parse("https://example.com/").unwrap();
+        let set_cookie = |value: &str| vec![("Set-Cookie".to_string(), value.to_string())];
+        let mut jar = CookieJar::load(&context);
+        jar.store_response(&url, &set_cookie("old=1"));
+        jar.save(&context);
+
+        // Two requests in flight load the same jar and finish one after the other
+        let mut first = CookieJar::load(&context);
+        let mut second = CookieJar::load(&context);
+        first.store_response(&url, &set_cookie("a=1"));
+        second.store_response(&url, &set_cookie("b=2"));
+        second.store_response(&url, &set_cookie("old=; Max-Age=0"));
+        first.save(&context);
+        second.save(&context);
+
+        assert_eq!(second.header(&url).as_deref(), Some("a=1; b=2"));
+        assert_eq!(
+            CookieJar::load(&context).header(&url).as_deref(),
+            Some("a=1; b=2")
+        );
+
+        let mut jar = CookieJar::load(&context);
+        jar.cookies.clear();
+        jar.save(&context);
+        assert!(CookieJar::load(&context).cookies.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    fn parse(header: &str, url: &str) -> Option<Cookie> {
+        Cookie::parse(header, &reqwest::Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_set_cookie_headers() {
+        let cookie = parse(" id = 42 ; Secure; HTTPONLY", "http://Example.com/a/b/c").unwrap();
+        assert_eq!(
+            cookie,
+            Cookie {
+                name: "id".to_string(),
+                value: "42".to_string(),
+                domain: "example.com".to_string(),
+                include_subdomains: false,
+                path: "/a/b".to_string(),
+                secure: true,
+                http_only: true,
+                expires: None,
+            }
+        );
+        assert_eq!(parse("id=1", "http://example.com/a").unwrap().path, "/");
+        assert_eq!(
+            parse("id=1; Path=/x", "http://example.com/a/b")
+                .unwrap()
+                .path,
+            "/x"
+        );
+        assert_eq!(
+            parse("id=1; Path=x", "http://example.com/a/b")
+                .unwrap()
+                .path,
+            "/a"
+        );
+        assert_eq!(parse("empty=", "http://example.com").unwrap().value, "");
+
+        assert!(parse("no-value", "http://example.com").is_none());
+        assert!(parse("=value", "http://example.com").is_none());
+    }
+
+    #[test]
+    fn checks_the_domain_attribute() {
+        let cookie = parse("id=1; Domain=.Example.com", "https://api.example.com").unwrap();
+        assert_eq!(cookie.domain, "example.com");
+        assert!(cookie.include_subdomains);
+        assert!(parse("id=1; Domain=api.example.com", "https://api.example.com").is_some());
+
+        assert!(parse("id=1; Domain=other.com", "https://example.com").is_none());
+        assert!(parse("id=1; Domain=ample.com", "https://example.com").is_none());
+        assert!(parse("id=1; Domain=com", "https://example.com").is_none());
+        assert!(parse("id=1; Domain=www.example.com", "https://example.com").is_none());
+    }
+
+    #[test]
+    fn reads_expiry_dates() {
+        let url = "https://example.com";
+        let expires = |header: &str| parse(header, url).unwrap().expires;
+        for date in [
+            "Wed, 21 Oct 2015 07:28:00 GMT",
+            "Wed, 21-Oct-2015 07:28:00 GMT",
+            "Wednesday, 21-Oct-15 07:28:00 GMT",
+            "Wed Oct 21 07:28:00 2015",
+        ] {
+            assert_eq!(
+                expires(&format!("id=1; Expires={}", date)),
+                Some(1_445_412_480_000),
+                "{}",
+                date
+            );
+        }
+        assert_eq!(expires("id=1; Expires=tomorrow"), None);
+
+        // Max-Age wins over Expires in either order
+        let now = history::now_millis();
+        let max_age = expires("id=1; Max-Age=60; Expires=Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert!((now + 60_000..now + 70_000).contains(&max_age));
+        assert_eq!(expires("id=1; Max-Age=0"), Some(0));
+        assert_eq!(expires("id=1; Max-Age=-5"), Some(0));
+        assert_eq!(expires("id=1; Max-Age=9223372036854775807"), Some(u64::MAX));
+    }
+
+    #[test]
+    fn matches_request_urls() {
+        let cookie = parse("id=1; Path=/api", "https://example.com").unwrap();
+        let matches = |url: &str| cookie.matches(&reqwest::Url::parse(url).unwrap());
+        assert!(matches("https://example.com/api"));
+        assert!(matches("https://EXAMPLE.com/api/users"));
+        assert!(matches("http://example.com/api?q=1"));
+        assert!(!matches("https://example.com/apis"));
+        assert!(!matches("https://example.com/"));
+        assert!(!matches("https://www.example.com/api"));
+
+        let cookie = parse(
+            "id=1; Domain=example.com; Path=/api/; Secure",
+            "https://example.com",
+        )
+        .unwrap();
+        let matches = |url: &str| cookie.matches(&reqwest::Url::parse(url).unwrap());
+        assert!(matches("https://www.example.com/api/users"));
+        assert!(matches("https://example.com/api/"));
+        assert!(!matches("https://example.com/api"));
+        assert!(!matches("http://example.com/api/users"));
+        assert!(!matches("https://notexample.com/api/users"));
+    }
+}
@@ -0,0 +1,603 @@
+use crate::request;
+use crate::types::{AuthConfig, BodyMode, HttpMethod, SavedKeyValue, SavedRequest};
+
+/// Options that consume the next argument but don't affect the request, so that
+/// argument isn't taken for the URL
+const IGNORED_WITH_VALUE: &[&str] = &[
+    "--abstract-unix-socket",
+    "--alt-svc",
+    "--aws-sigv4",
+    "--cacert",
+    "--capath",
+    "--cert",
+    "-E",
+    "--cert-type",
+    "--ciphers",
+    "--connect-timeout",
+    "--connect-to",
+    "-C",
+    "--continue-at",
+    "-c",
+    "--cookie-jar",
+    "--create-file-mode",
+    "--crlfile",
+    "--curves",
+    "--delegation",
+    "--dns-interface",
+    "--dns-ipv4-addr",
+    "--dns-ipv6-addr",
+    "--dns-servers",
+    "--doh-url",
+    "-D",
+    "--dump-header",
+    "--ech",
+    "--egd-file",
+    "--engine",
+    "--etag-compare",
+    "--etag-save",
+    "--expect100-timeout",
+    "--ftp-account",
+    "--ftp-alternative-to-user",
+    "--ftp-method",
+    "-P",
+    "--ftp-port",
+    "--ftp-ssl-ccc-mode",
+    "--happy-eyeballs-timeout-ms",
+    "--haproxy-clientip",
+    "--hostpubmd5",
+    "--hostpubsha256",
+    "--hsts",
+    "--interface",
+    "--ip-tos",
+    "--ipfs-gateway",
+    "--keepalive-cnt",
+    "--keepalive-time",
+    "--key",
+    "--key-type",
+    "--krb",
+    "--libcurl",
+    "--limit-rate",
+    "--local-port",
+    "--login-options",
+    "--mail-auth",
+    "--mail-from",
+    "--mail-rcpt",
+    "--max-filesize",
+    "--max-redirs",
+    "-m",
+    "--max-time",
+    "--netrc-file",
+    "--noproxy",
+    "-o",
+    "--output",
+    "--output-dir",
+    "--parallel-max",
+    "--pass",
+    "--pinnedpubkey",
+    "--preproxy",
+    "--proto",
+    "--proto-default",
+    "--proto-redir",
+    "-x",
+    "--proxy",
+    "--proxy-cacert",
+    "--proxy-capath",
+    "--proxy-cert",
+    "--proxy-cert-type",
+    "--proxy-ciphers",
+    "--proxy-crlfile",
+    "--proxy-header",
+    "--proxy-key",
+    "--proxy-key-type",
+    "--proxy-pass",
+    "--proxy-pinnedpubkey",
+    "--proxy-service-name",
+    "--proxy-tls13-ciphers",
+    "--proxy-tlsauthtype",
+    "--proxy-tlspassword",
+    "--proxy-tlsuser",
+    "-U",
+    "--proxy-user",
+    "--proxy1.0",
+    "--pubkey",
+    "-Q",
+    "--quote",
+    "--random-file",
+    "--rate",
+    "--request-target",
+    "--resolve",
+    "--retry",
+    "--retry-delay",
+    "--retry-max-time",
+    "--sasl-authzid",
+    "--service-name",
+    "--socks4",
+    "--socks4a",
+    "--socks5",
+    "--socks5-gssapi-service",
+    "--socks5-hostname",
+    "-Y",
+    "--speed-limit",
+    "-y",
+    "--speed-time",
+    "--stderr",
+    "-t",
+    "--telnet-option",
+    "--tftp-blksize",
+    "-z",
+    "--time-cond",
+    "--tls-max",
+    "--tls13-ciphers",
+    "--tlsauthtype",
+    "--tlspassword",
+    "--tlsuser",
+    "--trace",
+    "--trace-ascii",
+    "--trace-config",
+    "--unix-socket",
+    "--variable",
+    "--vlan-priority",
+    "-w",
+    "--write-out",
+];
+
+/// Short options that take a value, attached as in `-XPOST` or in the next argument
+const SHORT_WITH_VALUE: &str = "XHduFAebomxwcETUrKCDPQtyYz";
+
+/// Payload of a `-d` style option
+enum DataPart {
+    /// Sent as written
+    Raw(String),
+    /// `--data-urlencode`: the content after `=` is encoded first
+    UrlEncode(String),
+}
+
+/// Parse a `curl ...` command line into a request named `name`
+pub fn parse(command: &str, name: &str) -> Result<SavedRequest, String> {
+    let args = split_args(command)?;
+    let mut args = args.into_iter();
+
+    match args.next() {
+        Some(program) if program == "curl" || program.ends_with("/curl") => {}
+        _ => return Err("Not a curl command".to_string()),
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers: Vec<SavedKeyValue> = Vec::new();
+    let mut data: Vec<DataPart> = Vec::new();
+    let mut form: Vec<SavedKeyValue> = Vec::new();
+    let mut get = false;
+    let mut credentials: Option<(String, String)> = None;
+    let mut digest = false;
+    let mut bearer: Option<String> = None;
+    let mut upload: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        for (option, inline) in options(&arg) {
+            let mut value = |option: &str| -> Result<String, String> {
+                match &inline {
+                    Some(value) => Ok(value.clone()),
+                    None => args
+                        .next()
+                        .ok_or_else(|| format!("{} needs a value", option)),
+                }
+            };
+
+            match option.as_str() {
+                "-X" | "--request" => method = Some(value(&option)?.to_uppercase()),
+                "-H" | "--header" => {
+                    let header = value(&option)?;
+                    if let Some((key, val)) = header.split_once(':') {
+                        headers.push(SavedKeyValue::new(key.trim(), val.trim()));
+                    }
+                }
+                // curl drops the line breaks of files read with `-d @file`
+                "-d" | "--data" | "--data-ascii" => {
+                    let text = value(&option)?;
+                    let text = match text.strip_prefix('@') {
+                        Some(path) => read_file(path)?.replace(['\r', '\n'], ""),
+                        None => text,
+                    };
+                    data.push(DataPart::Raw(text));
+                }
+                "--data-binary" => data.push(DataPart::Raw(data_value(value(&option)?)?)),
+                "--data-raw" => data.push(DataPart::Raw(value(&option)?)),
+                "--data-urlencode" => data.push(DataPart::UrlEncode(value(&option)?)),
+                "--json" => {
+                    data.push(DataPart::Raw(data_value(value(&option)?)?));
+                    headers.push(SavedKeyValue::new("Content-Type", "application/json"));
+                    headers.push(SavedKeyValue::new("Accept", "application/json"));
+                }
+                "-F" | "--form" | "--form-string" => {
+                    let field = value(&option)?;
+                    let (key, val) = field.split_once('=').unwrap_or((field.as_str(), ""));
+                    // Drop `;type=` and `;filename=` modifiers of file fields
+                    let val = if val.starts_with('@') {
+                        val.split(';').next().unwrap_or(val)
+                    } else {
+                        val
+                    };
+                    form.push(SavedKeyValue::new(key, val));
+                }
+                "-T" | "--upload-file" => upload = Some(read_file(&value(&option)?)?),
+                "-u" | "--user" => {
+                    let user = value(&option)?;
+                    let (username, password) = user.split_once(':').unwrap_or((&user, ""));
+                    credentials = Some((username.to_string(), password.to_string()));
+                }
+                "--digest" => digest = true,
+                "--oauth2-bearer" => bearer = Some(value(&option)?),
+                "-A" | "--user-agent" => {
+                    headers.push(SavedKeyValue::new("User-Agent", value(&option)?));
+                }
+                "-e" | "--referer" => {
+                    headers.push(SavedKeyValue::new("Referer", value(&option)?));
+                }
+                "-b" | "--cookie" => headers.push(SavedKeyValue::new("Cookie", value(&option)?)),
+                "-r" | "--range" => {
+                    headers.push(SavedKeyValue::new(
+                        "Range",
+                        format!("bytes={}", value(&option)?),
+                    ));
+                }
+                "-G" | "--get" => get = true,
+                "-I" | "--head" => return Err("HEAD requests are not supported".to_string()),
+                "-K" | "--config" => {
+                    return Err("Options from a config file are not supported".to_string())
+                }
+                "--url" => url = Some(value(&option)?),
+                // Responses are always decompressed
+                "--compressed" => {}
+                option if IGNORED_WITH_VALUE.contains(&option) => {
+                    value(option)?;
+                }
+                flag if flag.starts_with('-') && flag.len() > 1 => {}
+                _ if url.is_none() => url = Some(option),
+                extra => return Err(format!("Unexpected argument: {}", extra)),
+            }
+        }
+    }
+
+    let url = url.ok_or("No URL found in the command")?;
+    let (base_url, mut params) = request::split_query(&url);
+
+    let has_body = !data.is_empty() || !form.is_empty();
+    let method = match method {
+        Some(method) => method,
+        None if has_body && !get => "POST".to_string(),
+        // `-T` uploads with PUT
+        None if upload.is_some() => "PUT".to_string(),
+        None => "GET".to_string(),
+    };
+    let method =
+        HttpMethod::parse(&method).ok_or_else(|| format!("Unsupported method {}", method))?;
+
+    let mut saved = SavedRequest::new(name, &method, base_url);
+
+    let body = data
+        .iter()
+        .map(|part| match part {
+            DataPart::Raw(text) => text.clone(),
+            DataPart::UrlEncode(text) => match text.split_once('=') {
+                Some((key, value)) if !key.is_empty() => {
+                    format!("{}={}", key, request::urlencoding(value))
+                }
+                Some((_, value)) => request::urlencoding(value),
+                None => request::urlencoding(text),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    if get {
+        // `-G` moves the data into the query string
+        params.extend(request::parse_query(&body).unwrap_or_default());
+    } else if !form.is_empty() {
+        saved.body_mode = BodyMode::Multipart;
+        saved.form = form;
+    } else if let Some(upload) = upload {
+        saved.body_mode = BodyMode::Text;
+        saved.body = upload;
+    } else if !data.is_empty() {
+        let content_type = headers
+            .iter()
+            .find(|h| h.key.eq_ignore_ascii_case("content-type"))
+            .map(|h| h.value.to_lowercase());
+
+        match content_type {
+            Some(ct) if ct.contains("json") => {
+                saved.body_mode = BodyMode::Json;
+                saved.body = body;
+            }
+            Some(ct) if ct.contains("xml") => {
+                saved.body_mode = BodyMode::Xml;
+                saved.body = body;
+            }
+            Some(ct) if !ct.contains("x-www-form-urlencoded") => {
+                saved.body_mode = BodyMode::Text;
+                saved.body = body;
+            }
+            // curl sends -d data as a url-encoded form unless told otherwise
+            _ => match request::parse_query(&body) {
+                Some(fields) => {
+                    saved.body_mode = BodyMode::FormUrlEncoded;
+                    saved.form = fields;
+                }
+                None => {
+                    if content_type.is_none() {
+                        headers.push(SavedKeyValue::new(
+                            "Content-Type",
+                            "application/x-www-form-urlencoded",
+                        ));
+                    }
+                    saved.body_mode = BodyMode::Text;
+                    saved.body = body;
+                }
+            },
+        }
+    }
+
+    saved.params = params;
+    saved.headers = headers;
+    saved.auth = match credentials {
+        Some((username, password)) if digest => AuthConfig::Digest { username, password },
+        Some((username, password)) => AuthConfig::Basic { username, password },
+        None => match bearer {
+            Some(token) => AuthConfig::Bearer { token },
+            None => AuthConfig::None,
+        },
+    };
+    Ok(saved)
+}
+
+/// Options given by one argument: `--opt=value` is split, and bundled short flags
+/// such as `-sSX POST` are taken one by one until a flag that takes a value, which
+/// gets the rest of the bundle or else the next argument
+fn options(arg: &str) -> Vec<(String, Option<String>)> {
+    if let Some(long) = arg.strip_prefix("--") {
+        return match long.split_once('=') {
+            Some((name, value)) if !name.is_empty() => {
+                vec![(format!("--{}", name), Some(value.to_string()))]
+            }
+            _ => vec![(arg.to_string(), None)],
+        };
+    }
+    let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+        return vec![(arg.to_string(), None)];
+    };
+
+    let mut options = Vec::new();
+    for (i, flag) in flags.char_indices() {
+        let option = format!("-{}", flag);
+        if SHORT_WITH_VALUE.contains(flag) {
+            let rest = &flags[i + flag.len_utf8()..];
+            options.push((option, (!rest.is_empty()).then(|| rest.to_string())));
+            break;
+        }
+        options.push((option, None));
+    }
+    options
+}
+
+/// Value of a data option, read from a file when it starts with `@`
+fn data_value(value: String) -> Result<String, String> {
+    match value.strip_prefix('@') {
+        Some(path) => read_file(path),
+        None => Ok(value),
+    }
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    if path == "-" {
+        return Err("Reading data from stdin is not supported".to_string());
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))
+}
+
+/// Split a shell command into arguments, handling quotes, escapes and line continuations
+fn split_args(command: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                // Line continuation
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(next) => {
+                    current.push(next);
+                    in_arg = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(next) => current.push(next),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(next @ ('"' | '\\' | '$' | '`')) => current.push(next),
+                            Some('\n') => {}
+                            Some(next) => {
+                                current.push('\\');
+                                current.push(next);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(next) => current.push(next),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            // ANSI-C quoting as produced by browser "Copy as cURL"
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(next) => current.push(next),
+                            None => return Err("Unterminated $'' quote".to_string()),
+                        },
+                        Some(next) => current.push(next),
+                        None => return Err("Unterminated $'' quote".to_string()),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header<'a>(request: &'a SavedRequest, key: &str) -> Option<&'a str> {
+        request
+            .headers
+            .iter()
+            .find(|h| h.key == key)
+            .map(|h| h.value.as_str())
+    }
+
+    #[test]
+    fn parses_bundled_and_attached_short_flags() {
+        let request = parse("curl -sX POST https://example.com/users", "r").unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://example.com/users");
+
+        let request = parse("curl -sSLXPUT -HAccept:text/plain https://example.com", "r").unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(header(&request, "Accept"), Some("text/plain"));
+
+        // Flags before the value-taking one in a bundle still apply
+        let request = parse("curl -sGd q=1 https://example.com/search", "r").unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.params, vec![SavedKeyValue::new("q", "1")]);
+
+        let request = parse("curl -X POST --url=https://example.com", "r").unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://example.com");
+
+        assert_eq!(
+            parse("curl https://example.com -sX", "r").unwrap_err(),
+            "-X needs a value"
+        );
+    }
+
+    #[test]
+    fn non_ascii_arguments_do_not_panic() {
+        let request = parse("curl -é -Hé:ü https://example.com", "r").unwrap();
+        assert_eq!(header(&request, "é"), Some("ü"));
+        let request = parse("curl -Xé https://example.com", "r");
+        assert!(request.is_err());
+    }
+
+    #[test]
+    fn value_options_consume_their_value() {
+        let request = parse(
+            "curl -U proxy:secret --oauth2-bearer abc -r 0-99 https://example.com",
+            "r",
+        )
+        .unwrap();
+        assert_eq!(request.url, "https://example.com");
+        assert_eq!(
+            request.auth,
+            AuthConfig::Bearer {
+                token: "abc".to_string()
+            }
+        );
+        assert_eq!(header(&request, "Range"), Some("bytes=0-99"));
+
+        // Options the request doesn't use still take their value along
+        let request = parse(
+            "curl --noproxy localhost --cert-type PEM --proxy-header 'X-Via: proxy' \
+             --limit-rate 1M -Y 100 -y5 -sC - https://example.com/path",
+            "r",
+        )
+        .unwrap();
+        assert_eq!(request.url, "https://example.com/path");
+        assert!(request.headers.is_empty());
+
+        assert_eq!(
+            parse("curl -K options.txt https://example.com", "r").unwrap_err(),
+            "Options from a config file are not supported"
+        );
+    }
+
+    #[test]
+    fn reads_data_and_uploads_from_files() {
+        let dir = std::env::temp_dir().join(format!("api-client-curl-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("body.txt");
+        std::fs::write(&file, "a=1\n&b=2\n").unwrap();
+        let file = file.display();
+
+        let request = parse(&format!("curl -d @{} https://example.com", file), "r").unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.body_mode, BodyMode::FormUrlEncoded);
+        assert_eq!(
+            request.form,
+            vec![SavedKeyValue::new("a", "1"), SavedKeyValue::new("b", "2")]
+        );
+
+        let command = format!(
+            "curl --data-binary @{} -H 'Content-Type: text/plain' https://example.com",
+            file
+        );
+        let request = parse(&command, "r").unwrap();
+        assert_eq!(request.body, "a=1\n&b=2\n");
+
+        let request = parse("curl --data-raw @name https://example.com", "r").unwrap();
+        assert_eq!(request.form, vec![SavedKeyValue::new("@name", "")]);
+
+        let request = parse(&format!("curl -T {} https://example.com", file), "r").unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.body, "a=1\n&b=2\n");
+
+        let missing = dir.join("missing.txt");
+        let error = parse(
+            &format!("curl -d @{} https://example.com", missing.display()),
+            "r",
+        )
+        .unwrap_err();
+        assert!(error.starts_with("Cannot read"), "{}", error);
+        assert!(parse("curl -d @- https://example.com", "r").is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,332 @@
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+/// Hash function named by a challenge's `algorithm` parameter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "MD5" => Some(Algorithm::Md5),
+            "MD5-SESS" => Some(Algorithm::Md5Sess),
+            "SHA-256" => Some(Algorithm::Sha256),
+            "SHA-256-SESS" => Some(Algorithm::Sha256Sess),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Md5Sess => "MD5-sess",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_session(&self) -> bool {
+        matches!(self, Algorithm::Md5Sess | Algorithm::Sha256Sess)
+    }
+
+    fn hash(&self, data: &str) -> String {
+        match self {
+            Algorithm::Md5 | Algorithm::Md5Sess => format!("{:x}", Md5::digest(data)),
+            Algorithm::Sha256 | Algorithm::Sha256Sess => format!("{:x}", Sha256::digest(data)),
+        }
+    }
+}
+
+/// A `WWW-Authenticate: Digest` challenge from a 401 response
+#[derive(Clone, Debug)]
+pub struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    /// Whether the server offered `qop=auth`; `auth-int` alone is not supported
+    qop_auth: bool,
+}
+
+impl Challenge {
+    /// Parse the parameters that follow `Digest ` in a challenge
+    fn parse(params: &str) -> Option<Self> {
+        let params = parse_params(params);
+        let get = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+        };
+
+        let algorithm = match get("algorithm") {
+            Some(name) => Algorithm::parse(&name)?,
+            None => Algorithm::Md5,
+        };
+        let qop = get("qop");
+        let qop_auth = qop
+            .as_deref()
+            .is_some_and(|qop| qop.split(',').any(|q| q.trim() == "auth"));
+        if qop.is_some() && !qop_auth {
+            return None;
+        }
+
+        Some(Self {
+            realm: get("realm").unwrap_or_default(),
+            nonce: get("nonce")?,
+            opaque: get("opaque"),
+            algorithm,
+            qop_auth,
+        })
+    }
+
+    /// `Authorization` header answering the challenge for `method` on `uri`
+    /// (the path and query of the request URL)
+    pub fn authorization(&self, username: &str, password: &str, method: &str, uri: &str) -> String {
+        self.authorization_with_cnonce(username, password, method, uri, &cnonce())
+    }
+
+    fn authorization_with_cnonce(
+        &self,
+        username: &str,
+        password: &str,
+        method: &str,
+        uri: &str,
+        cnonce: &str,
+    ) -> String {
+        let algorithm = self.algorithm;
+        let nc = "00000001";
+
+        let mut ha1 = algorithm.hash(&format!("{}:{}:{}", username, self.realm, password));
+        if algorithm.is_session() {
+            ha1 = algorithm.hash(&format!("{}:{}:{}", ha1, self.nonce, cnonce));
+        }
+        let ha2 = algorithm.hash(&format!("{}:{}", method, uri));
+        let response = if self.qop_auth {
+            algorithm.hash(&format!(
+                "{}:{}:{}:{}:auth:{}",
+                ha1, self.nonce, nc, cnonce, ha2
+            ))
+        } else {
+            // RFC 2069 compatibility when the server sends no qop
+            algorithm.hash(&format!("{}:{}:{}", ha1, self.nonce, ha2))
+        };
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+            quote(username),
+            quote(&self.realm),
+            quote(&self.nonce),
+            quote(uri),
+            algorithm.name(),
+            response
+        );
+        if let Some(opaque) = &self.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", quote(opaque)));
+        }
+        if self.qop_auth {
+            header.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce));
+        }
+        header
+    }
+}
+
+/// Pick the strongest usable Digest challenge from a response's headers
+pub fn find_challenge(headers: &[(String, String)]) -> Option<Challenge> {
+    headers
+        .iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case("www-authenticate"))
+        .filter_map(|(_, value)| {
+            let (scheme, params) = value.trim().split_once(' ')?;
+            if !scheme.eq_ignore_ascii_case("digest") {
+                return None;
+            }
+            Challenge::parse(params)
+        })
+        .max_by_key(|challenge| {
+            matches!(
+                challenge.algorithm,
+                Algorithm::Sha256 | Algorithm::Sha256Sess
+            )
+        })
+}
+
+/// Split `key=value, key="quoted, value"` into pairs
+fn parse_params(text: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace() || *c == ',') {
+            chars.next();
+        }
+        let mut key = String::new();
+        while let Some(&c) = chars.peek() {
+            if c == '=' || c == ',' {
+                break;
+            }
+            key.push(c);
+            chars.next();
+        }
+        if key.is_empty() {
+            break;
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => value.extend(chars.next()),
+                        c => value.push(c),
+                    }
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| *c != ',') {
+                    value.push(c);
+                }
+                value = value.trim().to_string();
+            }
+        }
+        params.push((key.trim().to_string(), value));
+    }
+    params
+}
+
+/// Escape a value for a quoted-string parameter
+fn quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Random client nonce
+fn cnonce() -> String {
+    rand::random::<[u8; 16]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Option<Challenge> {
+        find_challenge(&[("WWW-Authenticate".to_string(), value.to_string())])
+    }
+
+    /// The `response` parameter of an `Authorization` header
+    fn response(header: &str) -> String {
+        parse_params(header.strip_prefix("Digest ").unwrap())
+            .into_iter()
+            .find(|(key, _)| key == "response")
+            .map(|(_, value)| value)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_challenges() {
+        let challenge = parse(
+            r#"Digest realm="a \"quoted\" realm", nonce=abc, opaque="x,y", qop="auth, auth-int", algorithm=md5-sess"#,
+        )
+        .unwrap();
+        assert_eq!(challenge.realm, "a \"quoted\" realm");
+        assert_eq!(challenge.nonce, "abc");
+        assert_eq!(challenge.opaque.as_deref(), Some("x,y"));
+        assert_eq!(challenge.algorithm, Algorithm::Md5Sess);
+        assert!(challenge.qop_auth);
+
+        let legacy = parse(r#"Digest realm="r", nonce="n""#).unwrap();
+        assert_eq!(legacy.algorithm, Algorithm::Md5);
+        assert!(!legacy.qop_auth);
+
+        assert!(parse(r#"Digest realm="r""#).is_none());
+        assert!(parse(r#"Digest nonce="n", qop="auth-int""#).is_none());
+        assert!(parse(r#"Digest nonce="n", algorithm=SHA-512-256"#).is_none());
+        assert!(parse(r#"Basic realm="r""#).is_none());
+    }
+
+    #[test]
+    fn prefers_sha_256_challenges() {
+        let headers: Vec<(String, String)> = ["MD5", "SHA-256"]
+            .iter()
+            .map(|algorithm| {
+                (
+                    "WWW-Authenticate".to_string(),
+                    format!(r#"Digest realm="r", nonce="n", algorithm={}"#, algorithm),
+                )
+            })
+            .collect();
+        assert_eq!(
+            find_challenge(&headers).unwrap().algorithm,
+            Algorithm::Sha256
+        );
+    }
+
+    /// Example of RFC 7616 section 3.9.1
+    #[test]
+    fn answers_the_rfc_7616_example() {
+        for (algorithm, expected) in [
+            ("MD5", "8ca523f5e9506fed4657c9700eebdbec"),
+            (
+                "SHA-256",
+                "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1",
+            ),
+        ] {
+            let challenge = parse(&format!(
+                r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm={}, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+                algorithm
+            ))
+            .unwrap();
+            let header = challenge.authorization_with_cnonce(
+                "Mufasa",
+                "Circle of Life",
+                "GET",
+                "/dir/index.html",
+                "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ",
+            );
+            assert_eq!(response(&header), expected, "{}", algorithm);
+            assert!(header.contains(", qop=auth, nc=00000001, cnonce=\""));
+            assert!(header.contains(r#"opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#));
+        }
+    }
+
+    /// Example of RFC 2617 section 3.5, still answered by RFC 7616 clients
+    #[test]
+    fn answers_the_rfc_2617_example() {
+        let challenge = parse(
+            r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#,
+        )
+        .unwrap();
+        let header = challenge.authorization_with_cnonce(
+            "Mufasa",
+            "Circle Of Life",
+            "GET",
+            "/dir/index.html",
+            "0a4f113b",
+        );
+        assert_eq!(response(&header), "6629fae49393a05397450978507c4ef1");
+    }
+
+    #[test]
+    fn uses_a_fresh_cnonce() {
+        let challenge = parse(r#"Digest realm="r", nonce="n", qop=auth"#).unwrap();
+        let cnonce = |header: String| {
+            parse_params(header.strip_prefix("Digest ").unwrap())
+                .into_iter()
+                .find(|(key, _)| key == "cnonce")
+                .map(|(_, value)| value)
+                .unwrap()
+        };
+        let first = cnonce(challenge.authorization("u", "p", "GET", "/"));
+        let second = cnonce(challenge.authorization("u", "p", "GET", "/"));
+        assert_eq!(first.len(), 32);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+    }
+}
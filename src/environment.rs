@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::fs;
+
+/// A single `{{name}}` variable of an environment
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Variable {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Named set of variables, e.g. "local", "staging" or "prod"
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    #[serde(default)]
+    pub variables: Vec<Variable>,
+}
+
+/// All environments of a request folder plus the active selection
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EnvironmentStore {
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub environments: Vec<Environment>,
+}
+
+impl EnvironmentStore {
+    pub fn path(folder: &Path) -> PathBuf {
+        fs::data_dir(folder).join("environments.json")
+    }
+
+    pub fn load(folder: &Path) -> Self {
+        if let Ok(content) = std::fs::read_to_string(Self::path(folder)) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, folder: &Path) {
+        let path = Self::path(folder);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+
+    /// Create the environments file with an example environment if it does not exist yet
+    pub fn ensure_exists(folder: &Path) -> PathBuf {
+        let path = Self::path(folder);
+        if !path.exists() {
+            let store = Self {
+                active: None,
+                environments: vec![Environment {
+                    name: "local".to_string(),
+                    variables: vec![Variable {
+                        key: "baseUrl".to_string(),
+                        value: "http://localhost:8080".to_string(),
+                        enabled: true,
+                    }],
+                }],
+            };
+            store.save(folder);
+        }
+        path
+    }
+
+    /// Add variables to the named environment, creating it and making it active if
+    /// nothing is selected yet; existing keys are overwritten
+    pub fn upsert(&mut self, name: &str, variables: Vec<Variable>) {
+        let index = match self.environments.iter().position(|e| e.name == name) {
+            Some(index) => index,
+            None => {
+                self.environments.push(Environment {
+                    name: name.to_string(),
+                    variables: Vec::new(),
+                });
+                self.environments.len() - 1
+            }
+        };
+        let environment = &mut self.environments[index];
+        for variable in variables {
+            match environment
+                .variables
+                .iter_mut()
+                .find(|v| v.key == variable.key)
+            {
+                Some(existing) => *existing = variable,
+                None => environment.variables.push(variable),
+            }
+        }
+        if self.active.is_none() {
+            self.active = Some(name.to_string());
+        }
+    }
+
+    pub fn active_environment(&self) -> Option<&Environment> {
+        let active = self.active.as_ref()?;
+        self.environments.iter().find(|e| &e.name == active)
+    }
+
+    /// Enabled variables of the active environment
+    pub fn variables(&self) -> HashMap<String, String> {
+        self.active_environment()
+            .map(|env| {
+                env.variables
+                    .iter()
+                    .filter(|v| v.enabled && !v.key.is_empty())
+                    .map(|v| (v.key.clone(), v.value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Replace every `{{name}}` whose name is known; unknown placeholders are kept verbatim
+pub fn substitute(text: &str, vars: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        result.push_str(&rest[..start]);
+        match vars.get(name) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..start + 2 + len + 2]),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Names of `{{placeholders}}` in `text` that have no value in `vars`
+pub fn unresolved_variables(text: &str, vars: &HashMap<String, String>) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        if !name.is_empty() && !vars.contains_key(name) && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn substitutes_known_variables() {
+        let vars = vars(&[("host", "example.com"), ("id", "7")]);
+        assert_eq!(
+            substitute("https://{{host}}/users/{{ id }}?q={{id}}", &vars),
+            "https://example.com/users/7?q=7"
+        );
+    }
+
+    #[test]
+    fn keeps_missing_variables() {
+        let vars = vars(&[("host", "example.com")]);
+        let text = "{{host}}/{{missing}}/{{ other }}/{{missing}}";
+        assert_eq!(
+            substitute(text, &vars),
+            "example.com/{{missing}}/{{ other }}/{{missing}}"
+        );
+        assert_eq!(unresolved_variables(text, &vars), vec!["missing", "other"]);
+    }
+
+    #[test]
+    fn values_are_not_substituted_again() {
+        // A value holding a placeholder is inserted as written, so variables
+        // referring to each other can't recurse
+        let vars = vars(&[("a", "{{b}}"), ("b", "{{a}}")]);
+        assert_eq!(substitute("{{a}}-{{b}}", &vars), "{{b}}-{{a}}");
+    }
+
+    #[test]
+    fn leaves_other_braces_alone() {
+        let vars = vars(&[("x", "1")]);
+        assert_eq!(substitute("{\"x\": {x}}", &vars), "{\"x\": {x}}");
+        assert_eq!(
+            substitute("{{x}} {{unterminated", &vars),
+            "1 {{unterminated"
+        );
+        assert_eq!(substitute("{{}}", &vars), "{{}}");
+        assert!(unresolved_variables("{{}} {{x", &vars).is_empty());
+    }
+}
@@ -1,46 +1,257 @@
-use crate::types::{FileEntry, HttpMethod, SavedRequest};
-use std::path::PathBuf;
+use crate::types::{FileEntry, HttpMethod, RequestFormat, SavedRequest, TreeNode};
+use std::path::{Path, PathBuf};
+
+/// Hidden directory inside a request folder holding environments and other app data
+pub const DATA_DIR_NAME: &str = ".api-client";
+
+/// Path of the app data directory for a request folder
+pub fn data_dir(folder: &Path) -> PathBuf {
+    folder.join(DATA_DIR_NAME)
+}
+
+/// File name a request with this name is saved under
+pub fn request_file_name(name: &str, format: RequestFormat) -> String {
+    let safe_name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("{}.{}", safe_name, format.extension())
+}
+
+/// Path for a new request file in `dir`, numbering the name when it is taken
+pub fn unique_request_path(dir: &Path, name: &str, format: RequestFormat) -> PathBuf {
+    dir.join(request_file_name(
+        &unique_request_name(dir, name, format),
+        format,
+    ))
+}
+
+/// `name`, numbered when a request file of that name already exists in `dir`
+pub fn unique_request_name(dir: &Path, name: &str, format: RequestFormat) -> String {
+    let mut unique = name.to_string();
+    let mut n = 2;
+    while dir.join(request_file_name(&unique, format)).exists() {
+        unique = format!("{} {}", name, n);
+        n += 1;
+    }
+    unique
+}
+
+/// What an import wrote to disk
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub requests: usize,
+    pub folders: usize,
+    /// Environment the imported variables were added to
+    pub environment: Option<String>,
+    /// Items that could not be converted, with the reason
+    pub skipped: Vec<String>,
+}
+
+impl ImportSummary {
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "Imported {} request(s) in {} folder(s).",
+            self.requests, self.folders
+        );
+        if let Some(environment) = &self.environment {
+            text.push_str(&format!(
+                "\nVariables were added to the \"{}\" environment.",
+                environment
+            ));
+        }
+        if !self.skipped.is_empty() {
+            text.push_str("\n\nSkipped:\n");
+            text.push_str(&self.skipped.join("\n"));
+        }
+        text
+    }
+}
+
+/// Directory name for a folder of requests, without path separators
+pub fn safe_dir_name(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let safe = safe.trim().trim_start_matches('.').to_string();
+    if safe.is_empty() {
+        "Folder".to_string()
+    } else {
+        safe
+    }
+}
+
+/// Whether a file name has one of the saved request extensions
+pub fn is_request_file(path: &Path) -> bool {
+    RequestFormat::from_path(path).is_some()
+}
+
+/// Scan folder recursively into a tree; folders come first, hidden entries such as
+/// the app data directory and `.git` are skipped, and so are symlinks, which could
+/// point back up the tree
+pub fn scan_tree(folder: &Path) -> Vec<TreeNode> {
+    let mut folders = Vec::new();
+    let mut requests = Vec::new();
 
-/// Scan folder for request files
-pub fn scan_folder(folder: &PathBuf) -> Vec<FileEntry> {
-    let mut saved_requests = Vec::new();
     if let Ok(entries) = std::fs::read_dir(folder) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_file() {
-                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if ext == "json" || ext == "yaml" || ext == "yml" {
-                    // Try to parse the method from the file
-                    let method = parse_method_from_file(&path);
-                    let name = path
-                        .file_stem()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("Unknown")
-                        .to_string();
-
-                    saved_requests.push(FileEntry { name, path, method });
-                }
+            let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if name.starts_with('.') || file_type.is_symlink() {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                let children = scan_tree(&path);
+                folders.push(TreeNode::Folder {
+                    name,
+                    path,
+                    children,
+                });
+            } else if file_type.is_file() && is_request_file(&path) {
+                // Try to parse the method from the file
+                let method = parse_method_from_file(&path);
+                let name = path
+                    .file_stem()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("Unknown")
+                    .to_string();
+
+                requests.push(FileEntry { name, path, method });
             }
         }
     }
+
     // sort by name
-    saved_requests.sort_by(|a, b| a.name.cmp(&b.name));
-    saved_requests
+    folders.sort_by(|a, b| node_name(a).cmp(node_name(b)));
+    requests.sort_by(|a, b| a.name.cmp(&b.name));
+    folders.extend(requests.into_iter().map(TreeNode::Request));
+    folders
 }
 
-/// Parse HTTP method from a saved request file
-pub fn parse_method_from_file(path: &PathBuf) -> Option<HttpMethod> {
-    if let Ok(content) = std::fs::read_to_string(path) {
-        if let Ok(request) = serde_json::from_str::<SavedRequest>(&content) {
-            return match request.method.to_uppercase().as_str() {
-                "GET" => Some(HttpMethod::Get),
-                "POST" => Some(HttpMethod::Post),
-                "PUT" => Some(HttpMethod::Put),
-                "DELETE" => Some(HttpMethod::Delete),
-                "PATCH" => Some(HttpMethod::Patch),
-                _ => None,
-            };
+fn node_name(node: &TreeNode) -> &str {
+    match node {
+        TreeNode::Folder { name, .. } => name,
+        TreeNode::Request(entry) => &entry.name,
+    }
+}
+
+/// Every request of a tree in display order
+pub fn flatten_tree(nodes: &[TreeNode]) -> Vec<FileEntry> {
+    let mut entries = Vec::new();
+    for node in nodes {
+        match node {
+            TreeNode::Folder { children, .. } => entries.extend(flatten_tree(children)),
+            TreeNode::Request(entry) => entries.push(entry.clone()),
         }
     }
-    None
+    entries
+}
+
+/// Scan folder and its subfolders for request files
+pub fn scan_folder(folder: &Path) -> Vec<FileEntry> {
+    flatten_tree(&scan_tree(folder))
+}
+
+/// Path for a new folder in `dir`, numbering the name when it is taken
+pub fn unique_dir_path(dir: &Path, name: &str) -> PathBuf {
+    let mut path = dir.join(safe_dir_name(name));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(safe_dir_name(&format!("{} {}", name, n)));
+        n += 1;
+    }
+    path
+}
+
+/// Move a request file or folder into `dir`, refusing to overwrite anything
+pub fn move_into(path: &Path, dir: &Path) -> std::io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("path has no file name"))?;
+    let target = dir.join(name);
+    if target.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", target.display()),
+        ));
+    }
+    std::fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Read a saved JSON or YAML request file, migrating older formats
+pub fn load_request(path: &Path) -> Option<SavedRequest> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match RequestFormat::from_path(path)? {
+        RequestFormat::Json => SavedRequest::from_json(&content).ok(),
+        RequestFormat::Yaml => SavedRequest::from_yaml(&content).ok(),
+    }
+}
+
+/// Write a saved request file in the current format, as YAML when the path has a
+/// `.yaml`/`.yml` extension and as JSON otherwise
+pub fn save_request(path: &Path, request: &SavedRequest) -> std::io::Result<()> {
+    let content = match RequestFormat::from_path(path).unwrap_or_default() {
+        RequestFormat::Json => serde_json::to_string_pretty(request)?,
+        RequestFormat::Yaml => serde_yaml::to_string(request).map_err(std::io::Error::other)?,
+    };
+    std::fs::write(path, content)
+}
+
+/// Parse HTTP method from a saved request file
+pub fn parse_method_from_file(path: &Path) -> Option<HttpMethod> {
+    load_request(path).and_then(|request| HttpMethod::parse(&request.method))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_skips_symlinks() {
+        let folder = std::env::temp_dir().join(format!("api-client-scan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(folder.join("users")).unwrap();
+        let request = SavedRequest::new("list", &HttpMethod::Get, "https://example.com");
+        save_request(&folder.join("users").join("list.json"), &request).unwrap();
+        // A link back to the root would recurse forever if followed
+        std::os::unix::fs::symlink(&folder, folder.join("users").join("loop")).unwrap();
+        std::os::unix::fs::symlink(
+            folder.join("users").join("list.json"),
+            folder.join("linked.json"),
+        )
+        .unwrap();
+
+        let tree = scan_tree(&folder);
+        assert_eq!(tree.len(), 1);
+        let TreeNode::Folder { name, children, .. } = &tree[0] else {
+            panic!("expected a folder, got {:?}", tree[0]);
+        };
+        assert_eq!(name, "users");
+        assert!(
+            matches!(children.as_slice(), [TreeNode::Request(entry)] if entry.name == "list"),
+            "{:?}",
+            children
+        );
+
+        let _ = std::fs::remove_dir_all(folder);
+    }
 }
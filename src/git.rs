@@ -101,7 +101,7 @@ impl GitService {
         let head = self.repo.head()?.peel_to_commit()?;
         let path_str = path.to_str().context("Invalid path")?;
         self.repo
-            .reset_default(Some(&head.as_object()), [path_str])?;
+            .reset_default(Some(head.as_object()), [path_str])?;
         Ok(())
     }
 
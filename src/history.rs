@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::fs;
+use crate::timing::Timings;
+use crate::types::SavedRequest;
+
+/// Oldest entries are dropped once the history grows past this
+pub const MAX_HISTORY_ENTRIES: usize = 500;
+
+/// Response bodies are truncated to this many bytes before being recorded
+pub const MAX_HISTORY_BODY_BYTES: usize = 64 * 1024;
+
+/// One sent request and the response it got
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Unix timestamp in milliseconds, also used as the entry id
+    pub timestamp: u64,
+    /// Request file the entry was sent from, relative to the folder
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    /// Editor state at send time
+    pub request: SavedRequest,
+    /// URL after variable substitution and query params
+    pub url: String,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub body_truncated: bool,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Timings>,
+}
+
+impl HistoryEntry {
+    /// Start an entry for a request being sent now; the response is filled in later
+    pub fn new(file: Option<PathBuf>, request: SavedRequest, url: String) -> Self {
+        Self {
+            timestamp: now_millis(),
+            file,
+            request,
+            url,
+            status: None,
+            reason: String::new(),
+            error: None,
+            headers: Vec::new(),
+            body: String::new(),
+            body_truncated: false,
+            duration_ms: 0,
+            timings: None,
+        }
+    }
+
+    /// Record a body, truncating it on a character boundary
+    pub fn set_body(&mut self, body: &str) {
+        if body.len() > MAX_HISTORY_BODY_BYTES {
+            let mut end = MAX_HISTORY_BODY_BYTES;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.body = body[..end].to_string();
+            self.body_truncated = true;
+        } else {
+            self.body = body.to_string();
+            self.body_truncated = false;
+        }
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// History file of a folder, kept outside it because entries hold credentials,
+/// resolved URLs and response cookies that must not end up in git
+pub fn path(folder: &Path) -> PathBuf {
+    let folder = folder
+        .canonicalize()
+        .unwrap_or_else(|_| folder.to_path_buf());
+    let hash = Sha256::digest(folder.to_string_lossy().as_bytes());
+    let name: String = hash[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("api-client")
+        .join("history")
+        .join(format!("{}.jsonl", name))
+}
+
+/// Move a history file left inside the folder by earlier versions to `path`
+fn migrate(folder: &Path) {
+    let legacy = fs::data_dir(folder).join("history.jsonl");
+    let path = path(folder);
+    if legacy.exists() && !path.exists() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if std::fs::copy(&legacy, &path).is_ok() {
+            let _ = std::fs::remove_file(legacy);
+        }
+    }
+}
+
+/// Load the history of a folder, newest entry first
+pub fn load(folder: &Path) -> Vec<HistoryEntry> {
+    migrate(folder);
+    let Ok(content) = std::fs::read_to_string(path(folder)) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<HistoryEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    entries.reverse();
+
+    if entries.len() > MAX_HISTORY_ENTRIES {
+        entries.truncate(MAX_HISTORY_ENTRIES);
+        rewrite(folder, &entries);
+    }
+    entries
+}
+
+/// Append an entry to the folder's history file
+pub fn append(folder: &Path, entry: &HistoryEntry) {
+    let path = path(folder);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(line) = serde_json::to_string(entry) {
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+        {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Replace the history file with `entries` (newest first)
+fn rewrite(folder: &Path, entries: &[HistoryEntry]) {
+    let content: String = entries
+        .iter()
+        .rev()
+        .filter_map(|entry| serde_json::to_string(entry).ok())
+        .map(|line| line + "\n")
+        .collect();
+    let _ = std::fs::write(path(folder), content);
+}
+
+pub fn clear(folder: &Path) {
+    let _ = std::fs::remove_file(path(folder));
+}
+
+/// Human friendly age of a timestamp, e.g. "5m ago"
+pub fn relative_time(timestamp: u64) -> String {
+    let seconds = now_millis().saturating_sub(timestamp) / 1000;
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86_399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86_400),
+    }
+}
+
+/// A line of a line-based diff
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Inputs longer than this are compared line by line without alignment
+const MAX_DIFF_LINES: usize = 2000;
+
+/// Line diff of `old` against `new` based on the longest common subsequence
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    if old.len() > MAX_DIFF_LINES || new.len() > MAX_DIFF_LINES {
+        let mut lines = Vec::new();
+        for i in 0..old.len().max(new.len()) {
+            match (old.get(i), new.get(i)) {
+                (Some(a), Some(b)) if a == b => lines.push(DiffLine::Same(a.to_string())),
+                (a, b) => {
+                    if let Some(a) = a {
+                        lines.push(DiffLine::Removed(a.to_string()));
+                    }
+                    if let Some(b) = b {
+                        lines.push(DiffLine::Added(b.to_string()));
+                    }
+                }
+            }
+        }
+        return lines;
+    }
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    lines.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::HttpMethod;
+    use DiffLine::{Added, Removed, Same};
+
+    fn lines(diff: Vec<DiffLine>) -> Vec<String> {
+        diff.into_iter()
+            .map(|line| match line {
+                Same(text) => format!(" {}", text),
+                Removed(text) => format!("-{}", text),
+                Added(text) => format!("+{}", text),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diffs_by_longest_common_subsequence() {
+        assert_eq!(
+            lines(diff_lines("a\nb\nc\nd", "a\nc\nx\nd\ne")),
+            [" a", "-b", " c", "+x", " d", "+e"]
+        );
+        assert_eq!(
+            lines(diff_lines("same\ntext", "same\ntext")),
+            [" same", " text"]
+        );
+        assert_eq!(lines(diff_lines("", "new")), ["+new"]);
+        assert_eq!(lines(diff_lines("old", "")), ["-old"]);
+        assert!(diff_lines("", "").is_empty());
+        // A changed line is shown as removed, then added
+        assert_eq!(
+            lines(diff_lines("x\n1\ny", "x\n2\ny")),
+            [" x", "-1", "+2", " y"]
+        );
+    }
+
+    #[test]
+    fn compares_long_inputs_line_by_line() {
+        let old: String = (0..=MAX_DIFF_LINES).map(|i| format!("{}\n", i)).collect();
+        // Inserting a line shifts everything after it out of place without alignment
+        let new = format!("first\n{}", old);
+        let diff = diff_lines(&old, &new);
+        assert_eq!(diff.len(), 2 * (MAX_DIFF_LINES + 1) + 1);
+        assert_eq!(diff[0], Removed("0".to_string()));
+        assert_eq!(diff[1], Added("first".to_string()));
+
+        let diff = diff_lines(&old, &old);
+        assert!(diff.iter().all(|line| matches!(line, Same(_))));
+    }
+
+    #[test]
+    fn truncates_bodies_on_character_boundaries() {
+        let mut entry = HistoryEntry::new(
+            None,
+            SavedRequest::new("r", &HttpMethod::Get, "https://example.com"),
+            "https://example.com".to_string(),
+        );
+        let body = format!("{}é", "a".repeat(MAX_HISTORY_BODY_BYTES - 1));
+        entry.set_body(&body);
+        assert!(entry.body_truncated);
+        assert_eq!(entry.body.len(), MAX_HISTORY_BODY_BYTES - 1);
+
+        entry.set_body("short");
+        assert!(!entry.body_truncated);
+        assert_eq!(entry.body, "short");
+    }
+}
@@ -2,17 +2,40 @@ use gpui::*;
 use gpui_component::*;
 
 mod app;
+mod assertions;
+mod client;
+mod codegen;
 mod components;
 mod config;
+mod cookies;
+mod curl;
+mod digest;
+mod environment;
 mod fs;
 mod git;
+mod history;
+mod oauth2;
+mod openapi;
+mod postman;
 mod request;
+mod runner;
+mod settings;
+mod sigv4;
+mod timing;
 mod types;
+mod watcher;
 use app::{
-    App, CloseWindow, NewRequest, OpenFolder, SaveRequest, SendRequest, ToggleSidebar, ToggleTheme,
+    App, CancelRequest, CloseWindow, NewRequest, NewTab, OpenFolder, SaveRequest, SendRequest,
+    ToggleSidebar, ToggleTheme,
 };
 
 fn main() {
+    // Headless mode: `api-client run <folder>` executes requests without opening a window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("run") {
+        std::process::exit(runner::main(&args[1..]));
+    }
+
     let app = Application::new().with_assets(gpui_component_assets::Assets);
 
     // Initialize Tokio runtime for reqwest
@@ -28,12 +51,17 @@ fn main() {
             // Send request: Cmd/Ctrl + Enter
             KeyBinding::new("cmd-enter", SendRequest, Some("ApiClient")),
             KeyBinding::new("ctrl-enter", SendRequest, Some("ApiClient")),
+            // Cancel request: Escape
+            KeyBinding::new("escape", CancelRequest, Some("ApiClient")),
             // Save request: Cmd/Ctrl + S
             KeyBinding::new("cmd-s", SaveRequest, Some("ApiClient")),
             KeyBinding::new("ctrl-s", SaveRequest, Some("ApiClient")),
             // New request: Cmd/Ctrl + N
             KeyBinding::new("cmd-n", NewRequest, Some("ApiClient")),
             KeyBinding::new("ctrl-n", NewRequest, Some("ApiClient")),
+            // New tab: Cmd/Ctrl + T
+            KeyBinding::new("cmd-t", NewTab, Some("ApiClient")),
+            KeyBinding::new("ctrl-t", NewTab, Some("ApiClient")),
             // Open folder: Cmd/Ctrl + O
             KeyBinding::new("cmd-o", OpenFolder, Some("ApiClient")),
             KeyBinding::new("ctrl-o", OpenFolder, Some("ApiClient")),
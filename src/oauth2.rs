@@ -0,0 +1,631 @@
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::client::ClientSettings;
+use crate::history;
+use crate::request::{self, RequestContext};
+use crate::types::{OAuth2Config, OAuthGrant, RequestSettings};
+
+/// Tokens this close to expiring are refreshed before use
+const EXPIRY_MARGIN_MS: u64 = 30_000;
+
+/// How long to wait for the browser to come back to the loopback listener
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// An access token as returned by the token endpoint
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OAuthToken {
+    pub access_token: String,
+    #[serde(default)]
+    pub token_type: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Unix timestamp in milliseconds, `None` if the server gave no lifetime
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+impl OAuthToken {
+    fn is_fresh(&self) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| history::now_millis() + EXPIRY_MARGIN_MS < expires_at)
+    }
+
+    /// Value of the `Authorization` header carrying the token
+    pub fn authorization(&self) -> String {
+        let token_type =
+            if self.token_type.is_empty() || self.token_type.eq_ignore_ascii_case("bearer") {
+                "Bearer"
+            } else {
+                self.token_type.as_str()
+            };
+        format!("{} {}", token_type, self.access_token)
+    }
+}
+
+/// Held while the token store is read, changed and written back, so requests
+/// fetching tokens at the same time don't drop each other's
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Tokens cached on this machine, kept outside the request folder so they never end up in git
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TokenStore {
+    #[serde(default)]
+    tokens: HashMap<String, OAuthToken>,
+}
+
+impl TokenStore {
+    fn path(context: &RequestContext) -> PathBuf {
+        context.store_path("oauth-tokens.json")
+    }
+
+    fn load(context: &RequestContext) -> Self {
+        if let Ok(content) = std::fs::read_to_string(Self::path(context)) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    fn save(&self, context: &RequestContext) {
+        let path = Self::path(context);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        // Written aside and moved into place so a reader never sees half a file
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            let temp = path.with_extension("json.tmp");
+            if std::fs::write(&temp, content).is_ok() {
+                let _ = std::fs::rename(temp, path);
+            }
+        }
+    }
+
+    /// Change the stored tokens, re-reading them first so tokens other requests saved
+    /// since are kept
+    fn update(context: &RequestContext, change: impl FnOnce(&mut Self)) {
+        let _lock = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut store = Self::load(context);
+        change(&mut store);
+        store.save(context);
+    }
+}
+
+/// Cache key of a token: the folder and environment it was fetched for, plus everything
+/// in the settings that changes which token the server hands out
+fn cache_key(config: &OAuth2Config, context: &RequestContext) -> String {
+    [
+        context.key().as_str(),
+        grant_type(config.grant),
+        &config.token_url,
+        &config.client_id,
+        &config.scope,
+        &config.username,
+    ]
+    .join("\n")
+}
+
+fn grant_type(grant: OAuthGrant) -> &'static str {
+    match grant {
+        OAuthGrant::ClientCredentials => "client_credentials",
+        OAuthGrant::Password => "password",
+        OAuthGrant::AuthorizationCode => "authorization_code",
+    }
+}
+
+/// Remove the cached token for these settings so the next send fetches a new one
+pub fn forget_token(config: &OAuth2Config, context: &RequestContext) {
+    TokenStore::update(context, |store| {
+        store.tokens.remove(&cache_key(config, context));
+    });
+}
+
+/// A usable access token: the cached one while it is fresh, otherwise refreshed with
+/// the refresh token, otherwise newly requested with the configured grant. Token
+/// requests are limited by the timeouts of `settings`.
+pub async fn access_token(
+    config: &OAuth2Config,
+    context: &RequestContext,
+    settings: &RequestSettings,
+) -> Result<OAuthToken, String> {
+    let key = cache_key(config, context);
+    let cached = TokenStore::load(context).tokens.remove(&key);
+
+    if let Some(token) = &cached {
+        if token.is_fresh() {
+            return Ok(token.clone());
+        }
+    }
+
+    // The token endpoint is reached through the same proxy and certificate settings
+    // as the requests it authorizes
+    let client = context.clients.client(&ClientSettings {
+        connect_timeout: settings.connect_timeout(),
+        proxy: context.proxy.clone(),
+        tls: context.tls_for(&config.token_url),
+        ..Default::default()
+    })?;
+    let timeout = settings.timeout();
+    let refreshed = match cached.and_then(|token| token.refresh_token) {
+        Some(refresh_token) => refresh(&client, timeout, config, &refresh_token).await.ok(),
+        None => None,
+    };
+    let token = match refreshed {
+        Some(token) => token,
+        None => match config.grant {
+            OAuthGrant::ClientCredentials => {
+                let mut form = vec![("grant_type", "client_credentials".to_string())];
+                if !config.scope.is_empty() {
+                    form.push(("scope", config.scope.clone()));
+                }
+                token_request(&client, timeout, config, form).await?
+            }
+            OAuthGrant::Password => {
+                let mut form = vec![
+                    ("grant_type", "password".to_string()),
+                    ("username", config.username.clone()),
+                    ("password", config.password.clone()),
+                ];
+                if !config.scope.is_empty() {
+                    form.push(("scope", config.scope.clone()));
+                }
+                token_request(&client, timeout, config, form).await?
+            }
+            OAuthGrant::AuthorizationCode => authorize(&client, timeout, config).await?,
+        },
+    };
+
+    TokenStore::update(context, |store| {
+        store.tokens.insert(key, token.clone());
+    });
+    Ok(token)
+}
+
+async fn refresh(
+    client: &reqwest::Client,
+    timeout: Option<Duration>,
+    config: &OAuth2Config,
+    refresh_token: &str,
+) -> Result<OAuthToken, String> {
+    let form = vec![
+        ("grant_type", "refresh_token".to_string()),
+        ("refresh_token", refresh_token.to_string()),
+    ];
+    let mut token = token_request(client, timeout, config, form).await?;
+    // Servers that don't rotate refresh tokens leave them out of the response
+    if token.refresh_token.is_none() {
+        token.refresh_token = Some(refresh_token.to_string());
+    }
+    Ok(token)
+}
+
+/// POST a grant to the token endpoint and parse the token it returns, giving up
+/// after `timeout`
+async fn token_request(
+    client: &reqwest::Client,
+    timeout: Option<Duration>,
+    config: &OAuth2Config,
+    mut form: Vec<(&str, String)>,
+) -> Result<OAuthToken, String> {
+    if config.token_url.is_empty() {
+        return Err("OAuth 2.0 needs a token URL".to_string());
+    }
+
+    let mut builder = client
+        .post(&config.token_url)
+        .header("Accept", "application/json");
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    // Confidential clients authenticate with HTTP Basic, public clients only name themselves
+    if config.client_secret.is_empty() {
+        form.push(("client_id", config.client_id.clone()));
+    } else {
+        let credentials = format!(
+            "{}:{}",
+            request::urlencoding(&config.client_id),
+            request::urlencoding(&config.client_secret)
+        );
+        let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+        builder = builder.header("Authorization", format!("Basic {}", encoded));
+    }
+
+    let response = builder
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+
+    // Some providers answer in url-encoded form despite the Accept header
+    let fields: HashMap<String, String> = match serde_json::from_str::<Value>(&body) {
+        Ok(Value::Object(map)) => map
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Value::String(text) => text,
+                    other => other.to_string(),
+                };
+                (key, value)
+            })
+            .collect(),
+        _ => request::parse_query(&body)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|kv| (kv.key, kv.value))
+            .collect(),
+    };
+
+    if let Some(error) = fields.get("error") {
+        return Err(match fields.get("error_description") {
+            Some(description) => format!("Token request failed: {} ({})", error, description),
+            None => format!("Token request failed: {}", error),
+        });
+    }
+    if !status.is_success() {
+        return Err(format!("Token request failed with status {}", status));
+    }
+    let access_token = fields
+        .get("access_token")
+        .filter(|token| !token.is_empty())
+        .ok_or("Token response has no access_token")?;
+
+    Ok(OAuthToken {
+        access_token: access_token.clone(),
+        token_type: fields.get("token_type").cloned().unwrap_or_default(),
+        refresh_token: fields.get("refresh_token").cloned(),
+        expires_at: fields.get("expires_in").map(|seconds| expires_at(seconds)),
+    })
+}
+
+/// Expiry of a token with the lifetime `expires_in` seconds; a lifetime that can't be
+/// read makes the token expire right away so it is used once and then fetched again
+fn expires_at(expires_in: &str) -> u64 {
+    let now = history::now_millis();
+    match expires_in.trim().parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds > 0.0 => now + (seconds * 1000.0) as u64,
+        _ => now,
+    }
+}
+
+/// Authorization code grant with PKCE: send the user to the authorization endpoint in
+/// the browser and catch the redirect on a loopback listener
+async fn authorize(
+    client: &reqwest::Client,
+    timeout: Option<Duration>,
+    config: &OAuth2Config,
+) -> Result<OAuthToken, String> {
+    if config.auth_url.is_empty() {
+        return Err("The authorization code grant needs an authorization URL".to_string());
+    }
+
+    let (listener, redirect_uri) = listen(&config.redirect_uri).await?;
+    let verifier = random_string(64);
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(verifier.as_bytes()));
+    let state = random_string(24);
+
+    let mut params = vec![
+        ("response_type".to_string(), "code".to_string()),
+        ("client_id".to_string(), config.client_id.clone()),
+        ("redirect_uri".to_string(), redirect_uri.clone()),
+        ("state".to_string(), state.clone()),
+        ("code_challenge".to_string(), challenge),
+        ("code_challenge_method".to_string(), "S256".to_string()),
+    ];
+    if !config.scope.is_empty() {
+        params.push(("scope".to_string(), config.scope.clone()));
+    }
+    open_browser(&request::build_url(&config.auth_url, &params))?;
+
+    let code = tokio::time::timeout(AUTHORIZATION_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| "Timed out waiting for the authorization redirect".to_string())??;
+
+    let form = vec![
+        ("grant_type", "authorization_code".to_string()),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+        ("code_verifier", verifier),
+    ];
+    token_request(client, timeout, config, form).await
+}
+
+/// Bind the loopback listener for `redirect_uri`, returning it with the URI to register
+async fn listen(redirect_uri: &str) -> Result<(TcpListener, String), String> {
+    if redirect_uri.is_empty() {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .map_err(|e| format!("Could not start the redirect listener: {}", e))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        return Ok((listener, format!("http://127.0.0.1:{}/callback", port)));
+    }
+
+    let url = reqwest::Url::parse(redirect_uri).map_err(|e| format!("Bad redirect URI: {}", e))?;
+    let loopback = matches!(url.host_str(), Some("127.0.0.1" | "localhost" | "[::1]"));
+    if url.scheme() != "http" || !loopback {
+        return Err("The redirect URI must be an http:// loopback address".to_string());
+    }
+    let port = url.port_or_known_default().unwrap_or(80);
+    let address = match url.host_str() {
+        Some("[::1]") => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        _ => IpAddr::V4(Ipv4Addr::LOCALHOST),
+    };
+    let listener = TcpListener::bind((address, port))
+        .await
+        .map_err(|e| format!("Could not listen on port {}: {}", port, e))?;
+    Ok((listener, redirect_uri.to_string()))
+}
+
+/// Accept browser connections until one carries the authorization response
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        let mut buffer = vec![0u8; 8192];
+        let len = stream.read(&mut buffer).await.unwrap_or(0);
+        let head = String::from_utf8_lossy(&buffer[..len]);
+
+        // Request line: `GET /callback?code=...&state=... HTTP/1.1`
+        let query = head
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|target| target.split_once('?'))
+            .map(|(_, query)| query.to_string());
+        let Some(query) = query else {
+            // Favicon and other requests the browser makes on its own
+            let _ = stream
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            continue;
+        };
+
+        let params = request::parse_query(&query).unwrap_or_default();
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|kv| kv.key == name)
+                .map(|kv| kv.value.clone())
+        };
+        let result = if let Some(error) = param("error") {
+            Err(format!(
+                "Authorization failed: {}",
+                param("error_description").unwrap_or(error)
+            ))
+        } else if param("state").as_deref() != Some(state) {
+            Err("Authorization response has a mismatched state".to_string())
+        } else {
+            param("code").ok_or_else(|| "Authorization response has no code".to_string())
+        };
+
+        let message = match &result {
+            Ok(_) => "Authorization complete. You can close this window.",
+            Err(_) => "Authorization failed. You can close this window.",
+        };
+        let page = format!(
+            "<!DOCTYPE html><html><body><p>{}</p></body></html>",
+            message
+        );
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            page.len(),
+            page
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return result;
+    }
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+fn open_browser(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Could not open the browser: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Token endpoint handing out `token-N`/`refresh-N`, recording each request body
+    async fn token_server() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut data = Vec::new();
+                let mut buffer = [0u8; 4096];
+                let body = loop {
+                    let len = stream.read(&mut buffer).await.unwrap();
+                    data.extend_from_slice(&buffer[..len]);
+                    let text = String::from_utf8_lossy(&data).into_owned();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_ascii_lowercase()
+                                    .strip_prefix("content-length: ")
+                                    .map(str::to_string)
+                            })
+                            .and_then(|value| value.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length || len == 0 {
+                            break body.to_string();
+                        }
+                    }
+                };
+                let count = {
+                    let mut requests = seen.lock().unwrap();
+                    requests.push(body);
+                    requests.len()
+                };
+                let token = format!(
+                    r#"{{"access_token":"token-{0}","token_type":"bearer","refresh_token":"refresh-{0}","expires_in":3600}}"#,
+                    count
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    token.len(),
+                    token
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn fetches_caches_and_refreshes_tokens() {
+        let (token_url, requests) = token_server().await;
+        let config = OAuth2Config {
+            token_url,
+            client_id: "app".to_string(),
+            client_secret: "secret".to_string(),
+            scope: "read".to_string(),
+            ..Default::default()
+        };
+        let dir = std::env::temp_dir().join(format!("api-client-oauth-{}", std::process::id()));
+        let context = RequestContext {
+            folder: Some(dir.clone()),
+            store_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+        forget_token(&config, &context);
+
+        let token = access_token(&config, &context, &RequestSettings::default())
+            .await
+            .unwrap();
+        assert_eq!(token.authorization(), "Bearer token-1");
+        assert_eq!(
+            requests.lock().unwrap().as_slice(),
+            ["grant_type=client_credentials&scope=read"]
+        );
+
+        // A fresh token comes from the cache
+        let token = access_token(&config, &context, &RequestSettings::default())
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "token-1");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        // An expired one is refreshed with its refresh token
+        TokenStore::update(&context, |store| {
+            let token = store.tokens.get_mut(&cache_key(&config, &context)).unwrap();
+            token.expires_at = Some(history::now_millis());
+        });
+        let token = access_token(&config, &context, &RequestSettings::default())
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "token-2");
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh-2"));
+        assert_eq!(
+            requests.lock().unwrap().last().map(String::as_str),
+            Some("grant_type=refresh_token&refresh_token=refresh-1")
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn token_requests_time_out() {
+        // Accepts connections and never answers
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let config = OAuth2Config {
+            token_url: format!("http://{}/token", listener.local_addr().unwrap()),
+            client_id: "app".to_string(),
+            ..Default::default()
+        };
+        let dir =
+            std::env::temp_dir().join(format!("api-client-oauth-slow-{}", std::process::id()));
+        let context = RequestContext {
+            store_dir: Some(dir),
+            ..Default::default()
+        };
+        let settings = RequestSettings {
+            timeout_ms: Some(200),
+            ..Default::default()
+        };
+        let error = access_token(&config, &context, &settings)
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("Token request failed"), "{}", error);
+        drop(listener);
+    }
+
+    #[test]
+    fn unreadable_lifetimes_expire_at_once() {
+        let now = history::now_millis();
+        assert!(expires_at("3600") >= now + 3_600_000);
+        assert!(expires_at("59.5") >= now + 59_500);
+        for lifetime in ["soon", "", "-5", "NaN"] {
+            let token = OAuthToken {
+                access_token: "token".to_string(),
+                token_type: String::new(),
+                refresh_token: None,
+                expires_at: Some(expires_at(lifetime)),
+            };
+            assert!(!token.is_fresh(), "{}", lifetime);
+        }
+    }
+
+    #[tokio::test]
+    async fn listens_on_the_redirect_address() {
+        let (listener, uri) = listen("").await.unwrap();
+        assert!(listener.local_addr().unwrap().ip().is_loopback());
+        assert!(uri.starts_with("http://127.0.0.1:"));
+
+        // Machines without IPv6 can't listen on [::1]
+        let Ok(ipv6) = TcpListener::bind(("::1", 0)).await else {
+            return;
+        };
+        let port = ipv6.local_addr().unwrap().port();
+        drop(ipv6);
+        let redirect_uri = format!("http://[::1]:{}/callback", port);
+        let (listener, uri) = listen(&redirect_uri).await.unwrap();
+        assert_eq!(uri, redirect_uri);
+        assert!(listener.local_addr().unwrap().is_ipv6());
+
+        assert!(listen("http://example.com/callback").await.is_err());
+    }
+}
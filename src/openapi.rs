@@ -0,0 +1,869 @@
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+use crate::environment::{EnvironmentStore, Variable};
+use crate::fs::{self, ImportSummary};
+use crate::settings::FolderSettings;
+use crate::types::{BodyMode, HttpMethod, SavedKeyValue, SavedRequest};
+
+/// Operation keys of a path item, in the order they are imported
+const OPERATIONS: [&str; 8] = [
+    "get", "post", "put", "patch", "delete", "head", "options", "trace",
+];
+
+/// Nested schemas are only expanded this deep when generating example bodies
+const MAX_EXAMPLE_DEPTH: usize = 8;
+
+/// Environment variable holding the server URL
+const BASE_URL_VARIABLE: &str = "baseUrl";
+
+/// Generate one request per operation of an OpenAPI 3 or Swagger 2 document,
+/// grouped by tag into subfolders of `folder`
+pub fn import(path: &Path, folder: &Path) -> Result<ImportSummary, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    // YAML is a superset of JSON, but JSON errors are easier to read
+    let spec: Value = match serde_json::from_str(&content) {
+        Ok(spec) => spec,
+        Err(_) => serde_yaml::from_str(&content).map_err(|e| format!("Invalid YAML: {}", e))?,
+    };
+
+    let swagger2 = spec
+        .get("swagger")
+        .and_then(Value::as_str)
+        .is_some_and(|v| v.starts_with("2."));
+    let openapi3 = spec
+        .get("openapi")
+        .and_then(Value::as_str)
+        .is_some_and(|v| v.starts_with("3."));
+    if !swagger2 && !openapi3 {
+        return Err("Not an OpenAPI 3.x or Swagger 2.0 document".to_string());
+    }
+
+    let title = spec
+        .pointer("/info/title")
+        .and_then(Value::as_str)
+        .unwrap_or("OpenAPI")
+        .to_string();
+    let spec = Spec {
+        root: &spec,
+        swagger2,
+    };
+
+    let mut summary = ImportSummary::default();
+    let mut folders = Vec::new();
+    let format = FolderSettings::load(folder).request_format;
+
+    let paths = spec
+        .root
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or("The document has no paths")?;
+    for (path, item) in paths {
+        let item = spec.resolve(item);
+        for method in OPERATIONS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            let operation = spec.resolve(operation);
+            let name = operation_name(operation, method, path);
+
+            let Some(http_method) = HttpMethod::parse(method) else {
+                summary.skipped.push(format!(
+                    "{}: unsupported method {}",
+                    name,
+                    method.to_uppercase()
+                ));
+                continue;
+            };
+
+            let dir = match operation
+                .get("tags")
+                .and_then(Value::as_array)
+                .and_then(|tags| tags.first())
+                .and_then(Value::as_str)
+            {
+                Some(tag) => {
+                    let dir = folder.join(fs::safe_dir_name(tag));
+                    if !folders.contains(&dir) {
+                        folders.push(dir.clone());
+                    }
+                    dir
+                }
+                None => folder.to_path_buf(),
+            };
+
+            let request = spec.convert_operation(&name, &http_method, path, item, operation);
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+            let file = fs::unique_request_path(&dir, &name, format);
+            fs::save_request(&file, &request)
+                .map_err(|e| format!("Failed to write {:?}: {}", file, e))?;
+            summary.requests += 1;
+        }
+    }
+    summary.folders = folders.len();
+
+    let mut variables = vec![Variable {
+        key: BASE_URL_VARIABLE.to_string(),
+        value: spec.base_url(),
+        enabled: true,
+    }];
+    let mut environments = EnvironmentStore::load(folder);
+    // Keep credentials filled in after an earlier import of the same document
+    let existing: Vec<String> = environments
+        .environments
+        .iter()
+        .filter(|e| e.name == title)
+        .flat_map(|e| e.variables.iter().map(|v| v.key.clone()))
+        .collect();
+    variables.extend(
+        spec.security_variables()
+            .into_iter()
+            .filter(|v| !existing.contains(&v.key)),
+    );
+    environments.upsert(&title, variables);
+    environments.save(folder);
+    summary.environment = Some(title);
+
+    Ok(summary)
+}
+
+fn operation_name(operation: &Value, method: &str, path: &str) -> String {
+    ["summary", "operationId"]
+        .iter()
+        .filter_map(|key| operation.get(*key).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path))
+}
+
+struct Spec<'a> {
+    root: &'a Value,
+    swagger2: bool,
+}
+
+impl<'a> Spec<'a> {
+    /// Follow local `$ref`s such as `#/components/schemas/User`
+    fn resolve(&self, value: &'a Value) -> &'a Value {
+        let mut current = value;
+        // Bounded so reference cycles can't hang the import
+        for _ in 0..16 {
+            match current.get("$ref").and_then(Value::as_str) {
+                Some(reference) => match reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| self.root.pointer(pointer))
+                {
+                    Some(target) => current = target,
+                    None => return current,
+                },
+                None => return current,
+            }
+        }
+        current
+    }
+
+    /// Server URL with server variables replaced by their defaults
+    fn base_url(&self) -> String {
+        if self.swagger2 {
+            let host = self.root.get("host").and_then(Value::as_str).unwrap_or("");
+            let base_path = self
+                .root
+                .get("basePath")
+                .and_then(Value::as_str)
+                .unwrap_or("");
+            if host.is_empty() {
+                return base_path.trim_end_matches('/').to_string();
+            }
+            let scheme = self
+                .root
+                .get("schemes")
+                .and_then(Value::as_array)
+                .and_then(|schemes| {
+                    schemes
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .find(|s| *s == "https")
+                        .or_else(|| schemes.first().and_then(Value::as_str))
+                })
+                .unwrap_or("https");
+            return format!("{}://{}{}", scheme, host, base_path.trim_end_matches('/'));
+        }
+
+        let Some(server) = self
+            .root
+            .get("servers")
+            .and_then(Value::as_array)
+            .and_then(|servers| servers.first())
+        else {
+            return String::new();
+        };
+        let mut url = server
+            .get("url")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+        if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+            for (name, variable) in variables {
+                let default = variable.get("default").map(scalar).unwrap_or_default();
+                url = url.replace(&format!("{{{}}}", name), &default);
+            }
+        }
+        url.trim_end_matches('/').to_string()
+    }
+
+    /// Placeholders for the credentials used by header and bearer security schemes
+    fn security_variables(&self) -> Vec<Variable> {
+        self.security_schemes()
+            .iter()
+            .filter_map(|(_, scheme)| security_header(scheme))
+            .map(|credential| Variable {
+                key: credential.variable,
+                value: String::new(),
+                enabled: true,
+            })
+            .collect()
+    }
+
+    fn security_schemes(&self) -> Vec<(String, &'a Value)> {
+        let schemes = if self.swagger2 {
+            self.root.get("securityDefinitions")
+        } else {
+            self.root.pointer("/components/securitySchemes")
+        };
+        schemes
+            .and_then(Value::as_object)
+            .map(|schemes| {
+                schemes
+                    .iter()
+                    .map(|(name, scheme)| (name.clone(), self.resolve(scheme)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn convert_operation(
+        &self,
+        name: &str,
+        method: &HttpMethod,
+        path: &str,
+        item: &Value,
+        operation: &Value,
+    ) -> SavedRequest {
+        let mut parameters: Vec<&Value> = Vec::new();
+        // Operation parameters override path-level ones with the same name and location
+        for list in [operation.get("parameters"), item.get("parameters")] {
+            for parameter in list.and_then(Value::as_array).into_iter().flatten() {
+                let parameter = self.resolve(parameter);
+                let key = (parameter.get("name"), parameter.get("in"));
+                if !parameters
+                    .iter()
+                    .any(|p| (p.get("name"), p.get("in")) == key)
+                {
+                    parameters.push(parameter);
+                }
+            }
+        }
+
+        let mut path_values: Vec<(String, String)> = Vec::new();
+        let mut request = SavedRequest::new(name, method, String::new());
+        request.description = operation
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .trim()
+            .to_string();
+
+        let mut form_fields = Vec::new();
+        let mut has_file = false;
+
+        for parameter in parameters {
+            let Some(param_name) = parameter.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            let required = parameter.get("required").and_then(Value::as_bool) == Some(true);
+            let example = self.parameter_example(parameter);
+
+            match parameter.get("in").and_then(Value::as_str).unwrap_or("") {
+                "path" => {
+                    if let Some(value) = example {
+                        path_values.push((param_name.to_string(), value));
+                    }
+                }
+                "query" => request.params.push(SavedKeyValue {
+                    key: param_name.to_string(),
+                    value: example.unwrap_or_default(),
+                    enabled: required,
+                }),
+                "header" => request.headers.push(SavedKeyValue {
+                    key: param_name.to_string(),
+                    value: example.unwrap_or_default(),
+                    enabled: required,
+                }),
+                "body" => {
+                    if let Some(schema) = parameter.get("schema") {
+                        request.body_mode = BodyMode::Json;
+                        request.body = pretty(&self.example(schema, 0));
+                    }
+                }
+                "formData" => {
+                    if parameter.get("type").and_then(Value::as_str) == Some("file") {
+                        has_file = true;
+                    }
+                    form_fields.push(SavedKeyValue {
+                        key: param_name.to_string(),
+                        value: example.unwrap_or_default(),
+                        enabled: true,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if !form_fields.is_empty() {
+            let consumes = self.consumes(operation);
+            request.body_mode = if has_file || consumes.iter().any(|c| c.contains("multipart")) {
+                BodyMode::Multipart
+            } else {
+                BodyMode::FormUrlEncoded
+            };
+            request.form = form_fields;
+        }
+
+        if let Some(body) = operation.get("requestBody") {
+            self.convert_request_body(self.resolve(body), &mut request);
+        }
+
+        self.apply_security(operation, &mut request);
+
+        request.url = format!(
+            "{{{{{}}}}}{}",
+            BASE_URL_VARIABLE,
+            fill_path(path, &path_values)
+        );
+        request
+    }
+
+    /// Swagger 2 media types accepted by an operation
+    fn consumes(&self, operation: &Value) -> Vec<String> {
+        operation
+            .get("consumes")
+            .or_else(|| self.root.get("consumes"))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn convert_request_body(&self, body: &Value, request: &mut SavedRequest) {
+        let Some(content) = body.get("content").and_then(Value::as_object) else {
+            return;
+        };
+
+        // Prefer JSON, then forms, then whatever comes first
+        let pick = content
+            .iter()
+            .find(|(media, _)| media.contains("json"))
+            .or_else(|| content.iter().find(|(media, _)| media.contains("form")))
+            .or_else(|| content.iter().next());
+        let Some((media_type, media)) = pick else {
+            return;
+        };
+        let schema = media.get("schema").map(|s| self.resolve(s));
+
+        let example = media
+            .get("example")
+            .cloned()
+            .or_else(|| {
+                media
+                    .get("examples")
+                    .and_then(Value::as_object)
+                    .and_then(|examples| examples.values().next())
+                    .map(|example| self.resolve(example))
+                    .and_then(|example| example.get("value").cloned())
+            })
+            .or_else(|| schema.map(|schema| self.example(schema, 0)));
+
+        if media_type.contains("x-www-form-urlencoded") || media_type.contains("multipart") {
+            request.body_mode = if media_type.contains("multipart") {
+                BodyMode::Multipart
+            } else {
+                BodyMode::FormUrlEncoded
+            };
+            let properties = schema
+                .and_then(|schema| schema.get("properties"))
+                .and_then(Value::as_object);
+            request.form = properties
+                .into_iter()
+                .flatten()
+                .map(|(key, property)| {
+                    let property = self.resolve(property);
+                    let binary = property.get("format").and_then(Value::as_str) == Some("binary");
+                    let value = if binary {
+                        "@".to_string()
+                    } else {
+                        example
+                            .as_ref()
+                            .and_then(|e| e.get(key))
+                            .map(scalar)
+                            .unwrap_or_else(|| scalar(&self.example(property, 1)))
+                    };
+                    SavedKeyValue::new(key.clone(), value)
+                })
+                .collect();
+            return;
+        }
+
+        let example = example.unwrap_or(Value::Null);
+        if media_type.contains("json") {
+            request.body_mode = BodyMode::Json;
+            request.body = pretty(&example);
+        } else {
+            request.body_mode = if media_type.contains("xml") {
+                BodyMode::Xml
+            } else {
+                BodyMode::Text
+            };
+            request.body = scalar(&example);
+            if !request
+                .headers
+                .iter()
+                .any(|h| h.key.eq_ignore_ascii_case("content-type"))
+                && request.body_mode.content_type() != Some(media_type.as_str())
+            {
+                request
+                    .headers
+                    .push(SavedKeyValue::new("Content-Type", media_type.clone()));
+            }
+        }
+    }
+
+    /// Add headers for the API key and bearer schemes the operation requires
+    fn apply_security(&self, operation: &Value, request: &mut SavedRequest) {
+        let requirements = operation
+            .get("security")
+            .or_else(|| self.root.get("security"))
+            .and_then(Value::as_array);
+        let schemes = self.security_schemes();
+
+        // Only the first alternative is applied; any one of them is sufficient
+        let Some(requirement) = requirements
+            .and_then(|r| r.first())
+            .and_then(Value::as_object)
+        else {
+            return;
+        };
+        for name in requirement.keys() {
+            let Some((_, scheme)) = schemes.iter().find(|(n, _)| n == name) else {
+                continue;
+            };
+            let Some(credential) = security_header(scheme) else {
+                continue;
+            };
+            let value = format!("{}{{{{{}}}}}", credential.prefix, credential.variable);
+            let entry = SavedKeyValue::new(credential.key, value);
+            if credential.in_query {
+                request.params.push(entry);
+            } else {
+                request.headers.push(entry);
+            }
+        }
+    }
+
+    fn parameter_example(&self, parameter: &Value) -> Option<String> {
+        let schema = parameter.get("schema").map(|s| self.resolve(s));
+        parameter
+            .get("example")
+            .or_else(|| schema.and_then(|s| s.get("example")))
+            .or_else(|| parameter.get("default"))
+            .or_else(|| schema.and_then(|s| s.get("default")))
+            .or_else(|| {
+                parameter
+                    .get("enum")
+                    .or_else(|| schema.and_then(|s| s.get("enum")))
+                    .and_then(Value::as_array)
+                    .and_then(|values| values.first())
+            })
+            .map(scalar)
+    }
+
+    /// Build an example value from a schema, preferring declared examples
+    fn example(&self, schema: &Value, depth: usize) -> Value {
+        let schema = self.resolve(schema);
+        if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+            return example.clone();
+        }
+        if let Some(first) = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|values| values.first())
+        {
+            return first.clone();
+        }
+        if depth >= MAX_EXAMPLE_DEPTH {
+            return Value::Null;
+        }
+        for combinator in ["allOf", "oneOf", "anyOf"] {
+            if let Some(schemas) = schema.get(combinator).and_then(Value::as_array) {
+                if combinator != "allOf" {
+                    return schemas
+                        .first()
+                        .map(|s| self.example(s, depth + 1))
+                        .unwrap_or(Value::Null);
+                }
+                // allOf merges the properties of every part
+                let mut merged = Map::new();
+                for part in schemas {
+                    if let Value::Object(object) = self.example(part, depth + 1) {
+                        merged.extend(object);
+                    }
+                }
+                return Value::Object(merged);
+            }
+        }
+
+        let kind = schema.get("type").and_then(|t| match t {
+            Value::Array(types) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|t| *t != "null"),
+            other => other.as_str(),
+        });
+        let kind = kind.unwrap_or(if schema.get("properties").is_some() {
+            "object"
+        } else {
+            ""
+        });
+
+        match kind {
+            "object" => {
+                let mut object = Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (key, property) in properties {
+                        if self
+                            .resolve(property)
+                            .get("readOnly")
+                            .and_then(Value::as_bool)
+                            == Some(true)
+                        {
+                            continue;
+                        }
+                        object.insert(key.clone(), self.example(property, depth + 1));
+                    }
+                }
+                Value::Object(object)
+            }
+            "array" => match schema.get("items") {
+                Some(items) => json!([self.example(items, depth + 1)]),
+                None => json!([]),
+            },
+            "string" => json!(match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("date") => "2024-01-01",
+                Some("email") => "user@example.com",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                Some("uri") | Some("url") => "https://example.com",
+                _ => "string",
+            }),
+            "integer" => json!(0),
+            "number" => json!(0),
+            "boolean" => json!(false),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Where an API key or bearer scheme puts its credential
+struct Credential {
+    in_query: bool,
+    key: String,
+    /// Environment variable holding the secret
+    variable: String,
+    prefix: &'static str,
+}
+
+fn security_header(scheme: &Value) -> Option<Credential> {
+    let kind = scheme.get("type").and_then(Value::as_str)?;
+    match kind {
+        "apiKey" => {
+            let location = scheme.get("in").and_then(Value::as_str)?;
+            if location == "cookie" {
+                return None;
+            }
+            let name = scheme.get("name").and_then(Value::as_str)?;
+            Some(Credential {
+                in_query: location == "query",
+                key: name.to_string(),
+                variable: variable_name(name),
+                prefix: "",
+            })
+        }
+        "http"
+            if scheme
+                .get("scheme")
+                .and_then(Value::as_str)
+                .is_some_and(|s| s.eq_ignore_ascii_case("bearer")) =>
+        {
+            Some(Credential {
+                in_query: false,
+                key: "Authorization".to_string(),
+                variable: "token".to_string(),
+                prefix: "Bearer ",
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Substitute `{name}` path templates, turning those without an example into
+/// `{{name}}` variables so they show up as unresolved
+fn fill_path(path: &str, values: &[(String, String)]) -> String {
+    let mut result = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        result.push_str(&rest[..start]);
+        match values.iter().find(|(key, _)| key == name) {
+            Some((_, value)) => result.push_str(value),
+            None => result.push_str(&format!("{{{{{}}}}}", name)),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Turn a header name like `X-API-Key` into a variable name like `xApiKey`
+fn variable_name(name: &str) -> String {
+    let mut variable = String::new();
+    for (i, part) in name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|p| !p.is_empty())
+        .enumerate()
+    {
+        let part = part.to_lowercase();
+        if i == 0 {
+            variable.push_str(&part);
+        } else {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                variable.push(first.to_ascii_uppercase());
+                variable.extend(chars);
+            }
+        }
+    }
+    variable
+}
+
+fn pretty(value: &Value) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// A value as text for a URL, header or form field
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn import_document(name: &str, document: &str) -> (PathBuf, ImportSummary) {
+        let dir = std::env::temp_dir().join(format!(
+            "api-client-openapi-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("spec");
+        std::fs::write(&path, document).unwrap();
+        let folder = dir.join("requests");
+        let summary = import(&path, &folder).unwrap();
+        (folder, summary)
+    }
+
+    #[test]
+    fn imports_openapi_3_operations() {
+        let (folder, summary) = import_document(
+            "v3",
+            r#"
+openapi: 3.0.3
+info:
+  title: Pets
+servers:
+  - url: https://{region}.example.com/v1/
+    variables:
+      region:
+        default: eu
+security:
+  - key: []
+components:
+  securitySchemes:
+    key:
+      type: apiKey
+      in: header
+      name: X-API-Key
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id:
+          type: integer
+          readOnly: true
+        name:
+          type: string
+          example: Rex
+        born:
+          type: string
+          format: date
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema:
+          type: integer
+          example: 7
+    get:
+      summary: Show pet
+      tags: [pets]
+      parameters:
+        - name: fields
+          in: query
+          schema:
+            type: string
+            enum: [name, born]
+    put:
+      operationId: updatePet
+      tags: [pets]
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+  /upload:
+    post:
+      security: []
+      requestBody:
+        content:
+          multipart/form-data:
+            schema:
+              properties:
+                file:
+                  type: string
+                  format: binary
+                note:
+                  type: string
+    trace:
+      summary: Trace
+"#,
+        );
+        assert_eq!((summary.requests, summary.folders), (3, 1));
+        assert_eq!(summary.skipped, vec!["Trace: unsupported method TRACE"]);
+
+        let show = fs::load_request(&folder.join("pets").join("Show-pet.json")).unwrap();
+        assert_eq!(show.method, "GET");
+        assert_eq!(show.url, "{{baseUrl}}/pets/7");
+        assert_eq!(
+            show.params,
+            vec![SavedKeyValue {
+                enabled: false,
+                ..SavedKeyValue::new("fields", "name")
+            }]
+        );
+        assert_eq!(
+            show.headers,
+            vec![SavedKeyValue::new("X-API-Key", "{{xApiKey}}")]
+        );
+
+        let update = fs::load_request(&folder.join("pets").join("updatePet.json")).unwrap();
+        assert_eq!(update.body_mode, BodyMode::Json);
+        let body: Value = serde_json::from_str(&update.body).unwrap();
+        assert_eq!(body, json!({ "name": "Rex", "born": "2024-01-01" }));
+
+        let upload = fs::load_request(&folder.join("POST--upload.json")).unwrap();
+        assert_eq!(upload.body_mode, BodyMode::Multipart);
+        assert_eq!(
+            upload.form,
+            vec![
+                SavedKeyValue::new("file", "@"),
+                SavedKeyValue::new("note", "string"),
+            ]
+        );
+        assert!(upload.headers.is_empty());
+
+        let environments = EnvironmentStore::load(&folder);
+        let variables = environments.variables();
+        assert_eq!(environments.active.as_deref(), Some("Pets"));
+        assert_eq!(variables["baseUrl"], "https://eu.example.com/v1");
+        assert_eq!(variables.get("xApiKey").map(String::as_str), Some(""));
+
+        let _ = std::fs::remove_dir_all(folder.parent().unwrap());
+    }
+
+    #[test]
+    fn imports_swagger_2_operations() {
+        let (folder, summary) = import_document(
+            "v2",
+            r#"{
+                "swagger": "2.0",
+                "info": { "title": "Legacy" },
+                "host": "api.example.com",
+                "basePath": "/v2",
+                "schemes": ["http", "https"],
+                "securityDefinitions": {
+                    "bearer": { "type": "apiKey", "in": "query", "name": "access_token" }
+                },
+                "paths": {
+                    "/users/{name}": {
+                        "post": {
+                            "security": [{ "bearer": [] }],
+                            "consumes": ["application/x-www-form-urlencoded"],
+                            "parameters": [
+                                { "name": "name", "in": "path", "type": "string" },
+                                { "name": "email", "in": "formData", "type": "string", "default": "a@example.com" },
+                                { "name": "X-Trace", "in": "header", "required": true, "type": "string" }
+                            ]
+                        }
+                    }
+                }
+            }"#,
+        );
+        assert_eq!((summary.requests, summary.folders), (1, 0));
+
+        let request = fs::load_request(&folder.join("POST--users--name-.json")).unwrap();
+        assert_eq!(request.url, "{{baseUrl}}/users/{{name}}");
+        assert_eq!(request.body_mode, BodyMode::FormUrlEncoded);
+        assert_eq!(
+            request.form,
+            vec![SavedKeyValue::new("email", "a@example.com")]
+        );
+        assert_eq!(request.headers, vec![SavedKeyValue::new("X-Trace", "")]);
+        assert_eq!(
+            request.params,
+            vec![SavedKeyValue::new("access_token", "{{accessToken}}")]
+        );
+        assert_eq!(
+            EnvironmentStore::load(&folder).variables()["baseUrl"],
+            "https://api.example.com/v2"
+        );
+
+        let _ = std::fs::remove_dir_all(folder.parent().unwrap());
+    }
+}
@@ -0,0 +1,869 @@
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+use crate::environment::{EnvironmentStore, Variable};
+use crate::fs;
+use crate::request;
+use crate::settings::FolderSettings;
+use crate::types::{
+    ApiKeyLocation, AuthConfig, AwsSigV4Config, BodyMode, HttpMethod, OAuth2Config, OAuthGrant,
+    RequestFormat, SavedKeyValue, SavedRequest, TreeNode,
+};
+
+const SCHEMA_V21: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Read a Postman v2.0/v2.1 collection and write its requests into `folder`
+pub fn import(path: &Path, folder: &Path) -> Result<fs::ImportSummary, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let collection: Value =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let info = collection
+        .get("info")
+        .ok_or("Not a Postman collection (missing \"info\")")?;
+    let schema = info.get("schema").and_then(Value::as_str).unwrap_or("");
+    if !schema.contains("v2.") {
+        return Err("Only Postman collection format v2.0 and v2.1 is supported".to_string());
+    }
+    let name = info
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("Postman")
+        .to_string();
+
+    let mut summary = fs::ImportSummary::default();
+    let format = FolderSettings::load(folder).request_format;
+    import_items(
+        items(&collection),
+        folder,
+        collection.get("auth"),
+        format,
+        &mut summary,
+    )?;
+
+    let variables: Vec<Variable> = collection
+        .get("variable")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|v| {
+            Some(Variable {
+                key: v.get("key")?.as_str()?.to_string(),
+                value: value_string(v.get("value")),
+                enabled: !is_disabled(v),
+            })
+        })
+        .collect();
+    if !variables.is_empty() {
+        let mut environments = EnvironmentStore::load(folder);
+        environments.upsert(&name, variables);
+        environments.save(folder);
+        summary.environment = Some(name);
+    }
+
+    Ok(summary)
+}
+
+fn items(parent: &Value) -> &[Value] {
+    parent
+        .get("item")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn import_items(
+    items: &[Value],
+    dir: &Path,
+    inherited_auth: Option<&Value>,
+    format: RequestFormat,
+    summary: &mut fs::ImportSummary,
+) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+
+    for item in items {
+        let name = item
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("Untitled")
+            .to_string();
+        // Folder auth applies to everything inside unless overridden
+        let auth = own_auth(item.get("auth"), inherited_auth);
+
+        if item.get("item").is_some() {
+            summary.folders += 1;
+            import_items(
+                self::items(item),
+                &dir.join(fs::safe_dir_name(&name)),
+                auth,
+                format,
+                summary,
+            )?;
+            continue;
+        }
+
+        match convert_item(item, &name, auth) {
+            Ok(request) => {
+                let path = fs::unique_request_path(dir, &name, format);
+                fs::save_request(&path, &request)
+                    .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+                summary.requests += 1;
+            }
+            Err(e) => summary.skipped.push(format!("{}: {}", name, e)),
+        }
+    }
+    Ok(())
+}
+
+fn convert_item(item: &Value, name: &str, auth: Option<&Value>) -> Result<SavedRequest, String> {
+    let request = item.get("request").ok_or("no request")?;
+
+    // A request may be given as just its URL
+    if let Some(url) = request.as_str() {
+        let (url, params) = request::split_query(url);
+        let mut saved = SavedRequest::new(name, &HttpMethod::Get, url);
+        saved.params = params;
+        return Ok(saved);
+    }
+
+    let method_name = request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET");
+    let method = HttpMethod::parse(method_name)
+        .ok_or_else(|| format!("unsupported method {}", method_name))?;
+
+    let (url, params) = convert_url(request.get("url"));
+    let mut saved = SavedRequest::new(name, &method, url);
+    saved.params = params;
+    saved.description = description(request.get("description"))
+        .or_else(|| description(item.get("description")))
+        .unwrap_or_default();
+    saved.headers = key_values(request.get("header"));
+
+    apply_auth(own_auth(request.get("auth"), auth), &mut saved);
+
+    if let Some(body) = request.get("body") {
+        convert_body(body, &mut saved)?;
+    }
+
+    Ok(saved)
+}
+
+/// Split a Postman URL into the base URL and query params
+fn convert_url(url: Option<&Value>) -> (String, Vec<SavedKeyValue>) {
+    match url {
+        Some(Value::String(raw)) => request::split_query(raw),
+        Some(url @ Value::Object(_)) => {
+            let raw = match url.get("raw").and_then(Value::as_str) {
+                Some(raw) => raw.to_string(),
+                None => raw_from_parts(url),
+            };
+            let (base, parsed) = request::split_query(&raw);
+            // The query list also holds disabled params that are missing from `raw`
+            let params = match url.get("query").and_then(Value::as_array) {
+                Some(query) => query
+                    .iter()
+                    .filter_map(|q| {
+                        let key = q.get("key").and_then(Value::as_str)?;
+                        Some(SavedKeyValue {
+                            key: request::urldecode(key),
+                            value: request::urldecode(&value_string(q.get("value"))),
+                            enabled: !is_disabled(q),
+                        })
+                    })
+                    .collect(),
+                None => parsed,
+            };
+            (resolve_path_variables(&base, url), params)
+        }
+        _ => (String::new(), Vec::new()),
+    }
+}
+
+/// Build a URL from the `protocol`/`host`/`port`/`path` parts of a URL object
+fn raw_from_parts(url: &Value) -> String {
+    let join = |value: Option<&Value>, separator: &str| match value {
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .map(|p| value_string(Some(p)))
+            .collect::<Vec<_>>()
+            .join(separator),
+        Some(other) => value_string(Some(other)),
+        None => String::new(),
+    };
+    let mut raw = String::new();
+    if let Some(protocol) = url.get("protocol").and_then(Value::as_str) {
+        raw.push_str(&format!("{}://", protocol));
+    }
+    raw.push_str(&join(url.get("host"), "."));
+    if let Some(port) = url.get("port").and_then(Value::as_str) {
+        raw.push_str(&format!(":{}", port));
+    }
+    let path = join(url.get("path"), "/");
+    if !path.is_empty() {
+        raw.push('/');
+        raw.push_str(&path);
+    }
+    raw
+}
+
+/// Replace `:id` path segments with the values Postman stores next to the URL
+fn resolve_path_variables(base: &str, url: &Value) -> String {
+    let Some(variables) = url.get("variable").and_then(Value::as_array) else {
+        return base.to_string();
+    };
+    base.split('/')
+        .map(|segment| {
+            segment
+                .strip_prefix(':')
+                .and_then(|name| {
+                    variables
+                        .iter()
+                        .find(|v| v.get("key").and_then(Value::as_str) == Some(name))
+                })
+                .map(|v| value_string(v.get("value")))
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| segment.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn convert_body(body: &Value, saved: &mut SavedRequest) -> Result<(), String> {
+    if body.get("disabled").and_then(Value::as_bool) == Some(true) {
+        return Ok(());
+    }
+    match body.get("mode").and_then(Value::as_str).unwrap_or("") {
+        "raw" => {
+            saved.body = value_string(body.get("raw"));
+            let language = body
+                .pointer("/options/raw/language")
+                .and_then(Value::as_str)
+                .unwrap_or("");
+            let content_type = saved
+                .headers
+                .iter()
+                .find(|h| h.key.eq_ignore_ascii_case("content-type"))
+                .map(|h| h.value.to_lowercase())
+                .unwrap_or_default();
+            saved.body_mode = if language == "json" || content_type.contains("json") {
+                BodyMode::Json
+            } else if language == "xml" || content_type.contains("xml") {
+                BodyMode::Xml
+            } else {
+                BodyMode::Text
+            };
+        }
+        "urlencoded" => {
+            saved.body_mode = BodyMode::FormUrlEncoded;
+            saved.form = key_values(body.get("urlencoded"));
+        }
+        "formdata" => {
+            saved.body_mode = BodyMode::Multipart;
+            saved.form = body
+                .get("formdata")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|field| {
+                    let key = field.get("key").and_then(Value::as_str)?;
+                    let value = if field.get("type").and_then(Value::as_str) == Some("file") {
+                        // `src` is a path, or a list of paths for multiple files
+                        let src = match field.get("src") {
+                            Some(Value::Array(paths)) => value_string(paths.first()),
+                            other => value_string(other),
+                        };
+                        format!("@{}", src)
+                    } else {
+                        value_string(field.get("value"))
+                    };
+                    Some(SavedKeyValue {
+                        key: key.to_string(),
+                        value,
+                        enabled: !is_disabled(field),
+                    })
+                })
+                .collect();
+        }
+        "graphql" => {
+            let graphql = body.get("graphql").cloned().unwrap_or(Value::Null);
+            let variables = match graphql.get("variables") {
+                Some(Value::String(text)) if !text.trim().is_empty() => {
+                    serde_json::from_str(text).unwrap_or(Value::Null)
+                }
+                Some(value @ Value::Object(_)) => value.clone(),
+                _ => Value::Null,
+            };
+            let payload = json!({
+                "query": value_string(graphql.get("query")),
+                "variables": variables,
+            });
+            saved.body_mode = BodyMode::Json;
+            saved.body = serde_json::to_string_pretty(&payload).unwrap_or_default();
+        }
+        "file" => return Err("binary file bodies are not supported".to_string()),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Auth block that applies to an item: its own, unless it is missing or set to
+/// `inherit`; a `noauth` block is kept so it overrides the parent's auth
+fn own_auth<'a>(auth: Option<&'a Value>, inherited: Option<&'a Value>) -> Option<&'a Value> {
+    match auth.and_then(|a| a.get("type")).and_then(Value::as_str) {
+        None | Some("inherit") => inherited,
+        Some(_) => auth,
+    }
+}
+
+/// Turn a Postman auth block into the request's auth settings
+fn apply_auth(auth: Option<&Value>, saved: &mut SavedRequest) {
+    let Some(auth) = auth else {
+        return;
+    };
+    let kind = auth.get("type").and_then(Value::as_str).unwrap_or("noauth");
+    // v2.1 stores the settings as a key/value list, v2.0 as an object
+    let setting = |name: &str| -> String {
+        match auth.get(kind) {
+            Some(Value::Array(entries)) => entries
+                .iter()
+                .find(|e| e.get("key").and_then(Value::as_str) == Some(name))
+                .map(|e| value_string(e.get("value")))
+                .unwrap_or_default(),
+            Some(Value::Object(map)) => value_string(map.get(name)),
+            _ => String::new(),
+        }
+    };
+
+    match kind {
+        "basic" => {
+            saved.auth = AuthConfig::Basic {
+                username: setting("username"),
+                password: setting("password"),
+            }
+        }
+        "bearer" => {
+            saved.auth = AuthConfig::Bearer {
+                token: setting("token"),
+            }
+        }
+        "apikey" => {
+            saved.auth = AuthConfig::ApiKey {
+                key: setting("key"),
+                value: setting("value"),
+                location: if setting("in") == "query" {
+                    ApiKeyLocation::Query
+                } else {
+                    ApiKeyLocation::Header
+                },
+            }
+        }
+        "digest" => {
+            saved.auth = AuthConfig::Digest {
+                username: setting("username"),
+                password: setting("password"),
+            }
+        }
+        "awsv4" => {
+            saved.auth = AuthConfig::AwsSigV4(AwsSigV4Config {
+                access_key: setting("accessKey"),
+                secret_key: setting("secretKey"),
+                session_token: setting("sessionToken"),
+                region: setting("region"),
+                service: setting("service"),
+                unsigned_payload: false,
+            })
+        }
+        "oauth2" => {
+            let grant = match setting("grant_type").as_str() {
+                "password_credentials" => OAuthGrant::Password,
+                "authorization_code" | "authorization_code_with_pkce" => {
+                    OAuthGrant::AuthorizationCode
+                }
+                _ => OAuthGrant::ClientCredentials,
+            };
+            saved.auth = AuthConfig::OAuth2(OAuth2Config {
+                grant,
+                auth_url: setting("authUrl"),
+                token_url: setting("accessTokenUrl"),
+                client_id: setting("clientId"),
+                client_secret: setting("clientSecret"),
+                scope: setting("scope"),
+                username: setting("username"),
+                password: setting("password"),
+                redirect_uri: setting("redirect_uri"),
+            })
+        }
+        "noauth" => saved.auth = AuthConfig::None,
+        _ => {}
+    }
+}
+
+fn key_values(list: Option<&Value>) -> Vec<SavedKeyValue> {
+    list.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            Some(SavedKeyValue {
+                key: entry.get("key")?.as_str()?.to_string(),
+                value: value_string(entry.get("value")),
+                enabled: !is_disabled(entry),
+            })
+        })
+        .collect()
+}
+
+fn is_disabled(entry: &Value) -> bool {
+    entry.get("disabled").and_then(Value::as_bool) == Some(true)
+}
+
+fn description(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text.clone()),
+        other => other
+            .get("content")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+/// Text of a scalar JSON value; strings are returned without quotes
+fn value_string(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Write every request under `folder` to `dest` as a Postman v2.1 collection,
+/// returning how many requests were exported
+pub fn export(folder: &Path, dest: &Path) -> Result<usize, String> {
+    let name = folder
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Collection")
+        .to_string();
+
+    let mut count = 0;
+    let item = export_tree(&fs::scan_tree(folder), &mut count);
+
+    let mut collection = json!({
+        "info": {
+            "name": name,
+            "schema": SCHEMA_V21,
+        },
+        "item": item,
+    });
+
+    // Variables of the active environment become collection variables
+    let environments = EnvironmentStore::load(folder);
+    if let Some(environment) = environments.active_environment() {
+        let variables: Vec<Value> = environment
+            .variables
+            .iter()
+            .map(|v| {
+                let mut variable = json!({ "key": v.key, "value": v.value });
+                if !v.enabled {
+                    variable["disabled"] = json!(true);
+                }
+                variable
+            })
+            .collect();
+        collection["variable"] = json!(variables);
+    }
+
+    let content = serde_json::to_string_pretty(&collection).map_err(|e| e.to_string())?;
+    std::fs::write(dest, content).map_err(|e| format!("Failed to write {:?}: {}", dest, e))?;
+    Ok(count)
+}
+
+/// Items for the requests and folders of a tree, in the order the sidebar shows them
+fn export_tree(nodes: &[TreeNode], count: &mut usize) -> Vec<Value> {
+    let mut items = Vec::new();
+    for node in nodes {
+        match node {
+            TreeNode::Folder { name, children, .. } => {
+                let children = export_tree(children, count);
+                if !children.is_empty() {
+                    items.push(json!({ "name": name, "item": children }));
+                }
+            }
+            TreeNode::Request(entry) => {
+                if let Some(request) = fs::load_request(&entry.path) {
+                    *count += 1;
+                    items.push(export_request(&request));
+                }
+            }
+        }
+    }
+    items
+}
+
+/// Postman v2.1 auth block for a request's auth settings
+fn export_auth(auth: &AuthConfig) -> Option<Value> {
+    let setting = |key: &str, value: &str| json!({ "key": key, "value": value, "type": "string" });
+    let (kind, settings) = match auth {
+        AuthConfig::None => return None,
+        AuthConfig::Basic { username, password } => (
+            "basic",
+            vec![setting("username", username), setting("password", password)],
+        ),
+        AuthConfig::Bearer { token } => ("bearer", vec![setting("token", token)]),
+        AuthConfig::ApiKey {
+            key,
+            value,
+            location,
+        } => (
+            "apikey",
+            vec![
+                setting("key", key),
+                setting("value", value),
+                setting(
+                    "in",
+                    match location {
+                        ApiKeyLocation::Header => "header",
+                        ApiKeyLocation::Query => "query",
+                    },
+                ),
+            ],
+        ),
+        AuthConfig::Digest { username, password } => (
+            "digest",
+            vec![setting("username", username), setting("password", password)],
+        ),
+        AuthConfig::OAuth2(config) => {
+            let grant_type = match config.grant {
+                OAuthGrant::ClientCredentials => "client_credentials",
+                OAuthGrant::Password => "password_credentials",
+                OAuthGrant::AuthorizationCode => "authorization_code_with_pkce",
+            };
+            let mut settings = vec![
+                setting("grant_type", grant_type),
+                setting("accessTokenUrl", &config.token_url),
+                setting("clientId", &config.client_id),
+                setting("clientSecret", &config.client_secret),
+                setting("scope", &config.scope),
+            ];
+            match config.grant {
+                OAuthGrant::ClientCredentials => {}
+                OAuthGrant::Password => {
+                    settings.push(setting("username", &config.username));
+                    settings.push(setting("password", &config.password));
+                }
+                OAuthGrant::AuthorizationCode => {
+                    settings.push(setting("authUrl", &config.auth_url));
+                    settings.push(setting("redirect_uri", &config.redirect_uri));
+                    settings.push(setting("challengeAlgorithm", "S256"));
+                }
+            }
+            ("oauth2", settings)
+        }
+        AuthConfig::AwsSigV4(config) => (
+            "awsv4",
+            vec![
+                setting("accessKey", &config.access_key),
+                setting("secretKey", &config.secret_key),
+                setting("sessionToken", &config.session_token),
+                setting("region", &config.region),
+                setting("service", &config.service),
+            ],
+        ),
+    };
+    let mut block = json!({ "type": kind });
+    block[kind] = json!(settings);
+    Some(block)
+}
+
+fn export_request(saved: &SavedRequest) -> Value {
+    let key_value = |kv: &SavedKeyValue| {
+        let mut entry = json!({ "key": kv.key, "value": kv.value });
+        if !kv.enabled {
+            entry["disabled"] = json!(true);
+        }
+        entry
+    };
+
+    // Postman keeps `raw` unencoded so `{{variables}}` stay readable
+    let query: Vec<String> = saved
+        .params
+        .iter()
+        .filter(|p| p.enabled && !p.key.is_empty())
+        .map(|p| format!("{}={}", p.key, p.value))
+        .collect();
+    let raw = if query.is_empty() {
+        saved.url.clone()
+    } else if saved.url.contains('?') {
+        format!("{}&{}", saved.url, query.join("&"))
+    } else {
+        format!("{}?{}", saved.url, query.join("&"))
+    };
+
+    let mut url = url_parts(&saved.url);
+    url.insert("raw".to_string(), json!(raw));
+    if !saved.params.is_empty() {
+        url.insert(
+            "query".to_string(),
+            json!(saved.params.iter().map(key_value).collect::<Vec<_>>()),
+        );
+    }
+
+    let mut request = json!({
+        "method": saved.http_method().as_str(),
+        "header": saved.headers.iter().map(key_value).collect::<Vec<_>>(),
+        "url": url,
+    });
+    if !saved.description.is_empty() {
+        request["description"] = json!(saved.description);
+    }
+    if let Some(auth) = export_auth(&saved.auth) {
+        request["auth"] = auth;
+    }
+
+    let body = match saved.body_mode {
+        BodyMode::None => None,
+        BodyMode::Json | BodyMode::Text | BodyMode::Xml => {
+            let language = match saved.body_mode {
+                BodyMode::Json => "json",
+                BodyMode::Xml => "xml",
+                _ => "text",
+            };
+            Some(json!({
+                "mode": "raw",
+                "raw": saved.body,
+                "options": { "raw": { "language": language } },
+            }))
+        }
+        BodyMode::FormUrlEncoded => Some(json!({
+            "mode": "urlencoded",
+            "urlencoded": saved.form.iter().map(key_value).collect::<Vec<_>>(),
+        })),
+        BodyMode::Multipart => Some(json!({
+            "mode": "formdata",
+            "formdata": saved
+                .form
+                .iter()
+                .map(|kv| {
+                    let mut field = match kv.value.strip_prefix('@') {
+                        Some(path) => json!({ "key": kv.key, "type": "file", "src": path }),
+                        None => json!({ "key": kv.key, "type": "text", "value": kv.value }),
+                    };
+                    if !kv.enabled {
+                        field["disabled"] = json!(true);
+                    }
+                    field
+                })
+                .collect::<Vec<_>>(),
+        })),
+    };
+    if let Some(body) = body {
+        request["body"] = body;
+    }
+
+    json!({
+        "name": saved.name,
+        "request": request,
+    })
+}
+
+/// Split a URL without its query into Postman's protocol/host/port/path parts
+fn url_parts(url: &str) -> Map<String, Value> {
+    let mut parts = Map::new();
+    let url = url.split(['?', '#']).next().unwrap_or("");
+    let rest = match url.split_once("://") {
+        Some((protocol, rest)) => {
+            parts.insert("protocol".to_string(), json!(protocol));
+            rest
+        }
+        None => url,
+    };
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => {
+            parts.insert("port".to_string(), json!(port));
+            host
+        }
+        _ => authority,
+    };
+    parts.insert(
+        "host".to_string(),
+        json!(host.split('.').collect::<Vec<_>>()),
+    );
+    if !path.is_empty() {
+        parts.insert(
+            "path".to_string(),
+            json!(path.split('/').collect::<Vec<_>>()),
+        );
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "api-client-postman-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn inherits_auth_unless_turned_off() {
+        let dir = temp_dir("auth");
+        let bearer = json!({ "type": "bearer", "bearer": [{ "key": "token", "value": "abc" }] });
+        let request = |name: &str, auth: Value| json!({ "name": name, "request": { "method": "GET", "url": "https://example.com", "auth": auth } });
+        let collection = json!({
+            "info": { "name": "Auth", "schema": SCHEMA_V21 },
+            "auth": bearer,
+            "item": [
+                request("Inherited", json!({ "type": "inherit" })),
+                request("Unset", Value::Null),
+                request("Off", json!({ "type": "noauth" })),
+                {
+                    "name": "Public",
+                    "auth": { "type": "noauth" },
+                    "item": [
+                        request("Nested", json!({ "type": "inherit" })),
+                        request("Own", json!({ "type": "basic", "basic": { "username": "ada" } })),
+                    ],
+                },
+            ],
+        });
+        let path = dir.join("collection.json");
+        std::fs::write(&path, collection.to_string()).unwrap();
+        let folder = dir.join("requests");
+        import(&path, &folder).unwrap();
+
+        let auth = |file: &str| fs::load_request(&folder.join(file)).unwrap().auth;
+        let token = AuthConfig::Bearer {
+            token: "abc".to_string(),
+        };
+        assert_eq!(auth("Inherited.json"), token);
+        assert_eq!(auth("Unset.json"), token);
+        assert_eq!(auth("Off.json"), AuthConfig::None);
+        assert_eq!(auth("Public/Nested.json"), AuthConfig::None);
+        assert_eq!(
+            auth("Public/Own.json"),
+            AuthConfig::Basic {
+                username: "ada".to_string(),
+                password: String::new(),
+            }
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn exported_collections_import_unchanged() {
+        let dir = temp_dir("round-trip");
+        let source = dir.join("Shop");
+        std::fs::create_dir_all(source.join("orders")).unwrap();
+
+        let mut search = SavedRequest::new("search", &HttpMethod::Get, "https://{{host}}/items");
+        search.description = "Find items".to_string();
+        search.params = vec![
+            SavedKeyValue::new("q", "red shoes"),
+            SavedKeyValue {
+                enabled: false,
+                ..SavedKeyValue::new("page", "2")
+            },
+        ];
+        search.headers = vec![SavedKeyValue::new("Accept", "application/json")];
+        search.auth = AuthConfig::ApiKey {
+            key: "X-Key".to_string(),
+            value: "{{key}}".to_string(),
+            location: ApiKeyLocation::Query,
+        };
+
+        let mut create = SavedRequest::new("create", &HttpMethod::Post, "https://shop:8443/orders");
+        create.body_mode = BodyMode::Json;
+        create.body = r#"{"item": 1}"#.to_string();
+        create.auth = AuthConfig::OAuth2(OAuth2Config {
+            grant: OAuthGrant::Password,
+            token_url: "https://auth.example.com/token".to_string(),
+            client_id: "shop".to_string(),
+            username: "ada".to_string(),
+            password: "secret".to_string(),
+            ..Default::default()
+        });
+
+        let mut upload = SavedRequest::new("upload", &HttpMethod::Put, "https://shop/orders/1");
+        upload.body_mode = BodyMode::Multipart;
+        upload.form = vec![
+            SavedKeyValue::new("note", "gift"),
+            SavedKeyValue::new("receipt", "@/tmp/receipt.pdf"),
+        ];
+        upload.auth = AuthConfig::AwsSigV4(AwsSigV4Config {
+            access_key: "AKID".to_string(),
+            secret_key: "SECRET".to_string(),
+            region: "eu-west-1".to_string(),
+            service: "execute-api".to_string(),
+            ..Default::default()
+        });
+
+        let mut cancel = SavedRequest::new("cancel", &HttpMethod::Patch, "https://shop/orders/1");
+        cancel.body_mode = BodyMode::FormUrlEncoded;
+        cancel.form = vec![SavedKeyValue::new("status", "cancelled")];
+        cancel.auth = AuthConfig::Digest {
+            username: "ada".to_string(),
+            password: "secret".to_string(),
+        };
+
+        let files = [
+            ("search.json", &search),
+            ("orders/create.json", &create),
+            ("orders/upload.json", &upload),
+            ("orders/cancel.json", &cancel),
+        ];
+        for (file, request) in files {
+            fs::save_request(&source.join(file), request).unwrap();
+        }
+        let mut environments = EnvironmentStore::default();
+        environments.upsert(
+            "local",
+            vec![Variable {
+                key: "host".to_string(),
+                value: "localhost".to_string(),
+                enabled: true,
+            }],
+        );
+        environments.save(&source);
+        // A link back up the tree is left out rather than followed
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&source, source.join("orders").join("loop")).unwrap();
+
+        let collection = dir.join("shop.postman_collection.json");
+        assert_eq!(export(&source, &collection).unwrap(), 4);
+        let target = dir.join("imported");
+        let summary = import(&collection, &target).unwrap();
+        assert_eq!((summary.requests, summary.folders), (4, 1));
+        assert!(summary.skipped.is_empty(), "{:?}", summary.skipped);
+
+        // Compared as JSON, the way the files are written
+        for (file, request) in files {
+            let imported = fs::load_request(&target.join(file)).unwrap();
+            assert_eq!(
+                serde_json::to_value(imported).unwrap(),
+                serde_json::to_value(request).unwrap(),
+                "{}",
+                file
+            );
+        }
+        assert_eq!(summary.environment.as_deref(), Some("Shop"));
+        assert_eq!(
+            EnvironmentStore::load(&target).variables().get("host"),
+            Some(&"localhost".to_string())
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,12 +1,573 @@
-use crate::types::HttpMethod;
+use base64::Engine;
+use encoding_rs::Encoding;
 
+use crate::client::{ClientManager, ClientSettings};
+use crate::config::{ProxyConfig, TlsConfig};
+use crate::cookies::CookieJar;
+use crate::digest;
+use crate::environment;
+use crate::oauth2;
+use crate::sigv4;
+use crate::timing::{self, Timings};
+use crate::types::{
+    ApiKeyLocation, AuthConfig, AwsSigV4Config, BodyMode, HttpMethod, OAuth2Config,
+    RequestSettings, SavedKeyValue, SavedRequest,
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+
+/// Body payload ready to be sent
+#[derive(Clone, Debug)]
+pub enum RequestBody {
+    None,
+    Raw(String),
+    Form(Vec<(String, String)>),
+    /// Multipart fields; a value starting with `@` is read from that file path
+    Multipart(Vec<(String, String)>),
+}
+
+/// A request with environment variables resolved, ready to be executed
+#[derive(Clone, Debug)]
+pub struct PreparedRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: RequestBody,
+    /// Resolved credentials; Basic, Bearer and API key auth are already applied to
+    /// `headers` or `url`, the others are handled when the request is executed
+    pub auth: AuthConfig,
+    /// Send stored cookies and keep the ones received
+    pub use_cookie_jar: bool,
+    pub settings: RequestSettings,
+    /// Write the response body to this file instead of keeping it in memory
+    pub download_to: Option<PathBuf>,
+}
+
+/// Where a request is sent from, for state kept per folder and environment, and the
+/// clients it is sent with
+#[derive(Clone, Debug, Default)]
+pub struct RequestContext {
+    pub folder: Option<PathBuf>,
+    /// Name of the active environment
+    pub environment: Option<String>,
+    pub clients: ClientManager,
+    /// Folder defaults for the settings a request leaves unset
+    pub defaults: RequestSettings,
+    /// Proxy for the active environment
+    pub proxy: ProxyConfig,
+    /// Certificate settings per host
+    pub tls: Vec<TlsConfig>,
+    /// Directory of the cookie and OAuth token stores; the user's data directory
+    /// when unset
+    pub store_dir: Option<PathBuf>,
+}
+
+impl RequestContext {
+    /// Identifies the folder and environment in stores kept outside the folder
+    pub fn key(&self) -> String {
+        let folder = self
+            .folder
+            .as_ref()
+            .map(|folder| folder.display().to_string())
+            .unwrap_or_default();
+        format!("{}\n{}", folder, self.environment.as_deref().unwrap_or(""))
+    }
+
+    /// Certificate settings for the host of `url`, if any match it
+    pub fn tls_for(&self, url: &str) -> Option<TlsConfig> {
+        let url = reqwest::Url::parse(url).ok()?;
+        let host = url.host_str()?;
+        self.tls.iter().find(|tls| tls.matches(host)).cloned()
+    }
+
+    /// Path of the store file `name`
+    pub fn store_path(&self, name: &str) -> PathBuf {
+        self.store_dir
+            .clone()
+            .unwrap_or_else(|| {
+                dirs::data_local_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join("api-client")
+            })
+            .join(name)
+    }
+}
+
+impl PreparedRequest {
+    /// Resolve `{{variables}}`, drop disabled entries and append query params to the URL
+    pub fn from_saved(request: &SavedRequest, vars: &HashMap<String, String>) -> Self {
+        let resolve = |entries: &[SavedKeyValue]| -> Vec<(String, String)> {
+            entries
+                .iter()
+                .filter(|kv| kv.enabled && !kv.key.is_empty())
+                .map(|kv| {
+                    (
+                        environment::substitute(&kv.key, vars),
+                        environment::substitute(&kv.value, vars),
+                    )
+                })
+                .collect()
+        };
+
+        let auth = resolve_auth(&request.auth, vars);
+        let mut params = resolve(&request.params);
+        let mut headers = resolve(&request.headers);
+
+        // Headers set explicitly in the Headers tab take precedence over the Auth tab
+        let mut set_header = |key: &str, value: String| {
+            if !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                headers.push((key.to_string(), value));
+            }
+        };
+        match &auth {
+            AuthConfig::Basic { username, password } => {
+                let credentials = format!("{}:{}", username, password);
+                let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+                set_header("Authorization", format!("Basic {}", encoded));
+            }
+            AuthConfig::Bearer { token } => {
+                set_header("Authorization", format!("Bearer {}", token));
+            }
+            AuthConfig::ApiKey {
+                key,
+                value,
+                location,
+            } if !key.is_empty() => match location {
+                ApiKeyLocation::Header => set_header(key, value.clone()),
+                ApiKeyLocation::Query => params.push((key.clone(), value.clone())),
+            },
+            _ => {}
+        }
+
+        let url = build_url(&environment::substitute(&request.url, vars), &params);
+
+        let body = match request.body_mode {
+            BodyMode::None => RequestBody::None,
+            BodyMode::FormUrlEncoded => RequestBody::Form(resolve(&request.form)),
+            BodyMode::Multipart => RequestBody::Multipart(resolve(&request.form)),
+            BodyMode::Json | BodyMode::Text | BodyMode::Xml => {
+                RequestBody::Raw(environment::substitute(&request.body, vars))
+            }
+        };
+
+        if let Some(content_type) = request.body_mode.content_type() {
+            if !headers
+                .iter()
+                .any(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            {
+                headers.push(("Content-Type".to_string(), content_type.to_string()));
+            }
+        }
+
+        Self {
+            method: request.http_method(),
+            url,
+            headers,
+            body,
+            auth,
+            use_cookie_jar: !request.skip_cookie_jar,
+            settings: request.settings.clone(),
+            download_to: None,
+        }
+    }
+
+    /// Body that is actually sent; only POST, PUT and PATCH carry one
+    pub fn effective_body(&self) -> &RequestBody {
+        match (&self.method, &self.body) {
+            (HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch, RequestBody::Raw(body))
+                if body.is_empty() =>
+            {
+                &RequestBody::None
+            }
+            (HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch, body) => body,
+            _ => &RequestBody::None,
+        }
+    }
+}
+
+/// Substitute `{{variables}}` in the credentials of an auth config
+fn resolve_auth(auth: &AuthConfig, vars: &HashMap<String, String>) -> AuthConfig {
+    let resolve = |text: &String| environment::substitute(text, vars);
+    match auth {
+        AuthConfig::None => AuthConfig::None,
+        AuthConfig::Basic { username, password } => AuthConfig::Basic {
+            username: resolve(username),
+            password: resolve(password),
+        },
+        AuthConfig::Bearer { token } => AuthConfig::Bearer {
+            token: resolve(token),
+        },
+        AuthConfig::ApiKey {
+            key,
+            value,
+            location,
+        } => AuthConfig::ApiKey {
+            key: resolve(key),
+            value: resolve(value),
+            location: *location,
+        },
+        AuthConfig::Digest { username, password } => AuthConfig::Digest {
+            username: resolve(username),
+            password: resolve(password),
+        },
+        AuthConfig::OAuth2(config) => AuthConfig::OAuth2(OAuth2Config {
+            grant: config.grant,
+            auth_url: resolve(&config.auth_url),
+            token_url: resolve(&config.token_url),
+            client_id: resolve(&config.client_id),
+            client_secret: resolve(&config.client_secret),
+            scope: resolve(&config.scope),
+            username: resolve(&config.username),
+            password: resolve(&config.password),
+            redirect_uri: resolve(&config.redirect_uri),
+        }),
+        AuthConfig::AwsSigV4(config) => AuthConfig::AwsSigV4(AwsSigV4Config {
+            access_key: resolve(&config.access_key),
+            secret_key: resolve(&config.secret_key),
+            session_token: resolve(&config.session_token),
+            region: resolve(&config.region),
+            service: resolve(&config.service),
+            unsigned_payload: config.unsigned_payload,
+        }),
+    }
+}
+
+/// Append query parameters to a URL, keeping any query it already has
+pub fn build_url(base_url: &str, params: &[(String, String)]) -> String {
+    if params.is_empty() {
+        return base_url.to_string();
+    }
+
+    let query = params
+        .iter()
+        .map(|(k, v)| format!("{}={}", urlencoding(k), urlencoding(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    if base_url.contains('?') {
+        format!("{}&{}", base_url, query)
+    } else {
+        format!("{}?{}", base_url, query)
+    }
+}
+
+/// Simple URL encoding helper; other characters are encoded as their UTF-8 bytes
+pub fn urlencoding(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | '~' => encoded.push(c),
+            ' ' => encoded.push('+'),
+            _ => {
+                for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                    encoded.push_str(&format!("%{:02X}", byte));
+                }
+            }
+        }
+    }
+    encoded
+}
+
+/// Split a URL into its base and decoded query parameters
+pub fn split_query(url: &str) -> (String, Vec<SavedKeyValue>) {
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = url.split_once('?') else {
+        return (
+            fragment.map_or(url.to_string(), |f| format!("{}#{}", url, f)),
+            Vec::new(),
+        );
+    };
+    let base = fragment.map_or(base.to_string(), |f| format!("{}#{}", base, f));
+    match parse_query(query) {
+        Some(params) => (base, params),
+        None => (url.to_string(), Vec::new()),
+    }
+}
+
+/// Decode `a=1&b=2` into key-value pairs; `None` if it isn't such a list
+pub fn parse_query(text: &str) -> Option<Vec<SavedKeyValue>> {
+    if text.is_empty() {
+        return Some(Vec::new());
+    }
+    text.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            if key.is_empty() || key.contains(char::is_whitespace) || key.contains(['{', '"']) {
+                return None;
+            }
+            Some(SavedKeyValue::new(urldecode(key), urldecode(value)))
+        })
+        .collect()
+}
+
+/// Reverse of `urlencoding`, leaving malformed escapes as-is
+pub fn urldecode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len()
+                && bytes[i + 1].is_ascii_hexdigit()
+                && bytes[i + 2].is_ascii_hexdigit() =>
+            {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("00");
+                out.push(u8::from_str_radix(hex, 16).unwrap_or(0));
+                i += 2;
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// A fully captured HTTP response
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Canonical reason phrase for the status code (e.g. "Not Found")
+    pub reason: String,
+    /// HTTP version as shown on the wire (e.g. "HTTP/1.1")
+    pub version: String,
+    /// Response headers in received order; repeated headers keep every value
+    pub headers: Vec<(String, String)>,
+    pub remote_addr: Option<SocketAddr>,
+    /// Empty when the body was written to `body_file`
+    pub body: Vec<u8>,
+    pub body_file: Option<PathBuf>,
+    /// Bytes of the body received, wherever they went
+    pub body_size: u64,
+    /// The body passed the maximum download size and was cut off there
+    pub truncated: bool,
+    /// Server certificates were not checked on the way to this response
+    pub unverified_tls: bool,
+    pub timings: Timings,
+}
+
+/// A response body while it is being read
+pub struct BodyProgress<'a> {
+    /// Everything received so far; empty when the body goes to a file
+    pub body: &'a [u8],
+    pub received: u64,
+    encoding: &'static Encoding,
+}
+
+impl BodyProgress<'_> {
+    /// Body received so far, decoded like `HttpResponse::text`
+    pub fn text(&self) -> String {
+        self.encoding.decode(self.body).0.into_owned()
+    }
+}
+
+/// Reports a body in progress at most this often
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+impl HttpResponse {
+    /// Body decoded with the charset of its `Content-Type`, replacing invalid sequences
+    pub fn text(&self) -> String {
+        body_encoding(&self.headers)
+            .decode(&self.body)
+            .0
+            .into_owned()
+    }
+}
+
+/// Encoding named by the `charset` of a `Content-Type` header; UTF-8 when there is
+/// none or it is unknown. A byte order mark in the body still wins.
+fn body_encoding(headers: &[(String, String)]) -> &'static Encoding {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .and_then(|(_, value)| {
+            value.split(';').skip(1).find_map(|param| {
+                let (name, label) = param.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("charset")
+                    .then(|| label.trim().trim_matches('"'))
+            })
+        })
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8)
+}
+
+/// Send a request, following redirects; `progress` sees each response body as it
+/// is read
 pub async fn execute_request(
-    url: &str,
-    method: &HttpMethod,
-    body: &str,
-    headers: &[(String, String)],
-) -> Result<(u16, String), String> {
-    let client = reqwest::Client::new();
+    request: &PreparedRequest,
+    context: &RequestContext,
+    progress: Option<&dyn Fn(BodyProgress)>,
+) -> Result<HttpResponse, String> {
+    let started = Instant::now();
+    let mut request = request.clone();
+    request.settings = request.settings.or(&context.defaults);
+    let mut jar = request.use_cookie_jar.then(|| CookieJar::load(context));
+
+    let max_redirects = request.settings.max_redirects();
+    let mut redirects = 0;
+    let mut unverified_tls = false;
+    let result = loop {
+        // Certificate settings depend on the host, which a redirect may change
+        let tls = context.tls_for(&request.url);
+        unverified_tls |= tls.as_ref().is_some_and(|tls| tls.skip_verification);
+        let client = match context.clients.client(&ClientSettings {
+            connect_timeout: request.settings.connect_timeout(),
+            http_version: request.settings.http_version(),
+            proxy: context.proxy.clone(),
+            tls,
+        }) {
+            Ok(client) => client,
+            Err(e) => break Err(e),
+        };
+
+        let mut response =
+            match send_authenticated(&client, &request, context, jar.as_mut(), progress).await {
+                Ok(response) => response,
+                Err(e) => break Err(e),
+            };
+        response.unverified_tls = unverified_tls;
+        response.timings.prior_ms =
+            (timing::millis(started.elapsed()) - response.timings.request_ms()).max(0.0);
+        match redirect_request(&request, &response) {
+            // With redirects turned off the redirect itself is the response
+            Some(_) if max_redirects == 0 => break Ok(response),
+            Some(_) if redirects == max_redirects => {
+                break Err(format!("Stopped after {} redirects", max_redirects))
+            }
+            Some(next) => {
+                request = next;
+                redirects += 1;
+            }
+            None => break Ok(response),
+        }
+    };
+
+    if let Some(jar) = &mut jar {
+        jar.save(context);
+    }
+    result
+}
+
+/// Send a request with its auth applied, answering a Digest challenge if one comes back
+async fn send_authenticated(
+    client: &reqwest::Client,
+    request: &PreparedRequest,
+    context: &RequestContext,
+    mut jar: Option<&mut CookieJar>,
+    progress: Option<&dyn Fn(BodyProgress)>,
+) -> Result<HttpResponse, String> {
+    // OAuth 2.0 tokens are fetched or refreshed here so they are current when sent
+    let authorization = match &request.auth {
+        AuthConfig::OAuth2(config) if !has_authorization_header(request) => Some(
+            oauth2::access_token(config, context, &request.settings)
+                .await?
+                .authorization(),
+        ),
+        _ => None,
+    };
+    let sent_token = authorization.is_some();
+    let response = send(client, request, authorization, jar.as_deref_mut(), progress).await?;
+
+    // A rejected token is dropped from the cache so the next send fetches a new one
+    if let AuthConfig::OAuth2(config) = &request.auth {
+        if sent_token && response.status == 401 {
+            oauth2::forget_token(config, context);
+        }
+    }
+
+    // Digest auth needs the nonce from the server's challenge, so the first attempt
+    // goes out without credentials and is repeated with the answer
+    if let AuthConfig::Digest { username, password } = &request.auth {
+        if response.status == 401 {
+            if let Some(challenge) = digest::find_challenge(&response.headers) {
+                let url = reqwest::Url::parse(&request.url).map_err(|e| e.to_string())?;
+                let uri = match url.query() {
+                    Some(query) => format!("{}?{}", url.path(), query),
+                    None => url.path().to_string(),
+                };
+                let authorization =
+                    challenge.authorization(username, password, request.method.as_str(), &uri);
+                return send(client, request, Some(authorization), jar, progress).await;
+            }
+        }
+    }
+    Ok(response)
+}
+
+/// The request to send next when `response` is a redirect
+fn redirect_request(request: &PreparedRequest, response: &HttpResponse) -> Option<PreparedRequest> {
+    if !matches!(response.status, 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+    let (_, location) = response
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("location"))?;
+    let current = reqwest::Url::parse(&request.url).ok()?;
+    let next = current.join(location).ok()?;
+
+    let mut redirected = request.clone();
+    // 303, and 301 or 302 after a POST, continue as a GET without the body
+    if response.status == 303
+        || (matches!(response.status, 301 | 302) && request.method == HttpMethod::Post)
+    {
+        redirected.method = HttpMethod::Get;
+        redirected.body = RequestBody::None;
+        redirected
+            .headers
+            .retain(|(k, _)| !k.eq_ignore_ascii_case("content-type"));
+    }
+    // Credentials only go to the host they were entered for, unless told otherwise
+    let same_host = next.host_str() == current.host_str()
+        && next.port_or_known_default() == current.port_or_known_default();
+    if !same_host && !request.settings.keep_authorization() {
+        let api_key = match &request.auth {
+            AuthConfig::ApiKey {
+                key,
+                location: ApiKeyLocation::Header,
+                ..
+            } => key.as_str(),
+            _ => "",
+        };
+        redirected.headers.retain(|(k, _)| {
+            !k.eq_ignore_ascii_case("authorization")
+                && !k.eq_ignore_ascii_case("cookie")
+                && (api_key.is_empty() || !k.eq_ignore_ascii_case(api_key))
+        });
+        redirected.auth = AuthConfig::None;
+    }
+    redirected.url = next.to_string();
+    Some(redirected)
+}
+
+/// Whether the Headers tab sets `Authorization`, which then wins over the Auth tab
+fn has_authorization_header(request: &PreparedRequest) -> bool {
+    request
+        .headers
+        .iter()
+        .any(|(k, _)| k.eq_ignore_ascii_case("authorization"))
+}
+
+/// Send a request once, with an optional `Authorization` header added and cookies
+/// from `jar` unless the Headers tab sets its own `Cookie`
+async fn send(
+    client: &reqwest::Client,
+    request: &PreparedRequest,
+    authorization: Option<String>,
+    jar: Option<&mut CookieJar>,
+    progress: Option<&dyn Fn(BodyProgress)>,
+) -> Result<HttpResponse, String> {
+    let url = request.url.as_str();
+    let method = &request.method;
 
     let mut builder = match method {
         HttpMethod::Get => client.get(url),
@@ -15,25 +576,765 @@ pub async fn execute_request(
         HttpMethod::Delete => client.delete(url),
         HttpMethod::Patch => client.patch(url),
     };
-
     // Add headers
-    for (key, value) in headers {
+    for (key, value) in &request.headers {
         builder = builder.header(key.as_str(), value.as_str());
     }
+    if let Some(authorization) = authorization {
+        builder = builder.header("Authorization", authorization);
+    }
 
     // Add body for methods that support it
-    if !body.is_empty()
-        && matches!(
-            method,
-            HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch
-        )
+    builder = match request.effective_body() {
+        RequestBody::None => builder,
+        RequestBody::Raw(body) => builder.body(body.clone()),
+        RequestBody::Form(fields) => builder.form(fields),
+        RequestBody::Multipart(fields) => builder.multipart(build_multipart(fields).await?),
+    };
+
+    let mut http_request = builder.build().map_err(|e| e.to_string())?;
+    let url = http_request.url().clone();
+    if let Some(cookie) = jar.as_ref().and_then(|jar| jar.header(&url)) {
+        if !http_request.headers().contains_key(reqwest::header::COOKIE) {
+            let value = reqwest::header::HeaderValue::from_str(&cookie)
+                .map_err(|e| format!("Invalid cookie: {}", e))?;
+            http_request
+                .headers_mut()
+                .insert(reqwest::header::COOKIE, value);
+        }
+    }
+    // Signed last, over exactly what goes on the wire
+    if let AuthConfig::AwsSigV4(config) = &request.auth {
+        if !has_authorization_header(request) {
+            sigv4::sign(&mut http_request, config, chrono::Utc::now())?;
+        }
+    }
+
+    let sent_bytes = request_size(&http_request);
+    let tls = url.scheme() == "https";
+    // The timeout limits the wait for the response and then each wait for more of
+    // its body, so a slow stream or a large download isn't cut off while data flows
+    let timeout = request.settings.timeout();
+    let timeout_ms = timeout.unwrap_or_default().as_millis();
+    let started = Instant::now();
+    let (response, connection) = within(timeout, timing::measure(client.execute(http_request)))
+        .await
+        .ok_or_else(|| format!("Timed out after {} ms", timeout_ms))?;
+    let mut response = response.map_err(|e| send_error(e, &request.settings))?;
+    let headers_at = Instant::now();
+    let status = response.status();
+    let version = format!("{:?}", response.version());
+    let remote_addr = response.remote_addr();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    if let Some(jar) = jar {
+        jar.store_response(&url, &headers);
+    }
+    let encoding = body_encoding(&headers);
+    let mut file = match &request.download_to {
+        Some(path) => Some(
+            tokio::fs::File::create(path)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?,
+        ),
+        None => None,
+    };
+    let write_error = |e: std::io::Error| match &request.download_to {
+        Some(path) => format!("Failed to write {}: {}", path.display(), e),
+        None => e.to_string(),
+    };
+    let limit = request.settings.max_download_bytes();
+    let mut body = Vec::new();
+    let mut received: u64 = 0;
+    let mut truncated = false;
+    let mut reported: Option<Instant> = None;
+    while let Some(chunk) = within(timeout, response.chunk())
+        .await
+        .ok_or_else(|| format!("No data received for {} ms", timeout_ms))?
+        .map_err(|e| send_error(e, &request.settings))?
     {
-        builder = builder.body(body.to_string());
+        received += chunk.len() as u64;
+        match &mut file {
+            // The size limit guards memory, so a body going to disk is saved in full
+            Some(file) => file.write_all(&chunk).await.map_err(write_error)?,
+            // Past the limit the rest is dropped along with the connection
+            None => match limit {
+                Some(limit) if received > limit => {
+                    let keep = (limit as usize).saturating_sub(body.len());
+                    body.extend_from_slice(&chunk[..keep.min(chunk.len())]);
+                    truncated = true;
+                    break;
+                }
+                _ => body.extend_from_slice(&chunk),
+            },
+        }
+        if let Some(progress) = progress {
+            if reported.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL) {
+                progress(BodyProgress {
+                    body: &body,
+                    received,
+                    encoding,
+                });
+                reported = Some(Instant::now());
+            }
+        }
+    }
+    if let Some(file) = &mut file {
+        file.flush().await.map_err(write_error)?;
     }
+    let body_size = if file.is_some() {
+        received
+    } else {
+        body.len() as u64
+    };
+
+    // The connect happens inside `execute`, and the lookup inside the connect
+    let connect = connection
+        .connect
+        .map(|connect| connect.saturating_sub(connection.dns.unwrap_or_default()));
+    let waiting = (headers_at - started)
+        .saturating_sub(connection.dns.unwrap_or_default())
+        .saturating_sub(connect.unwrap_or_default());
+    let reason = status.canonical_reason().unwrap_or("").to_string();
+    let received_bytes = format!("{} {} {}\r\n", version, status.as_u16(), reason).len()
+        + headers_size(headers.iter().map(|(k, v)| (k.as_str(), v.as_bytes())))
+        + body_size as usize;
 
-    let response = builder.send().await.map_err(|e| e.to_string())?;
-    let status = response.status().as_u16();
-    let text = response.text().await.map_err(|e| e.to_string())?;
+    Ok(HttpResponse {
+        status: status.as_u16(),
+        reason,
+        version,
+        headers,
+        remote_addr,
+        body,
+        body_file: request.download_to.clone(),
+        body_size,
+        truncated,
+        unverified_tls: false,
+        timings: Timings {
+            prior_ms: 0.0,
+            dns_ms: connection.dns.map(timing::millis),
+            connect_ms: connect.map(timing::millis),
+            tls: tls && connect.is_some(),
+            waiting_ms: timing::millis(waiting),
+            download_ms: timing::millis(headers_at.elapsed()),
+            sent_bytes: sent_bytes as u64,
+            received_bytes: received_bytes as u64,
+        },
+    })
+}
 
-    Ok((status, text))
+/// Bytes of the request line, headers and body, as far as they are known before
+/// sending; headers reqwest adds itself, such as `Host`, aren't counted
+fn request_size(request: &reqwest::Request) -> usize {
+    let url = request.url();
+    let target = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let body = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(|bytes| bytes.len())
+        .or_else(|| {
+            // Multipart bodies are streamed, but their length is set up front
+            request
+                .headers()
+                .get(reqwest::header::CONTENT_LENGTH)?
+                .to_str()
+                .ok()?
+                .parse()
+                .ok()
+        })
+        .unwrap_or(0);
+    format!("{} {} HTTP/1.1\r\n", request.method(), target).len()
+        + headers_size(
+            request
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_bytes())),
+        )
+        + body
+}
+
+/// Bytes of a header block, including the blank line that ends it
+fn headers_size<'a>(headers: impl Iterator<Item = (&'a str, &'a [u8])>) -> usize {
+    headers
+        .map(|(name, value)| name.len() + value.len() + 4)
+        .sum::<usize>()
+        + 2
+}
+
+/// `future`'s output, or `None` once `limit` passes without it
+async fn within<T>(limit: Option<Duration>, future: impl Future<Output = T>) -> Option<T> {
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// Message for a failed send, naming the limit that was hit on a timeout
+fn send_error(error: reqwest::Error, settings: &RequestSettings) -> String {
+    match settings.connect_timeout() {
+        Some(limit) if error.is_timeout() && error.is_connect() => {
+            format!("Could not connect within {} ms", limit.as_millis())
+        }
+        // The cause says why a connection failed, such as an untrusted certificate
+        _ if error.is_connect() => {
+            let mut cause: &dyn std::error::Error = &error;
+            while let Some(source) = cause.source() {
+                cause = source;
+            }
+            format!("{}: {}", error, cause)
+        }
+        _ => error.to_string(),
+    }
+}
+
+async fn build_multipart(fields: &[(String, String)]) -> Result<reqwest::multipart::Form, String> {
+    let mut form = reqwest::multipart::Form::new();
+    for (key, value) in fields {
+        form = match value.strip_prefix('@') {
+            Some(path) => {
+                let data = tokio::fs::read(path)
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+                let file_name = std::path::Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("file")
+                    .to_string();
+                form.part(
+                    key.clone(),
+                    reqwest::multipart::Part::bytes(data).file_name(file_name),
+                )
+            }
+            None => form.text(key.clone(), value.clone()),
+        };
+    }
+    Ok(form)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OAuth2Config, SavedRequest};
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+    use std::io::{Read, Write};
+    use std::path::Path;
+
+    /// A CA, a server certificate for localhost and a client certificate it signed,
+    /// written as PEM files to `dir`
+    fn write_certificates(dir: &Path) {
+        use rcgen::{
+            BasicConstraints, CertificateParams, DistinguishedName, DnType,
+            ExtendedKeyUsagePurpose, IsCa, KeyPair,
+        };
+        // Distinct subjects, or a certificate would look self-signed
+        let subject = |name: &str| {
+            let mut subject = DistinguishedName::new();
+            subject.push(DnType::CommonName, name);
+            subject
+        };
+
+        let ca_key = KeyPair::generate().unwrap();
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params.distinguished_name = subject("Test CA");
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+
+        let server_key = KeyPair::generate().unwrap();
+        let mut server_params = CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+        server_params.distinguished_name = subject("localhost");
+        let server = server_params.signed_by(&server_key, &ca, &ca_key).unwrap();
+
+        let client_key = KeyPair::generate().unwrap();
+        let mut client_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        client_params.distinguished_name = subject("Test Client");
+        client_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        let client = client_params.signed_by(&client_key, &ca, &ca_key).unwrap();
+
+        let mut pkcs12 = p12_keystore::KeyStore::new();
+        pkcs12.add_entry(
+            "client",
+            p12_keystore::KeyStoreEntry::PrivateKeyChain(p12_keystore::PrivateKeyChain::new(
+                client_key.serialize_der(),
+                b"client",
+                [p12_keystore::Certificate::from_der(client.der()).unwrap()],
+            )),
+        );
+
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("client.p12"),
+            pkcs12.writer("secret").write().unwrap(),
+        )
+        .unwrap();
+        for (name, pem) in [
+            ("ca.pem", ca.pem()),
+            ("server.pem", server.pem()),
+            ("server-key.pem", server_key.serialize_pem()),
+            ("client.pem", client.pem()),
+            ("client-key.pem", client_key.serialize_pem()),
+        ] {
+            std::fs::write(dir.join(name), pem).unwrap();
+        }
+    }
+
+    /// HTTPS server on localhost that requires a client certificate from the CA in
+    /// `dir` and answers every request with an OAuth token
+    fn start_tls_server(dir: &Path) -> u16 {
+        let certificate = |name: &str| CertificateDer::from_pem_file(dir.join(name)).unwrap();
+        let provider = std::sync::Arc::new(rustls::crypto::ring::default_provider());
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(certificate("ca.pem")).unwrap();
+        let verifier = rustls::server::WebPkiClientVerifier::builder_with_provider(
+            std::sync::Arc::new(roots),
+            provider.clone(),
+        )
+        .build()
+        .unwrap();
+        let config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(
+                vec![certificate("server.pem")],
+                PrivateKeyDer::from_pem_file(dir.join("server-key.pem")).unwrap(),
+            )
+            .unwrap();
+        let config = std::sync::Arc::new(config);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let config = config.clone();
+                std::thread::spawn(move || {
+                    let Ok(connection) = rustls::ServerConnection::new(config) else {
+                        return;
+                    };
+                    // The handshake happens on the first read
+                    let mut stream = rustls::StreamOwned::new(connection, stream);
+                    let mut head = Vec::new();
+                    let mut buffer = [0u8; 1024];
+                    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut buffer) {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => head.extend_from_slice(&buffer[..n]),
+                        }
+                    }
+                    let body = r#"{"access_token":"tls-token","token_type":"bearer"}"#;
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    stream.conn.send_close_notify();
+                    let _ = stream.flush();
+                });
+            }
+        });
+        port
+    }
+
+    fn tls_config(host: &str, dir: &Path) -> TlsConfig {
+        let path = |name: &str| dir.join(name).display().to_string();
+        TlsConfig {
+            host: host.to_string(),
+            ca_files: vec![path("ca.pem")],
+            client_cert: path("client.pem"),
+            client_key: path("client-key.pem"),
+            ..Default::default()
+        }
+    }
+
+    async fn get(url: &str, tls: Vec<TlsConfig>) -> Result<HttpResponse, String> {
+        let mut saved = SavedRequest::new("tls", &crate::types::HttpMethod::Get, url);
+        saved.skip_cookie_jar = true;
+        let request = PreparedRequest::from_saved(&saved, &HashMap::new());
+        let context = RequestContext {
+            proxy: ProxyConfig {
+                mode: crate::config::ProxyMode::Direct,
+                ..Default::default()
+            },
+            tls,
+            ..Default::default()
+        };
+        execute_request(&request, &context, None).await
+    }
+
+    /// Plain HTTP server on 127.0.0.1 that answers each request with `respond` and
+    /// passes the request on, header names lowercased, to the receiver
+    fn start_server(
+        respond: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> (u16, std::sync::mpsc::Receiver<String>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let respond = std::sync::Arc::new(respond);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let respond = respond.clone();
+                let sender = sender.clone();
+                std::thread::spawn(move || {
+                    let mut request = Vec::new();
+                    let mut buffer = [0u8; 1024];
+                    let head_end = loop {
+                        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break end + 4;
+                        }
+                        match stream.read(&mut buffer) {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buffer[..n]),
+                        }
+                    };
+                    let head = String::from_utf8_lossy(&request[..head_end]).to_lowercase();
+                    let length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .and_then(|length| length.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    while request.len() < head_end + length {
+                        match stream.read(&mut buffer) {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buffer[..n]),
+                        }
+                    }
+                    let body = String::from_utf8_lossy(&request[head_end..]);
+                    let request = format!("{}{}", head, body);
+                    let _ = stream.write_all(respond(&request).as_bytes());
+                    let _ = sender.send(request);
+                });
+            }
+        });
+        (port, receiver)
+    }
+
+    fn redirect_to(status: &str, location: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            status, location
+        )
+    }
+
+    fn ok(_: &str) -> String {
+        "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok".to_string()
+    }
+
+    /// A request sent without the cookie jar, so tests leave the stored cookies alone
+    fn saved(method: HttpMethod, url: String) -> SavedRequest {
+        let mut saved = SavedRequest::new("test", &method, url);
+        saved.skip_cookie_jar = true;
+        saved
+    }
+
+    async fn execute(saved: &SavedRequest) -> Result<HttpResponse, String> {
+        let request = PreparedRequest::from_saved(saved, &HashMap::new());
+        let context = RequestContext {
+            proxy: ProxyConfig {
+                mode: crate::config::ProxyMode::Direct,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        execute_request(&request, &context, None).await
+    }
+
+    #[tokio::test]
+    async fn follows_redirects_with_the_method_of_the_status() {
+        let (port, requests) = start_server(|request| match request.split(' ').nth(1) {
+            Some("/see-other") => redirect_to("303 See Other", "/done"),
+            Some("/found") => redirect_to("302 Found", "/done"),
+            Some("/temporary") => redirect_to("307 Temporary Redirect", "/done"),
+            _ => ok(request),
+        });
+
+        for (method, path, follow_up) in [
+            (HttpMethod::Put, "/see-other", "get /done "),
+            (HttpMethod::Post, "/found", "get /done "),
+            (HttpMethod::Post, "/temporary", "post /done "),
+        ] {
+            let mut request = saved(method, format!("http://127.0.0.1:{}{}", port, path));
+            request.body_mode = BodyMode::Json;
+            request.body = r#"{"a":1}"#.to_string();
+            assert_eq!(execute(&request).await.unwrap().status, 200);
+
+            assert!(requests.recv().unwrap().ends_with(r#"{"a":1}"#), "{}", path);
+            let redirected = requests.recv().unwrap();
+            assert!(redirected.starts_with(follow_up), "{}", redirected);
+            // A GET goes without the body and its type
+            let keeps_body = follow_up.starts_with("post");
+            assert_eq!(redirected.ends_with(r#"{"a":1}"#), keeps_body, "{}", path);
+            assert_eq!(redirected.contains("content-type"), keeps_body, "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn strips_credentials_on_redirects_to_another_host() {
+        let (other, other_requests) = start_server(ok);
+        let (port, requests) = start_server(move |request| match request.split(' ').nth(1) {
+            Some("/same") => redirect_to("302 Found", "/done"),
+            Some("/other") => redirect_to("302 Found", &format!("http://127.0.0.1:{}/", other)),
+            _ => ok(request),
+        });
+
+        let mut request = saved(HttpMethod::Get, format!("http://127.0.0.1:{}/same", port));
+        request.auth = AuthConfig::Bearer {
+            token: "secret".to_string(),
+        };
+        request.headers = vec![SavedKeyValue::new("Cookie", "session=1")];
+        let has_credentials =
+            |sent: &str| sent.contains("authorization: bearer secret") && sent.contains("cookie:");
+
+        // Kept on the same host
+        execute(&request).await.unwrap();
+        assert!(has_credentials(&requests.recv().unwrap()));
+        assert!(has_credentials(&requests.recv().unwrap()));
+
+        // Dropped for another port
+        request.url = format!("http://127.0.0.1:{}/other", port);
+        execute(&request).await.unwrap();
+        assert!(has_credentials(&requests.recv().unwrap()));
+        let sent = other_requests.recv().unwrap();
+        assert!(
+            !sent.contains("authorization") && !sent.contains("cookie"),
+            "{}",
+            sent
+        );
+
+        // Unless told to keep them
+        request.settings.keep_authorization = Some(true);
+        execute(&request).await.unwrap();
+        requests.recv().unwrap();
+        assert!(has_credentials(&other_requests.recv().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn drops_the_api_key_header_on_redirects_to_another_host() {
+        let (other, other_requests) = start_server(ok);
+        let (port, requests) = start_server(move |_| {
+            redirect_to("302 Found", &format!("http://127.0.0.1:{}/", other))
+        });
+
+        let mut request = saved(HttpMethod::Get, format!("http://127.0.0.1:{}/", port));
+        request.auth = AuthConfig::ApiKey {
+            key: "X-Api-Key".to_string(),
+            value: "secret".to_string(),
+            location: ApiKeyLocation::Header,
+        };
+        let response = execute(&request).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert!(requests.recv().unwrap().contains("x-api-key: secret"));
+        assert!(!other_requests.recv().unwrap().contains("x-api-key"));
+    }
+
+    #[tokio::test]
+    async fn limits_the_redirects_followed() {
+        let (port, requests) = start_server(|_| redirect_to("302 Found", "/again"));
+        let mut request = saved(HttpMethod::Get, format!("http://127.0.0.1:{}/", port));
+
+        // 0 returns the redirect itself
+        request.settings.max_redirects = Some(0);
+        let response = execute(&request).await.unwrap();
+        assert_eq!(response.status, 302);
+        requests.recv().unwrap();
+        assert!(requests.try_recv().is_err());
+
+        request.settings.max_redirects = Some(2);
+        assert_eq!(
+            execute(&request).await.unwrap_err(),
+            "Stopped after 2 redirects"
+        );
+        assert_eq!(requests.iter().take(3).count(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_timeouts() {
+        let (port, _) = start_server(|request| {
+            std::thread::sleep(std::time::Duration::from_secs(2));
+            ok(request)
+        });
+        let mut request = saved(HttpMethod::Get, format!("http://127.0.0.1:{}/", port));
+        request.settings.timeout_ms = Some(200);
+        assert_eq!(
+            execute(&request).await.unwrap_err(),
+            "Timed out after 200 ms"
+        );
+
+        // The server never answers the TLS handshake, which is part of connecting
+        request.url = format!("https://127.0.0.1:{}/", port);
+        request.settings.connect_timeout_ms = Some(200);
+        request.settings.timeout_ms = Some(0);
+        assert_eq!(
+            execute(&request).await.unwrap_err(),
+            "Could not connect within 200 ms"
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_bodies_for_as_long_as_data_keeps_coming() {
+        // Five bytes a tenth of a second apart, or one and then nothing for a second
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut buffer = [0u8; 1024];
+                    let Ok(n) = stream.read(&mut buffer) else {
+                        return;
+                    };
+                    let pause = if buffer[..n].starts_with(b"GET /stall ") {
+                        std::time::Duration::from_secs(1)
+                    } else {
+                        std::time::Duration::from_millis(100)
+                    };
+                    let _ = stream.write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\n",
+                    );
+                    for byte in b"abcde" {
+                        let _ = stream.write_all(&[*byte]);
+                        std::thread::sleep(pause);
+                    }
+                });
+            }
+        });
+
+        let mut request = saved(HttpMethod::Get, format!("http://127.0.0.1:{}/", port));
+        request.settings.timeout_ms = Some(300);
+        assert_eq!(execute(&request).await.unwrap().body, b"abcde");
+
+        request.url = format!("http://127.0.0.1:{}/stall", port);
+        assert_eq!(
+            execute(&request).await.unwrap_err(),
+            "No data received for 300 ms"
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_tls_settings_of_the_matching_host() {
+        let dir = std::env::temp_dir().join(format!("api-client-tls-{}", std::process::id()));
+        write_certificates(&dir);
+        let port = start_tls_server(&dir);
+        let url = format!("https://localhost:{}/", port);
+
+        // The generated CA is not trusted by default
+        assert!(get(&url, Vec::new()).await.is_err());
+
+        // Trusted with the CA and client certificate configured for the host
+        let response = get(&url, vec![tls_config("localhost", &dir)])
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert!(!response.unverified_tls);
+        // A new connection is timed, handshake included
+        assert!(response
+            .timings
+            .connect_ms
+            .is_some_and(|connect| connect > 0.0));
+        assert!(response.timings.tls);
+
+        // The client certificate may also come as a PKCS#12 archive
+        let pkcs12 = TlsConfig {
+            client_cert: dir.join("client.p12").display().to_string(),
+            client_key: String::new(),
+            passphrase: "secret".to_string(),
+            ..tls_config("localhost", &dir)
+        };
+        assert_eq!(get(&url, vec![pkcs12]).await.unwrap().status, 200);
+
+        // Settings for other hosts are not applied
+        for host in ["*.localhost", "example.com", ""] {
+            assert!(
+                get(&url, vec![tls_config(host, &dir)]).await.is_err(),
+                "{}",
+                host
+            );
+        }
+
+        // The server insists on a client certificate
+        let without_client = TlsConfig {
+            client_cert: String::new(),
+            client_key: String::new(),
+            ..tls_config("LOCALHOST", &dir)
+        };
+        assert!(get(&url, vec![without_client]).await.is_err());
+
+        // Turning verification off accepts the unknown CA and marks the response
+        let unverified = TlsConfig {
+            ca_files: Vec::new(),
+            skip_verification: true,
+            ..tls_config("localhost", &dir)
+        };
+        let response = get(&url, vec![unverified]).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert!(response.unverified_tls);
+
+        // OAuth token requests use the same settings
+        let config = OAuth2Config {
+            token_url: format!("{}token", url),
+            client_id: "app".to_string(),
+            ..Default::default()
+        };
+        let context = RequestContext {
+            folder: Some(dir.clone()),
+            tls: vec![tls_config("localhost", &dir)],
+            store_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+        let token = oauth2::access_token(&config, &context, &RequestSettings::default())
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "tls-token");
+        oauth2::forget_token(&config, &context);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn decodes_bodies_with_their_charset() {
+        let decode = |content_type: &str, body: &[u8]| {
+            let headers = [("Content-Type".to_string(), content_type.to_string())];
+            BodyProgress {
+                body,
+                received: body.len() as u64,
+                encoding: body_encoding(&headers),
+            }
+            .text()
+        };
+        let latin1 = b"caf\xe9";
+        assert_eq!(decode("text/plain; charset=ISO-8859-1", latin1), "café");
+        assert_eq!(decode("text/html;Charset=\"windows-1252\"", latin1), "café");
+        assert_eq!(
+            decode("text/plain; charset=shift_jis", b"\x93\xfa\x96\x7b"),
+            "日本"
+        );
+        // UTF-8 without a charset, or with one that isn't known
+        assert_eq!(decode("text/plain", "café".as_bytes()), "café");
+        assert_eq!(
+            decode("text/plain; charset=nonsense", "café".as_bytes()),
+            "café"
+        );
+        assert_eq!(decode("text/plain", latin1), "caf\u{fffd}");
+    }
+
+    #[test]
+    fn encodes_utf8_bytes() {
+        assert_eq!(urlencoding("a b&c=d"), "a+b%26c%3Dd");
+        assert_eq!(urlencoding("café"), "caf%C3%A9");
+        assert_eq!(urlencoding("€ 😀"), "%E2%82%AC+%F0%9F%98%80");
+        for text in ["café", "€ 😀", "-_.~/?#"] {
+            assert_eq!(urldecode(&urlencoding(text)), text);
+        }
+    }
 }
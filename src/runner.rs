@@ -0,0 +1,310 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::assertions;
+use crate::config::AppConfig;
+use crate::environment::EnvironmentStore;
+use crate::fs;
+use crate::request::{self, PreparedRequest, RequestContext};
+use crate::settings::FolderSettings;
+
+const USAGE: &str = "Usage: api-client run <folder> [--request <name>] [--env <name>]
+
+Runs every saved request in <folder> without opening the window and exits
+with a non-zero status if any request fails.
+
+Options:
+  -r, --request <name>  Run only the request with this name or file name
+  -e, --env <name>      Resolve {{variables}} from this environment
+  -h, --help            Show this help";
+
+/// Command line options of the `run` subcommand
+struct RunOptions {
+    folder: PathBuf,
+    request: Option<String>,
+    environment: Option<String>,
+}
+
+impl RunOptions {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut folder = None;
+        let mut request = None;
+        let mut environment = None;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-r" | "--request" => {
+                    request = Some(iter.next().ok_or("--request needs a value")?.clone());
+                }
+                "-e" | "--env" => {
+                    environment = Some(iter.next().ok_or("--env needs a value")?.clone());
+                }
+                flag if flag.starts_with('-') => {
+                    return Err(format!("Unknown option: {}", flag));
+                }
+                path if folder.is_none() => folder = Some(PathBuf::from(path)),
+                extra => return Err(format!("Unexpected argument: {}", extra)),
+            }
+        }
+
+        Ok(Self {
+            folder: folder.ok_or("Missing <folder> argument")?,
+            request,
+            environment,
+        })
+    }
+}
+
+/// Entry point of `api-client run`; returns the process exit code
+pub fn main(args: &[String]) -> i32 {
+    run_with_output(args, &mut std::io::stdout())
+}
+
+/// `main`, writing the results to `out` instead of stdout
+fn run_with_output(args: &[String], out: &mut dyn Write) -> i32 {
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        let _ = writeln!(out, "{}", USAGE);
+        return 0;
+    }
+
+    let options = match RunOptions::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("error: failed to start runtime: {}", e);
+            return 2;
+        }
+    };
+
+    runtime.block_on(run(&options, out))
+}
+
+async fn run(options: &RunOptions, out: &mut dyn Write) -> i32 {
+    if !options.folder.is_dir() {
+        eprintln!("error: {} is not a folder", options.folder.display());
+        return 2;
+    }
+
+    let mut environments = EnvironmentStore::load(&options.folder);
+    if let Some(name) = &options.environment {
+        if !environments.environments.iter().any(|e| &e.name == name) {
+            eprintln!("error: unknown environment '{}'", name);
+            return 2;
+        }
+        environments.active = Some(name.clone());
+    }
+    let vars = environments.variables();
+    let config = AppConfig::load();
+    let context = RequestContext {
+        folder: Some(options.folder.clone()),
+        environment: environments.active.clone(),
+        defaults: FolderSettings::load(&options.folder).request_defaults,
+        proxy: config.proxy_for(environments.active.as_deref()),
+        tls: config.tls,
+        ..Default::default()
+    };
+
+    let mut entries = fs::scan_folder(&options.folder);
+    if let Some(name) = &options.request {
+        entries.retain(|entry| {
+            &entry.name == name
+                || fs::load_request(&entry.path).is_some_and(|request| &request.name == name)
+        });
+        if entries.is_empty() {
+            eprintln!("error: no request named '{}'", name);
+            return 2;
+        }
+    }
+
+    let mut passed = 0;
+    let mut failed = 0;
+
+    for entry in &entries {
+        let Some(saved) = fs::load_request(&entry.path) else {
+            failed += 1;
+            let _ = writeln!(
+                out,
+                "FAIL  {}  could not parse {}",
+                entry.name,
+                entry.path.display()
+            );
+            continue;
+        };
+
+        let prepared = PreparedRequest::from_saved(&saved, &vars);
+        let start = Instant::now();
+        let result = request::execute_request(&prepared, &context, None).await;
+        let elapsed = start.elapsed().as_millis();
+
+        match result {
+            Ok(response) => {
+                // Requests with assertions are judged by them alone, so an expected 404 can pass
+                let results = assertions::evaluate(&saved.assertions, &response, elapsed);
+                let ok = if saved.assertions.is_empty() {
+                    response.status < 400
+                } else {
+                    results.iter().all(|r| r.passed)
+                };
+                if ok {
+                    passed += 1;
+                } else {
+                    failed += 1;
+                }
+                let _ = writeln!(
+                    out,
+                    "{}  {} {}  {} {}  ({} ms)",
+                    if ok { "PASS" } else { "FAIL" },
+                    prepared.method.as_str(),
+                    saved.name,
+                    response.status,
+                    response.reason,
+                    elapsed
+                );
+                for result in results.iter().filter(|r| !r.passed) {
+                    let _ = writeln!(out, "        x {}: {}", result.description, result.message);
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                let _ = writeln!(
+                    out,
+                    "FAIL  {} {}  {}  ({} ms)",
+                    prepared.method.as_str(),
+                    saved.name,
+                    e,
+                    elapsed
+                );
+            }
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{} passed, {} failed", passed, failed);
+
+    if failed > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::{Environment, Variable};
+    use crate::types::{HttpMethod, RequestFormat, SavedRequest};
+    use std::path::Path;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve `/ok` with 200 and everything else with 500 on a background thread
+    fn start_server() -> u16 {
+        let (port_tx, port_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                port_tx.send(listener.local_addr().unwrap().port()).unwrap();
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    tokio::spawn(async move {
+                        let mut request = Vec::new();
+                        let mut buf = [0u8; 1024];
+                        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            match socket.read(&mut buf).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => request.extend_from_slice(&buf[..n]),
+                            }
+                        }
+                        let status = if request.starts_with(b"GET /ok ") {
+                            "200 OK"
+                        } else {
+                            "500 Internal Server Error"
+                        };
+                        let response = format!(
+                            "HTTP/1.1 {}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nhi",
+                            status
+                        );
+                        let _ = socket.write_all(response.as_bytes()).await;
+                    });
+                }
+            });
+        });
+        port_rx.recv().unwrap()
+    }
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("api-client-runner-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    fn save(folder: &Path, name: &str, path: &str) {
+        let mut request =
+            SavedRequest::new(name, &HttpMethod::Get, format!("{{{{base}}}}{}", path));
+        request.skip_cookie_jar = true;
+        let file = folder.join(fs::request_file_name(name, RequestFormat::Json));
+        fs::save_request(&file, &request).unwrap();
+    }
+
+    fn run_args(args: &[&str]) -> (i32, String) {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let mut out = Vec::new();
+        let code = run_with_output(&args, &mut out);
+        (code, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn runs_saved_requests() {
+        let port = start_server();
+        let folder = temp_folder("run");
+        save(&folder, "healthy", "/ok");
+        save(&folder, "broken", "/error");
+        EnvironmentStore {
+            active: None,
+            environments: vec![Environment {
+                name: "local".to_string(),
+                variables: vec![Variable {
+                    key: "base".to_string(),
+                    value: format!("http://127.0.0.1:{}", port),
+                    enabled: true,
+                }],
+            }],
+        }
+        .save(&folder);
+        let dir = folder.to_str().unwrap();
+
+        let (code, out) = run_args(&[dir, "--env", "local", "--request", "healthy"]);
+        assert_eq!(code, 0, "{}", out);
+        assert!(out.contains("PASS  GET healthy  200 OK"), "{}", out);
+        assert!(out.contains("1 passed, 0 failed"), "{}", out);
+
+        let (code, out) = run_args(&[dir, "-e", "local"]);
+        assert_eq!(code, 1, "{}", out);
+        assert!(out.contains("PASS  GET healthy  200 OK"), "{}", out);
+        assert!(
+            out.contains("FAIL  GET broken  500 Internal Server Error"),
+            "{}",
+            out
+        );
+        assert!(out.contains("1 passed, 1 failed"), "{}", out);
+
+        assert_eq!(run_args(&[dir, "--env", "staging"]).0, 2);
+        assert_eq!(run_args(&[dir, "--env", "local", "-r", "missing"]).0, 2);
+        assert_eq!(run_args(&[dir, "--verbose"]).0, 2);
+        assert_eq!(run_args(&[dir, "--env"]).0, 2);
+        assert_eq!(run_args(&[]).0, 2);
+
+        let _ = std::fs::remove_dir_all(folder);
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::fs;
+use crate::types::{RequestFormat, RequestSettings};
+
+/// Preferences stored per request folder, shared with everyone using the folder
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FolderSettings {
+    /// Format new request files are written in
+    #[serde(default)]
+    pub request_format: RequestFormat,
+    /// Connection settings used where a request doesn't set its own
+    #[serde(default, skip_serializing_if = "RequestSettings::is_default")]
+    pub request_defaults: RequestSettings,
+}
+
+impl FolderSettings {
+    pub fn path(folder: &Path) -> PathBuf {
+        fs::data_dir(folder).join("settings.json")
+    }
+
+    pub fn load(folder: &Path) -> Self {
+        if let Ok(content) = std::fs::read_to_string(Self::path(folder)) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn save(&self, folder: &Path) {
+        let path = Self::path(folder);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::future::Future;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// How long each phase of a response took, in milliseconds, and how much was sent
+/// and received. Connection phases are `None` when a pooled connection was reused.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Timings {
+    /// Client setup, redirects, token fetches and auth challenges before the request
+    /// that got this response
+    #[serde(default)]
+    pub prior_ms: f64,
+    #[serde(default)]
+    pub dns_ms: Option<f64>,
+    /// TCP connect, including any proxy tunnel; for HTTPS also the TLS handshake
+    #[serde(default)]
+    pub connect_ms: Option<f64>,
+    /// The connection was secured with TLS, so `connect_ms` includes the handshake
+    #[serde(default)]
+    pub tls: bool,
+    /// Sending the request until the response headers arrived
+    #[serde(default)]
+    pub waiting_ms: f64,
+    /// Reading the response body
+    #[serde(default)]
+    pub download_ms: f64,
+    /// Request line, headers and body
+    #[serde(default)]
+    pub sent_bytes: u64,
+    /// Status line, headers and body after decompression
+    #[serde(default)]
+    pub received_bytes: u64,
+}
+
+impl Timings {
+    /// Phases from the start of the final request to the end of its body
+    pub fn request_ms(&self) -> f64 {
+        self.dns_ms.unwrap_or(0.0)
+            + self.connect_ms.unwrap_or(0.0)
+            + self.waiting_ms
+            + self.download_ms
+    }
+
+    /// Every phase with its label and duration, in order
+    pub fn phases(&self) -> Vec<(&'static str, f64)> {
+        let mut phases = Vec::new();
+        if self.prior_ms > 0.0 {
+            phases.push(("Before Request", self.prior_ms));
+        }
+        if let Some(dns) = self.dns_ms {
+            phases.push(("DNS Lookup", dns));
+        }
+        if let Some(connect) = self.connect_ms {
+            let label = if self.tls {
+                "TCP + TLS Handshake"
+            } else {
+                "TCP Connect"
+            };
+            phases.push((label, connect));
+        }
+        phases.push(("Waiting (TTFB)", self.waiting_ms));
+        phases.push(("Download", self.download_ms));
+        phases
+    }
+}
+
+pub fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Connection phases seen while one request was sent
+#[derive(Debug, Default)]
+pub struct ConnectionTimings {
+    pub dns: Option<Duration>,
+    /// Includes `dns`, which happens inside the connect
+    pub connect: Option<Duration>,
+}
+
+tokio::task_local! {
+    static CONNECTION: Arc<Mutex<ConnectionTimings>>;
+}
+
+/// Run `future`, collecting the DNS lookup and connect it caused. Phases are missed
+/// when a pooled connection is reused, which is what should be reported.
+pub async fn measure<F: Future>(future: F) -> (F::Output, ConnectionTimings) {
+    let slot = Arc::new(Mutex::new(ConnectionTimings::default()));
+    let output = CONNECTION.scope(slot.clone(), future).await;
+    let timings = std::mem::take(&mut *slot.lock().unwrap_or_else(|e| e.into_inner()));
+    (output, timings)
+}
+
+/// Add `elapsed` to a phase of the request being measured on this task, if any
+fn record(slot: Option<Arc<Mutex<ConnectionTimings>>>, elapsed: Duration, dns: bool) {
+    if let Some(slot) = slot {
+        let mut timings = slot.lock().unwrap_or_else(|e| e.into_inner());
+        let phase = if dns {
+            &mut timings.dns
+        } else {
+            &mut timings.connect
+        };
+        *phase = Some(phase.unwrap_or_default() + elapsed);
+    }
+}
+
+/// The system resolver, timed
+#[derive(Debug, Default)]
+pub struct TimedResolver;
+
+impl reqwest::dns::Resolve for TimedResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        // Taken now, while the connect is still polled by the task that sends
+        let slot = CONNECTION.try_with(Arc::clone).ok();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let started = Instant::now();
+            let addrs = tokio::task::spawn_blocking(move || (host.as_str(), 0).to_socket_addrs())
+                .await??
+                .collect::<Vec<SocketAddr>>();
+            record(slot, started.elapsed(), true);
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Times every new connection a client makes
+#[derive(Clone, Debug, Default)]
+pub struct TimingLayer;
+
+impl<S> tower::Layer<S> for TimingLayer {
+    type Service = TimedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnector { inner }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TimedConnector<S> {
+    inner: S,
+}
+
+impl<S, R> tower::Service<R> for TimedConnector<S>
+where
+    S: tower::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let slot = CONNECTION.try_with(Arc::clone).ok();
+        let started = Instant::now();
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let result = connecting.await;
+            if result.is_ok() {
+                record(slot, started.elapsed(), false);
+            }
+            result
+        })
+    }
+}
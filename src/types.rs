@@ -1,7 +1,11 @@
 use gpui::*;
 use gpui_component::input::InputState;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+
+use crate::assertions::Assertion;
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// HTTP Methods supported by the client
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -14,6 +18,18 @@ pub enum HttpMethod {
 }
 
 impl HttpMethod {
+    /// Parse a method name case-insensitively
+    pub fn parse(method: &str) -> Option<HttpMethod> {
+        match method.to_uppercase().as_str() {
+            "GET" => Some(HttpMethod::Get),
+            "POST" => Some(HttpMethod::Post),
+            "PUT" => Some(HttpMethod::Put),
+            "DELETE" => Some(HttpMethod::Delete),
+            "PATCH" => Some(HttpMethod::Patch),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             HttpMethod::Get => "GET",
@@ -51,6 +67,36 @@ pub enum RequestTab {
     Params,
     Headers,
     Body,
+    Auth,
+    Settings,
+}
+
+/// What the Settings tab edits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsScope {
+    Request,
+    FolderDefaults,
+    Proxy,
+    Tls,
+}
+
+impl SettingsScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingsScope::Request => "This Request",
+            SettingsScope::FolderDefaults => "Folder Defaults",
+            SettingsScope::Proxy => "Proxy",
+            SettingsScope::Tls => "TLS",
+        }
+    }
+}
+
+/// Response tabs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResponseTab {
+    Body,
+    Headers,
+    Tests,
 }
 
 /// Key-Value pair for params and headers
@@ -61,16 +107,531 @@ pub struct KeyValuePair {
     pub enabled: bool,
 }
 
+/// How the request body is encoded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BodyMode {
+    #[default]
+    None,
+    Json,
+    Text,
+    Xml,
+    FormUrlEncoded,
+    Multipart,
+}
+
+impl BodyMode {
+    pub const ALL: [BodyMode; 6] = [
+        BodyMode::None,
+        BodyMode::Json,
+        BodyMode::Text,
+        BodyMode::Xml,
+        BodyMode::FormUrlEncoded,
+        BodyMode::Multipart,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BodyMode::None => "None",
+            BodyMode::Json => "JSON",
+            BodyMode::Text => "Text",
+            BodyMode::Xml => "XML",
+            BodyMode::FormUrlEncoded => "Form URL Encoded",
+            BodyMode::Multipart => "Multipart Form",
+        }
+    }
+
+    /// Content-Type sent when the request does not set one explicitly
+    pub fn content_type(&self) -> Option<&'static str> {
+        match self {
+            BodyMode::Json => Some("application/json"),
+            BodyMode::Text => Some("text/plain"),
+            BodyMode::Xml => Some("application/xml"),
+            // reqwest sets these itself, including the multipart boundary
+            BodyMode::None | BodyMode::FormUrlEncoded | BodyMode::Multipart => None,
+        }
+    }
+
+    /// Whether the body is edited as key-value fields instead of raw text
+    pub fn is_form(&self) -> bool {
+        matches!(self, BodyMode::FormUrlEncoded | BodyMode::Multipart)
+    }
+}
+
+/// Key-value entry as stored in a saved request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedKeyValue {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+impl SavedKeyValue {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            enabled: true,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Current version of the saved request file format
+pub const SAVED_REQUEST_VERSION: u32 = 2;
+
+/// Files written before the format was versioned have no `version` field
+fn legacy_version() -> u32 {
+    1
+}
+
 /// Saved request file format
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SavedRequest {
+    #[serde(default = "legacy_version")]
+    pub version: u32,
     pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
     pub method: String,
     pub url: String,
     #[serde(default)]
-    pub headers: std::collections::HashMap<String, String>,
+    pub params: Vec<SavedKeyValue>,
+    #[serde(default, deserialize_with = "deserialize_key_values")]
+    pub headers: Vec<SavedKeyValue>,
+    #[serde(default)]
+    pub body_mode: BodyMode,
+    /// Fields of url-encoded and multipart bodies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub form: Vec<SavedKeyValue>,
     #[serde(default)]
     pub body: String,
+    /// Checks evaluated against the response
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<Assertion>,
+    #[serde(default, skip_serializing_if = "AuthConfig::is_none")]
+    pub auth: AuthConfig,
+    /// Send without the folder's cookie jar and don't store the cookies received
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_cookie_jar: bool,
+    #[serde(default, skip_serializing_if = "RequestSettings::is_default")]
+    pub settings: RequestSettings,
+}
+
+impl SavedRequest {
+    pub fn new(name: impl Into<String>, method: &HttpMethod, url: impl Into<String>) -> Self {
+        Self {
+            version: SAVED_REQUEST_VERSION,
+            name: name.into(),
+            description: String::new(),
+            method: method.as_str().to_string(),
+            url: url.into(),
+            params: Vec::new(),
+            headers: Vec::new(),
+            body_mode: BodyMode::None,
+            form: Vec::new(),
+            body: String::new(),
+            assertions: Vec::new(),
+            auth: AuthConfig::None,
+            skip_cookie_jar: false,
+            settings: RequestSettings::default(),
+        }
+    }
+
+    /// Parse a saved request, upgrading older file versions
+    pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
+        let mut request: SavedRequest = serde_json::from_str(content)?;
+        request.migrate();
+        Ok(request)
+    }
+
+    /// Parse a saved request written as YAML, upgrading older file versions
+    pub fn from_yaml(content: &str) -> Result<Self, serde_yaml::Error> {
+        let mut request: SavedRequest = serde_yaml::from_str(content)?;
+        request.migrate();
+        Ok(request)
+    }
+
+    /// Bring a request read from an older file version up to the current format
+    pub fn migrate(&mut self) {
+        if self.version < 2 {
+            // Version 1 always sent the body as-is, with a JSON placeholder in the editor
+            if !self.body.is_empty() && self.body_mode == BodyMode::None {
+                self.body_mode = BodyMode::Json;
+            }
+        }
+        self.version = SAVED_REQUEST_VERSION;
+    }
+
+    pub fn http_method(&self) -> HttpMethod {
+        HttpMethod::parse(&self.method).unwrap_or(HttpMethod::Get)
+    }
+}
+
+/// Accept both the ordered list format and the version 1 `{"Header": "value"}` map
+fn deserialize_key_values<'de, D>(deserializer: D) -> Result<Vec<SavedKeyValue>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct KeyValuesVisitor;
+
+    impl<'de> Visitor<'de> for KeyValuesVisitor {
+        type Value = Vec<SavedKeyValue>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a list of key-value entries or a map")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut entries = Vec::new();
+            while let Some(entry) = seq.next_element()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = Vec::new();
+            while let Some((key, value)) = map.next_entry::<String, String>()? {
+                entries.push(SavedKeyValue::new(key, value));
+            }
+            Ok(entries)
+        }
+    }
+
+    deserializer.deserialize_any(KeyValuesVisitor)
+}
+
+/// Authentication schemes offered in the Auth tab
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthKind {
+    None,
+    Basic,
+    Bearer,
+    ApiKey,
+    Digest,
+    OAuth2,
+    AwsSigV4,
+}
+
+impl AuthKind {
+    pub const ALL: [AuthKind; 7] = [
+        AuthKind::None,
+        AuthKind::Basic,
+        AuthKind::Bearer,
+        AuthKind::ApiKey,
+        AuthKind::Digest,
+        AuthKind::OAuth2,
+        AuthKind::AwsSigV4,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuthKind::None => "No Auth",
+            AuthKind::Basic => "Basic Auth",
+            AuthKind::Bearer => "Bearer Token",
+            AuthKind::ApiKey => "API Key",
+            AuthKind::Digest => "Digest Auth",
+            AuthKind::OAuth2 => "OAuth 2.0",
+            AuthKind::AwsSigV4 => "AWS Signature v4",
+        }
+    }
+}
+
+/// Where an API key is sent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
+
+impl ApiKeyLocation {
+    pub const ALL: [ApiKeyLocation; 2] = [ApiKeyLocation::Header, ApiKeyLocation::Query];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApiKeyLocation::Header => "Header",
+            ApiKeyLocation::Query => "Query Param",
+        }
+    }
+}
+
+/// How an OAuth 2.0 access token is obtained
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OAuthGrant {
+    #[default]
+    ClientCredentials,
+    Password,
+    /// Authorization code with PKCE, redirected to a loopback listener
+    AuthorizationCode,
+}
+
+impl OAuthGrant {
+    pub const ALL: [OAuthGrant; 3] = [
+        OAuthGrant::ClientCredentials,
+        OAuthGrant::Password,
+        OAuthGrant::AuthorizationCode,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OAuthGrant::ClientCredentials => "Client Credentials",
+            OAuthGrant::Password => "Password",
+            OAuthGrant::AuthorizationCode => "Authorization Code (PKCE)",
+        }
+    }
+}
+
+/// Settings of the OAuth 2.0 auth type
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OAuth2Config {
+    #[serde(default)]
+    pub grant: OAuthGrant,
+    /// Authorization endpoint, only used by the authorization code grant
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub auth_url: String,
+    #[serde(default)]
+    pub token_url: String,
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub client_secret: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scope: String,
+    /// Resource owner credentials of the password grant
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub username: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password: String,
+    /// Loopback redirect of the authorization code grant; a free port is picked when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub redirect_uri: String,
+}
+
+/// Settings of the AWS Signature Version 4 auth type
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AwsSigV4Config {
+    #[serde(default)]
+    pub access_key: String,
+    #[serde(default)]
+    pub secret_key: String,
+    /// Session token of temporary credentials, sent as `X-Amz-Security-Token`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub session_token: String,
+    #[serde(default)]
+    pub region: String,
+    /// Signing name of the service, e.g. `execute-api` or `s3`
+    #[serde(default)]
+    pub service: String,
+    /// Sign `UNSIGNED-PAYLOAD` instead of hashing the body
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unsigned_payload: bool,
+}
+
+/// Credentials applied to a request when it is sent
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum AuthConfig {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    ApiKey {
+        key: String,
+        #[serde(default)]
+        value: String,
+        #[serde(default)]
+        location: ApiKeyLocation,
+    },
+    /// Answered after the server's 401 challenge
+    Digest {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    /// Access token fetched from the token endpoint and cached per environment
+    #[serde(rename = "oauth2")]
+    OAuth2(OAuth2Config),
+    /// Signed over the final request right before it is sent
+    #[serde(rename = "aws-sigv4")]
+    AwsSigV4(AwsSigV4Config),
+}
+
+impl AuthConfig {
+    pub fn kind(&self) -> AuthKind {
+        match self {
+            AuthConfig::None => AuthKind::None,
+            AuthConfig::Basic { .. } => AuthKind::Basic,
+            AuthConfig::Bearer { .. } => AuthKind::Bearer,
+            AuthConfig::ApiKey { .. } => AuthKind::ApiKey,
+            AuthConfig::Digest { .. } => AuthKind::Digest,
+            AuthConfig::OAuth2(_) => AuthKind::OAuth2,
+            AuthConfig::AwsSigV4(_) => AuthKind::AwsSigV4,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == AuthConfig::None
+    }
+}
+
+/// HTTP version requests are sent with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HttpVersion {
+    /// HTTP/2 when the server offers it during the TLS handshake, HTTP/1.1 otherwise
+    #[default]
+    Auto,
+    Http1,
+    /// HTTP/2 without upgrade negotiation, also over plain HTTP
+    Http2PriorKnowledge,
+}
+
+impl HttpVersion {
+    pub const ALL: [HttpVersion; 3] = [
+        HttpVersion::Auto,
+        HttpVersion::Http1,
+        HttpVersion::Http2PriorKnowledge,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HttpVersion::Auto => "Automatic",
+            HttpVersion::Http1 => "HTTP/1.1",
+            HttpVersion::Http2PriorKnowledge => "HTTP/2 (Prior Knowledge)",
+        }
+    }
+}
+
+/// Connection settings of a request; fields left unset fall back to the folder
+/// defaults, then to the built-in values
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_ms: Option<u64>,
+    /// Limit for the response to each request sent to arrive, and then for each wait
+    /// for more of its body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Redirects followed before giving up; 0 returns the redirect response itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_redirects: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<HttpVersion>,
+    /// Keep credentials when a redirect leads to another host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_authorization: Option<bool>,
+    /// Bodies kept in memory are cut off past this size; 0 for no limit. Bodies
+    /// written to a file with Send and Download are not limited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_download_mb: Option<u64>,
+}
+
+impl RequestSettings {
+    pub const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 10_000;
+    pub const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+    pub const DEFAULT_MAX_REDIRECTS: u32 = 10;
+    pub const DEFAULT_MAX_DOWNLOAD_MB: u64 = 100;
+
+    pub fn is_default(&self) -> bool {
+        *self == RequestSettings::default()
+    }
+
+    /// These settings with unset fields taken from `defaults`
+    pub fn or(&self, defaults: &RequestSettings) -> RequestSettings {
+        RequestSettings {
+            connect_timeout_ms: self.connect_timeout_ms.or(defaults.connect_timeout_ms),
+            timeout_ms: self.timeout_ms.or(defaults.timeout_ms),
+            max_redirects: self.max_redirects.or(defaults.max_redirects),
+            http_version: self.http_version.or(defaults.http_version),
+            keep_authorization: self.keep_authorization.or(defaults.keep_authorization),
+            max_download_mb: self.max_download_mb.or(defaults.max_download_mb),
+        }
+    }
+
+    /// `None` when set to 0, which means no limit
+    pub fn connect_timeout(&self) -> Option<std::time::Duration> {
+        let ms = self
+            .connect_timeout_ms
+            .unwrap_or(Self::DEFAULT_CONNECT_TIMEOUT_MS);
+        (ms > 0).then(|| std::time::Duration::from_millis(ms))
+    }
+
+    /// `None` when set to 0, which means no limit
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        let ms = self.timeout_ms.unwrap_or(Self::DEFAULT_TIMEOUT_MS);
+        (ms > 0).then(|| std::time::Duration::from_millis(ms))
+    }
+
+    pub fn max_redirects(&self) -> u32 {
+        self.max_redirects.unwrap_or(Self::DEFAULT_MAX_REDIRECTS)
+    }
+
+    pub fn http_version(&self) -> HttpVersion {
+        self.http_version.unwrap_or_default()
+    }
+
+    pub fn keep_authorization(&self) -> bool {
+        self.keep_authorization.unwrap_or(false)
+    }
+
+    /// Limit for bodies kept in memory; `None` when set to 0, which means no limit
+    pub fn max_download_bytes(&self) -> Option<u64> {
+        let mb = self
+            .max_download_mb
+            .unwrap_or(Self::DEFAULT_MAX_DOWNLOAD_MB);
+        (mb > 0).then(|| mb.saturating_mul(1024 * 1024))
+    }
+}
+
+/// File format of saved request files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl RequestFormat {
+    pub const ALL: [RequestFormat; 2] = [RequestFormat::Json, RequestFormat::Yaml];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RequestFormat::Json => "json",
+            RequestFormat::Yaml => "yaml",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RequestFormat::Json => "JSON",
+            RequestFormat::Yaml => "YAML",
+        }
+    }
+
+    /// Format of a request file, judged by its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|e| e.to_str())? {
+            "json" => Some(RequestFormat::Json),
+            "yaml" | "yml" => Some(RequestFormat::Yaml),
+            _ => None,
+        }
+    }
 }
 
 /// Sidebar file entry
@@ -81,8 +642,99 @@ pub struct FileEntry {
     pub method: Option<HttpMethod>,
 }
 
+/// Node of the sidebar collection tree
+#[derive(Clone, Debug)]
+pub enum TreeNode {
+    Folder {
+        name: String,
+        path: PathBuf,
+        children: Vec<TreeNode>,
+    },
+    Request(FileEntry),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SidebarTab {
     Files,
     Git,
+    History,
+    Cookies,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BodyMode, HttpMethod, RequestSettings, SavedKeyValue, SavedRequest, SAVED_REQUEST_VERSION,
+    };
+
+    #[test]
+    fn max_download_bytes_saturates() {
+        let settings = |mb| RequestSettings {
+            max_download_mb: mb,
+            ..Default::default()
+        };
+        assert_eq!(settings(None).max_download_bytes(), Some(100 * 1024 * 1024));
+        assert_eq!(settings(Some(0)).max_download_bytes(), None);
+        assert_eq!(
+            settings(Some(u64::MAX)).max_download_bytes(),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn migrates_version_1_files() {
+        let request = SavedRequest::from_json(
+            r#"{
+                "name": "Create user",
+                "method": "POST",
+                "url": "https://example.com/users",
+                "headers": {"Content-Type": "application/json", "X-Trace": "1"},
+                "body": "{\"name\": \"ada\"}"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(request.version, SAVED_REQUEST_VERSION);
+        assert_eq!(
+            request.headers,
+            vec![
+                SavedKeyValue::new("Content-Type", "application/json"),
+                SavedKeyValue::new("X-Trace", "1"),
+            ]
+        );
+        assert_eq!(request.body_mode, BodyMode::Json);
+
+        let request = SavedRequest::from_yaml(
+            "name: List users\nmethod: GET\nurl: https://example.com/users\nheaders:\n  Accept: text/plain\n",
+        )
+        .unwrap();
+        assert_eq!(
+            request.headers,
+            vec![SavedKeyValue::new("Accept", "text/plain")]
+        );
+        assert_eq!(request.body_mode, BodyMode::None);
+    }
+
+    #[test]
+    fn keeps_current_files_as_written() {
+        let mut request = SavedRequest::new("Upload", &HttpMethod::Put, "https://example.com");
+        request.body = "plain".to_string();
+        request.headers = vec![
+            SavedKeyValue {
+                enabled: false,
+                ..SavedKeyValue::new("B", "2")
+            },
+            SavedKeyValue::new("A", "1"),
+        ];
+        let json = serde_json::to_string(&request).unwrap();
+        let loaded = SavedRequest::from_json(&json).unwrap();
+        // A body without a mode is only upgraded in version 1 files
+        assert_eq!(loaded.body_mode, BodyMode::None);
+        assert_eq!(loaded.headers, request.headers);
+
+        let yaml = serde_yaml::to_string(&request).unwrap();
+        assert_eq!(
+            SavedRequest::from_yaml(&yaml).unwrap().headers,
+            request.headers
+        );
+    }
 }
@@ -0,0 +1,65 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+/// Watches a request folder for files created, changed, removed or renamed outside
+/// the app
+pub struct FolderWatcher {
+    // Events stop as soon as the watcher is dropped
+    _watcher: RecommendedWatcher,
+    events: UnboundedReceiver<Vec<PathBuf>>,
+}
+
+impl FolderWatcher {
+    pub fn new(folder: &Path) -> notify::Result<Self> {
+        let (sender, events) = mpsc::unbounded_channel();
+        let root = folder.to_path_buf();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            let paths: Vec<PathBuf> = event
+                .paths
+                .into_iter()
+                .filter(|path| is_relevant(&root, path))
+                .collect();
+            if !paths.is_empty() {
+                let _ = sender.send(paths);
+            }
+        })?;
+        watcher.watch(folder, RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Wait for the next change, returning the paths it touched
+    pub async fn next(&mut self) -> Option<Vec<PathBuf>> {
+        self.events.recv().await
+    }
+
+    /// Paths of changes that arrived since the last call, without waiting
+    pub fn drain(&mut self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        while let Ok(more) = self.events.try_recv() {
+            paths.extend(more);
+        }
+        paths
+    }
+}
+
+/// Whether a change under `folder` can affect what the app shows; git internals are
+/// ignored
+fn is_relevant(folder: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(folder) else {
+        return false;
+    };
+    relative
+        .components()
+        .next()
+        .is_none_or(|first| first.as_os_str() != ".git")
+}